@@ -8,16 +8,26 @@ import {
     Transaction,
     ParsedAccountData,
     TransactionInstruction,
+    AccountMeta,
     sendAndConfirmTransaction
 } from '@solana/web3.js';
-import { Token, TOKEN_PROGRAM_ID, AccountLayout, MintLayout, ASSOCIATED_TOKEN_PROGRAM_ID, u64 } from "@solana/spl-token";
+import { Token, TOKEN_PROGRAM_ID, NATIVE_MINT, AccountLayout, MintLayout, ASSOCIATED_TOKEN_PROGRAM_ID, u64 } from "@solana/spl-token";
+import { keccak_256 } from 'js-sha3';
 
 import * as fs from 'fs';
-import { OpenAuction } from './types';
+import {
+    OpenAuction,
+    BidRecord,
+    ReferralRecord,
+    SealedAuction,
+    DutchAuction,
+    HouseConfig,
+    ProjectConfig,
+} from './types';
 
 const PROGRAM_ID = "5JNnRQLNjzW21tJTPT6PaLnqiYN3d9jvtcCvHCfkoXuU";
-const TREASURY_WALLET = new PublicKey("32NL69SFk8GLPFZfKQwsuexcXHd7rqAQn1mrasF1ksVj");
-const DECIMALS = 100;
+const METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
 
 anchor.setProvider(anchor.Provider.local(web3.clusterApiUrl('devnet')));
 const solConnection = anchor.getProvider().connection;
@@ -39,41 +49,224 @@ console.log('ProgramId: ', program.programId.toBase58());
 
 
 const main = async () => {
-    // let address = await getAuctionKey(new PublicKey('GF4XmpVKCf9aozU5igmr9sKNzDBkjvmiWujx8uC7Bnp4'), 5);
+    // let address = await getAuctionKey(new PublicKey('GF4XmpVKCf9aozU5igmr9sKNzDBkjvmiWujx8uC7Bnp4'));
     // console.log(address.toBase58());
     // let state = await getOpenAuctionState(address);
     // console.log(state.endTime.toNumber());
     // console.log(state.title.toString());
-    await CreateOpenAuction(payer.publicKey, new PublicKey('Fp7WgqJpzBcRyMJP1i9aoSiwhAr8ZtzVSorz4YbZusAL'), new PublicKey('DjHSPVtttbj25BTNmPVvjG9Tnm5F4Wm9arGtrmaYKM6N'), 'My Ff Auction', 10, 1, 5, 0, 1650950713, 1, 1);
+    await CreateOpenAuction(payer.publicKey, new PublicKey('Fp7WgqJpzBcRyMJP1i9aoSiwhAr8ZtzVSorz4YbZusAL'), new PublicKey('DjHSPVtttbj25BTNmPVvjG9Tnm5F4Wm9arGtrmaYKM6N'), {
+        ...DEFAULT_OPEN_AUCTION_PARAMS,
+        title: 'My Ff Auction',
+        floor: 10,
+        increment: 1,
+        bidderCap: 5,
+        startTime: 0,
+        endTime: 1650950713,
+        tokenAmount: 1,
+        projectId: 1,
+    });
     // await CancelOpenAuction(payer.publicKey, address);
     // await MakeOpenBid(payer.publicKey, address, 11);
     // await ReclaimItemOpen(payer.publicKey, address);
     // console.log(state.endTime.toNumber());
 }
 
-export const CreateOpenAuction = async (
-    owner: PublicKey,
-    nft_mint: PublicKey,
-    token_mint: PublicKey,
-    auctionTitle: String,
+export const InitializeHouseConfig = async (
+    authority: PublicKey,
+    admins: PublicKey[],
+    treasury: PublicKey,
+    feeBps: number,
+    bidReferralBps: number,
+    creationReferralBps: number,
+) => {
+    const [config, bump] = await getHouseConfigKey();
+    const [programData] = await PublicKey.findProgramAddress(
+        [program.programId.toBuffer()],
+        BPF_LOADER_UPGRADEABLE_ID
+    );
+
+    const tx = await program.rpc.initializeHouseConfig(
+        bump,
+        admins,
+        treasury,
+        feeBps,
+        bidReferralBps,
+        creationReferralBps, {
+        accounts: {
+            config,
+            authority,
+            programData,
+            systemProgram: SystemProgram.programId,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const UpdateHouseConfig = async (
+    authority: PublicKey,
+    newAuthority: PublicKey,
+    admins: PublicKey[],
+    treasury: PublicKey,
+    feeBps: number,
+    bidReferralBps: number,
+    creationReferralBps: number,
+) => {
+    const [config] = await getHouseConfigKey();
+
+    const tx = await program.rpc.updateHouseConfig(
+        newAuthority,
+        admins,
+        treasury,
+        feeBps,
+        bidReferralBps,
+        creationReferralBps, {
+        accounts: {
+            config,
+            authority,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// Signed by the house config authority.
+export const InitializeProjectConfig = async (
+    houseAuthority: PublicKey,
+    projectId: number,
+    authority: PublicKey,
+    admins: PublicKey[],
+    treasury: PublicKey,
+    feeAmount: number,
+    tokenMints: PublicKey[],
+    crankReward: number,
+    feeBps: number,
+) => {
+    const [config] = await getHouseConfigKey();
+    const [projectConfig, bump] = await getProjectConfigKey(projectId);
+
+    const tx = await program.rpc.initializeProjectConfig(
+        bump,
+        projectId,
+        authority,
+        admins,
+        treasury,
+        new anchor.BN(feeAmount),
+        tokenMints,
+        new anchor.BN(crankReward),
+        feeBps, {
+        accounts: {
+            config,
+            projectConfig,
+            authority: houseAuthority,
+            systemProgram: SystemProgram.programId,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const UpdateProjectConfig = async (
+    authority: PublicKey,
+    projectId: number,
+    newAuthority: PublicKey,
+    admins: PublicKey[],
+    treasury: PublicKey,
+    feeAmount: number,
+    tokenMints: PublicKey[],
+    crankReward: number,
+    feeBps: number,
+) => {
+    const [projectConfig] = await getProjectConfigKey(projectId);
+
+    const tx = await program.rpc.updateProjectConfig(
+        newAuthority,
+        admins,
+        treasury,
+        new anchor.BN(feeAmount),
+        tokenMints,
+        new anchor.BN(crankReward),
+        feeBps, {
+        accounts: {
+            projectConfig,
+            authority,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// Arguments of createOpenAuction. Prices are in whole bid tokens.
+export interface CreateOpenAuctionParams {
+    title: string,
     floor: number,
     increment: number,
-    biddercap: number,
     startTime: number,
     endTime: number,
-    amount: number,
-    project_id: number,
+    bidderCap: number,
+    tokenAmount: number,
+    projectId: number,
+    extensionWindow: number,
+    extensionAmount: number,
+    maxEndTime: number,
+    buyNowPrice: number,
+    reserveHash: number[],
+    reserveRevealWindow: number,
+    retractCutoff: number,
+    leaderCanRetract: boolean,
+    maxBidRevealWindow: number,
+    allowlistRoot: number[],
+    gatingCollection: PublicKey,
+    referrer: PublicKey,
+}
+
+export const DEFAULT_OPEN_AUCTION_PARAMS: CreateOpenAuctionParams = {
+    title: '',
+    floor: 0,
+    increment: 0,
+    startTime: 0,
+    endTime: 0,
+    bidderCap: 0,
+    tokenAmount: 1,
+    projectId: 0,
+    extensionWindow: 0,
+    extensionAmount: 0,
+    maxEndTime: 0,
+    buyNowPrice: 0,
+    reserveHash: new Array(32).fill(0),
+    reserveRevealWindow: 0,
+    retractCutoff: 0,
+    leaderCanRetract: false,
+    maxBidRevealWindow: 0,
+    allowlistRoot: new Array(32).fill(0),
+    gatingCollection: PublicKey.default,
+    referrer: PublicKey.default,
+};
+
+export const CreateOpenAuction = async (
+    owner: PublicKey,
+    nft_mint: PublicKey,
+    token_mint: PublicKey,
+    params: CreateOpenAuctionParams,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
 ) => {
 
-    const [auctionAddress, bump] = await PublicKey.findProgramAddress(
-        [Buffer.from("open auction"), owner.toBytes(), Buffer.from(auctionTitle.slice(0, 32))],
-        program.programId
-    );
+    const [auctionAddress, bump] = await getOpenAuctionKey(owner, params.title);
+    const [config] = await getHouseConfigKey();
+    const [projectConfig] = await getProjectConfigKey(params.projectId);
 
-    let auctionAta = await getAssociatedTokenAccount(auctionAddress, nft_mint);
-    let ownerAta = await getAssociatedTokenAccount(owner, nft_mint);
+    let auctionAta = await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram);
+    let ownerAta = await getAssociatedTokenAccount(owner, nft_mint, tokenProgram);
 
-    let DECIMALS = await getDecimals(owner, token_mint);
+    let DECIMALS = await getDecimals(token_mint);
 
     console.log(auctionAddress.toBase58(), "auctionAddress");
     console.log(auctionAta.toBase58(), "auctionAta");
@@ -81,28 +274,40 @@ export const CreateOpenAuction = async (
     console.log(ownerAta.toBase58(), "ownerAta");
     console.log(nft_mint.toBase58(), "nft_mint");
     console.log(token_mint.toBase58(), "token_mint");
-    console.log(TOKEN_PROGRAM_ID.toBase58(), "TOKEN_PROGRAM_ID");
-    console.log(ASSOCIATED_TOKEN_PROGRAM_ID.toBase58(), "ASSOCIATED_TOKEN_PROGRAM_ID");
-    console.log(SYSVAR_RENT_PUBKEY.toBase58(), "SYSVAR_RENT_PUBKEY");
 
 
-    const tx = await program.rpc.createOpenAuction(new anchor.BN(bump),
-        auctionTitle,
-        new anchor.BN(floor * DECIMALS),
-        new anchor.BN(increment * DECIMALS),
-        new anchor.BN(startTime),
-        new anchor.BN(endTime),
-        new anchor.BN(biddercap),
-        new anchor.BN(amount),
-        new anchor.BN(project_id), {
+    const tx = await program.rpc.createOpenAuction(
+        bump,
+        params.title,
+        new anchor.BN(params.floor * DECIMALS),
+        new anchor.BN(params.increment * DECIMALS),
+        new anchor.BN(params.startTime),
+        new anchor.BN(params.endTime),
+        new anchor.BN(params.bidderCap),
+        new anchor.BN(params.tokenAmount),
+        params.projectId,
+        new anchor.BN(params.extensionWindow),
+        new anchor.BN(params.extensionAmount),
+        new anchor.BN(params.maxEndTime),
+        new anchor.BN(params.buyNowPrice * DECIMALS),
+        params.reserveHash,
+        new anchor.BN(params.reserveRevealWindow),
+        new anchor.BN(params.retractCutoff),
+        params.leaderCanRetract,
+        new anchor.BN(params.maxBidRevealWindow),
+        params.allowlistRoot,
+        params.gatingCollection,
+        params.referrer, {
         accounts: {
             auction: auctionAddress,
+            config,
+            projectConfig,
             auctionAta: auctionAta,
             owner,
             ownerAta,
             mint: nft_mint,
             tokenMint: token_mint,
-            tokenProgram: TOKEN_PROGRAM_ID,
+            tokenProgram,
             ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             rentSysvar: SYSVAR_RENT_PUBKEY,
@@ -132,31 +337,104 @@ export const CancelOpenAuction = async (
     console.log("txHash =", tx);
 }
 
+export const UpdateAllowlistOpen = async (
+    admin: PublicKey,
+    auctionAddress: PublicKey,
+    allowlistRoot: number[],
+) => {
+    let auctionState = await getOpenAuctionState(auctionAddress);
+    const [config] = await getHouseConfigKey();
+    const [projectConfig] = await getProjectConfigKey(auctionState.projectId.toNumber());
+
+    const tx = await program.rpc.updateAllowlistOpen(
+        allowlistRoot, {
+        accounts: {
+            auction: auctionAddress,
+            config,
+            projectConfig,
+            admin,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// reserve is in whole bid tokens, committed as computeReserveHash of it in
+// base units.
+export const RevealReserveOpen = async (
+    owner: PublicKey,
+    auctionAddress: PublicKey,
+    reserve: number,
+    nonce: number,
+) => {
+    let auctionState = await getOpenAuctionState(auctionAddress);
+    let DECIMALS = await getDecimals(auctionState.tokenMint);
+
+    const tx = await program.rpc.revealReserveOpen(
+        new anchor.BN(reserve * DECIMALS),
+        new anchor.BN(nonce), {
+        accounts: {
+            auction: auctionAddress,
+            owner,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// maxHash is computeMaxBidHash of the bidder's maximum in base units when the
+// auction hides maximums. collectionNft is the bidder's token account of an NFT in the
+// auction's gating collection and that NFT's mint, when the auction is gated.
 export const MakeOpenBid = async (
     bidder: PublicKey,
     auctionAddress: PublicKey,
-    amount: number
+    amount: number,
+    proof: number[][] = [],
+    referrer: PublicKey = PublicKey.default,
+    maxHash: number[] = new Array(32).fill(0),
+    collectionNft: [PublicKey, PublicKey] | null = null,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
 ) => {
     let auctionState = await getOpenAuctionState(auctionAddress);
     let token_mint = auctionState.tokenMint;
-    let DECIMALS = await getDecimals(bidder, token_mint);
+    let DECIMALS = await getDecimals(token_mint);
+
+    const [bidRecord, bump] = await getBidRecordKey(auctionAddress, bidder);
+    let auctionAta = await getBidAccount(auctionAddress, token_mint, tokenProgram);
+    let bidderAta = await getBidAccount(bidder, token_mint, tokenProgram);
 
-    let auctionAta = await getAssociatedTokenAccount(auctionAddress, token_mint);
-    let bidderAta = await getAssociatedTokenAccount(bidder, token_mint);
+    let remainingAccounts: AccountMeta[] = [];
+    if (collectionNft) {
+        const [nftAccount, nftMint] = collectionNft;
+        remainingAccounts.push(
+            { pubkey: nftAccount, isSigner: false, isWritable: false },
+            { pubkey: await getMetadataKey(nftMint), isSigner: false, isWritable: false },
+        );
+    }
 
     const tx = await program.rpc.makeOpenBid(
-        new anchor.BN(amount * DECIMALS), {
+        bump,
+        new anchor.BN(amount * DECIMALS),
+        proof,
+        referrer,
+        maxHash, {
         accounts: {
             auction: auctionAddress,
+            bidRecord,
             auctionAta: auctionAta,
             bidder,
             bidderAta,
             tokenMint: token_mint,
             systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
+            tokenProgram,
             ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             rentSysvar: SYSVAR_RENT_PUBKEY,
         },
+        remainingAccounts,
         signers: [],
     });
     await solConnection.confirmTransaction(tx, "confirmed");
@@ -167,22 +445,203 @@ export const MakeOpenBid = async (
 export const ReclaimOpenBid = async (
     bidder: PublicKey,
     auctionAddress: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
 ) => {
     let auctionState = await getOpenAuctionState(auctionAddress);
     let token_mint = auctionState.tokenMint;
-    let auctionAta = await getAssociatedTokenAccount(auctionAddress, token_mint);
-    let bidderAta = await getAssociatedTokenAccount(bidder, token_mint);
+    const [projectConfig] = await getProjectConfigKey(auctionState.projectId.toNumber());
+    let projectState = await getProjectConfigState(auctionState.projectId.toNumber());
+    const [bidRecord] = await getBidRecordKey(auctionAddress, bidder);
+    let auctionAta = await getBidAccount(auctionAddress, token_mint, tokenProgram);
+    let bidderAta = await getBidAccount(bidder, token_mint, tokenProgram);
 
     const tx = await program.rpc.reclaimOpenBid({
         accounts: {
             auction: auctionAddress,
+            projectConfig,
+            bidRecord,
             auctionAta: auctionAta,
             bidder,
             bidderAta,
-            treasuryWallet: TREASURY_WALLET,
+            treasuryWallet: projectState.treasury,
+            tokenMint: token_mint,
+            tokenProgram,
+            systemProgram: SystemProgram.programId,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// otherBidders are the other bidders with an outstanding bid, only needed when
+// the highest bidder retracts.
+export const RetractOpenBid = async (
+    bidder: PublicKey,
+    auctionAddress: PublicKey,
+    otherBidders: PublicKey[] = [],
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getOpenAuctionState(auctionAddress);
+    let token_mint = auctionState.tokenMint;
+    const [bidRecord] = await getBidRecordKey(auctionAddress, bidder);
+    let auctionAta = await getBidAccount(auctionAddress, token_mint, tokenProgram);
+    let bidderAta = await getBidAccount(bidder, token_mint, tokenProgram);
+
+    let remainingAccounts: AccountMeta[] = [];
+    for (const other of otherBidders) {
+        const [otherRecord] = await getBidRecordKey(auctionAddress, other);
+        remainingAccounts.push({ pubkey: otherRecord, isSigner: false, isWritable: false });
+    }
+
+    const tx = await program.rpc.retractOpenBid({
+        accounts: {
+            auction: auctionAddress,
+            bidRecord,
+            auctionAta,
+            bidder,
+            bidderAta,
+            tokenMint: token_mint,
+            tokenProgram,
+        },
+        remainingAccounts,
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const InitializeReferral = async (
+    referrer: PublicKey,
+    token_mint: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    const [referral, bump] = await getReferralKey(referrer, token_mint);
+    let referralAta = await getBidAccount(referral, token_mint, tokenProgram);
+
+    const tx = await program.rpc.initializeReferral(
+        bump, {
+        accounts: {
+            referral,
+            referralAta,
+            referrer,
             tokenMint: token_mint,
-            tokenProgram: TOKEN_PROGRAM_ID,
+            tokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const ClaimReferralRewards = async (
+    referrer: PublicKey,
+    token_mint: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    const [referral] = await getReferralKey(referrer, token_mint);
+    let referralAta = await getBidAccount(referral, token_mint, tokenProgram);
+    let referrerAta = await getBidAccount(referrer, token_mint, tokenProgram);
+
+    const tx = await program.rpc.claimReferralRewards({
+        accounts: {
+            referral,
+            referralAta,
+            referrer,
+            referrerAta,
+            tokenMint: token_mint,
+            tokenProgram,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// maxBid is in whole bid tokens, committed as computeMaxBidHash of it in base
+// units.
+export const RevealOpenBid = async (
+    bidder: PublicKey,
+    auctionAddress: PublicKey,
+    maxBid: number,
+    nonce: number,
+) => {
+    let auctionState = await getOpenAuctionState(auctionAddress);
+    let DECIMALS = await getDecimals(auctionState.tokenMint);
+    const [bidRecord] = await getBidRecordKey(auctionAddress, bidder);
+
+    const tx = await program.rpc.revealOpenBid(
+        new anchor.BN(maxBid * DECIMALS),
+        new anchor.BN(nonce), {
+        accounts: {
+            auction: auctionAddress,
+            bidRecord,
+            bidder,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// Refunds the bids of bidders, paying the cranker.
+export const RefundOpenBids = async (
+    cranker: PublicKey,
+    auctionAddress: PublicKey,
+    bidders: PublicKey[],
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getOpenAuctionState(auctionAddress);
+    let token_mint = auctionState.tokenMint;
+    const [projectConfig] = await getProjectConfigKey(auctionState.projectId.toNumber());
+    let auctionAta = await getBidAccount(auctionAddress, token_mint, tokenProgram);
+
+    let remainingAccounts: AccountMeta[] = [];
+    for (const bidder of bidders) {
+        const [bidRecord] = await getBidRecordKey(auctionAddress, bidder);
+        remainingAccounts.push(
+            { pubkey: bidRecord, isSigner: false, isWritable: true },
+            { pubkey: await getBidAccount(bidder, token_mint, tokenProgram), isSigner: false, isWritable: true },
+        );
+    }
+
+    const tx = await program.rpc.refundOpenBids({
+        accounts: {
+            auction: auctionAddress,
+            projectConfig,
+            auctionAta,
+            cranker,
+            tokenMint: token_mint,
+            tokenProgram,
+        },
+        remainingAccounts,
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// Closes the spent bid record of bidder, returning its rent to them.
+export const CloseOpenBid = async (
+    bidder: PublicKey,
+    auctionAddress: PublicKey,
+) => {
+    const [bidRecord] = await getBidRecordKey(auctionAddress, bidder);
+
+    const tx = await program.rpc.closeOpenBid({
+        accounts: {
+            auction: auctionAddress,
+            bidRecord,
+            bidder,
         },
         signers: [],
     });
@@ -194,11 +653,12 @@ export const ReclaimOpenBid = async (
 export const WithdrawItemOpen = async (
     winner: PublicKey,
     auctionAddress: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
 ) => {
     let auctionState = await getOpenAuctionState(auctionAddress);
     let nft_mint = auctionState.mint;
-    let auctionAta = await getAssociatedTokenAccount(auctionAddress, nft_mint);
-    let winnerAta = await getAssociatedTokenAccount(winner, nft_mint);
+    let auctionAta = await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram);
+    let winnerAta = await getAssociatedTokenAccount(winner, nft_mint, tokenProgram);
 
     const tx = await program.rpc.withdrawItemOpen({
         accounts: {
@@ -207,7 +667,7 @@ export const WithdrawItemOpen = async (
             highestBidder: winner,
             highestBidderAta: winnerAta,
             mint: nft_mint,
-            tokenProgram: TOKEN_PROGRAM_ID,
+            tokenProgram,
             ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             rentSysvar: SYSVAR_RENT_PUBKEY,
@@ -219,24 +679,135 @@ export const WithdrawItemOpen = async (
     console.log("txHash =", tx);
 }
 
+// creators are the verified creators of the item in metadata order.
 export const WithdrawWinningBidOpen = async (
     owner: PublicKey,
     auctionAddress: PublicKey,
+    creators: PublicKey[] = [],
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
 ) => {
     let auctionState = await getOpenAuctionState(auctionAddress);
     let token_mint = auctionState.tokenMint;
-    let auctionAta = await getAssociatedTokenAccount(auctionAddress, token_mint);
-    let ownerAta = await getAssociatedTokenAccount(owner, token_mint);
+    const [winningBidRecord] = await getBidRecordKey(auctionAddress, auctionState.highestBidder);
+    let winningBid = await getBidRecordState(winningBidRecord);
+    let payout = await getPayoutAccounts(auctionState, winningBid.referrer, tokenProgram);
+    let auctionAta = await getBidAccount(auctionAddress, token_mint, tokenProgram);
+    let ownerAta = await getBidAccount(owner, token_mint, tokenProgram);
 
     const tx = await program.rpc.withdrawWinningBidOpen({
         accounts: {
             auction: auctionAddress,
+            winningBidRecord,
+            ...payout,
             owner,
             auctionAta,
             ownerAta,
             tokenMint: token_mint,
-            tokenProgram: TOKEN_PROGRAM_ID,
+            tokenProgram,
+        },
+        remainingAccounts: await getRoyaltyAccounts(auctionState.mint, creators, token_mint, tokenProgram),
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// tokenProgram and bidTokenProgram are the token programs of the item and the
+// bid token.
+export const SettleOpenAuction = async (
+    payer: PublicKey,
+    auctionAddress: PublicKey,
+    creators: PublicKey[] = [],
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    bidTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getOpenAuctionState(auctionAddress);
+    let nft_mint = auctionState.mint;
+    let token_mint = auctionState.tokenMint;
+    let winner = auctionState.highestBidder;
+    const [winningBidRecord] = await getBidRecordKey(auctionAddress, winner);
+    let winningBid = await getBidRecordState(winningBidRecord);
+    let payout = await getPayoutAccounts(auctionState, winningBid.referrer, bidTokenProgram);
+
+    const tx = await program.rpc.settleOpenAuction({
+        accounts: {
+            auction: auctionAddress,
+            winningBidRecord,
+            ...payout,
+            auctionAta: await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram),
+            auctionTokenAta: await getBidAccount(auctionAddress, token_mint, bidTokenProgram),
+            payer,
+            highestBidder: winner,
+            highestBidderAta: await getAssociatedTokenAccount(winner, nft_mint, tokenProgram),
+            highestBidderTokenAta: await getBidAccount(winner, token_mint, bidTokenProgram),
+            owner: auctionState.owner,
+            ownerAta: await getBidAccount(auctionState.owner, token_mint, bidTokenProgram),
+            mint: nft_mint,
+            tokenMint: token_mint,
+            tokenProgram,
+            bidTokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
+        },
+        remainingAccounts: await getRoyaltyAccounts(nft_mint, creators, token_mint, bidTokenProgram),
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// The buy now price is paid out like a winning bid, see SettleOpenAuction.
+// proof, referrer and collectionNft are as for MakeOpenBid.
+export const BuyNowOpen = async (
+    buyer: PublicKey,
+    auctionAddress: PublicKey,
+    proof: number[][] = [],
+    referrer: PublicKey = PublicKey.default,
+    collectionNft: [PublicKey, PublicKey] | null = null,
+    creators: PublicKey[] = [],
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    bidTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getOpenAuctionState(auctionAddress);
+    let nft_mint = auctionState.mint;
+    let token_mint = auctionState.tokenMint;
+    let payout = await getPayoutAccounts(auctionState, referrer, bidTokenProgram);
+
+    let remainingAccounts: AccountMeta[] = [];
+    if (collectionNft) {
+        const [nftAccount, nftMint] = collectionNft;
+        remainingAccounts.push(
+            { pubkey: nftAccount, isSigner: false, isWritable: false },
+            { pubkey: await getMetadataKey(nftMint), isSigner: false, isWritable: false },
+        );
+    }
+    remainingAccounts.push(...await getRoyaltyAccounts(nft_mint, creators, token_mint, bidTokenProgram));
+
+    const tx = await program.rpc.buyNowOpen(
+        proof,
+        referrer, {
+        accounts: {
+            auction: auctionAddress,
+            ...payout,
+            auctionAta: await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram),
+            auctionTokenAta: await getBidAccount(auctionAddress, token_mint, bidTokenProgram),
+            buyer,
+            buyerAta: await getAssociatedTokenAccount(buyer, nft_mint, tokenProgram),
+            buyerTokenAta: await getBidAccount(buyer, token_mint, bidTokenProgram),
+            owner: auctionState.owner,
+            ownerAta: await getBidAccount(auctionState.owner, token_mint, bidTokenProgram),
+            mint: nft_mint,
+            tokenMint: token_mint,
+            tokenProgram,
+            bidTokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
         },
+        remainingAccounts,
         signers: [],
     });
     await solConnection.confirmTransaction(tx, "confirmed");
@@ -247,11 +818,12 @@ export const WithdrawWinningBidOpen = async (
 export const ReclaimItemOpen = async (
     owner: PublicKey,
     auctionAddress: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
 ) => {
     let auctionState = await getOpenAuctionState(auctionAddress);
     let nft_mint = auctionState.mint;
-    let auctionAta = await getAssociatedTokenAccount(auctionAddress, nft_mint);
-    let ownerAta = await getAssociatedTokenAccount(owner, nft_mint);
+    let auctionAta = await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram);
+    let ownerAta = await getAssociatedTokenAccount(owner, nft_mint, tokenProgram);
 
     console.log(owner.toBase58());
     console.log(ownerAta.toBase58());
@@ -266,7 +838,364 @@ export const ReclaimItemOpen = async (
             owner,
             ownerAta,
             mint: nft_mint,
-            tokenProgram: TOKEN_PROGRAM_ID,
+            tokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const CloseOpenAuction = async (
+    owner: PublicKey,
+    auctionAddress: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    bidTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getOpenAuctionState(auctionAddress);
+    let nft_mint = auctionState.mint;
+    let token_mint = auctionState.tokenMint;
+
+    const tx = await program.rpc.closeOpenAuction({
+        accounts: {
+            auction: auctionAddress,
+            auctionAta: await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram),
+            auctionTokenAta: await getBidAccount(auctionAddress, token_mint, bidTokenProgram),
+            owner,
+            mint: nft_mint,
+            tokenMint: token_mint,
+            tokenProgram,
+            bidTokenProgram,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// Sealed auctions take bids in SOL, floor is in SOL.
+export const CreateSealedAuction = async (
+    owner: PublicKey,
+    nft_mint: PublicKey,
+    auctionTitle: string,
+    floor: number,
+    firstPrice: boolean,
+    startTime: number,
+    endTime: number,
+    revealPeriod: number,
+    biddercap: number,
+    amount: number,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    const [auctionAddress, bump] = await getSealedAuctionKey(owner, auctionTitle);
+    const [config] = await getHouseConfigKey();
+    let auctionAta = await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram);
+    let ownerAta = await getAssociatedTokenAccount(owner, nft_mint, tokenProgram);
+
+    const tx = await program.rpc.createSealedAuction(
+        bump,
+        auctionTitle,
+        new anchor.BN(floor * web3.LAMPORTS_PER_SOL),
+        firstPrice,
+        new anchor.BN(startTime),
+        new anchor.BN(endTime),
+        new anchor.BN(revealPeriod),
+        new anchor.BN(biddercap),
+        new anchor.BN(amount), {
+        accounts: {
+            auction: auctionAddress,
+            config,
+            auctionAta,
+            owner,
+            ownerAta,
+            mint: nft_mint,
+            tokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const CancelSealedAuction = async (
+    owner: PublicKey,
+    auctionAddress: PublicKey
+) => {
+    const tx = await program.rpc.cancelSealedAuction({
+        accounts: {
+            auction: auctionAddress,
+            owner,
+            systemProgram: SystemProgram.programId,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// sealedBid is computeSealedBidHash of the bid in lamports, amount the SOL
+// escrowed with it, which can be higher than the bid to hide it.
+export const MakeSealedBid = async (
+    bidder: PublicKey,
+    auctionAddress: PublicKey,
+    sealedBid: number[],
+    amount: number,
+) => {
+    const tx = await program.rpc.makeSealedBid(
+        sealedBid,
+        new anchor.BN(amount * web3.LAMPORTS_PER_SOL), {
+        accounts: {
+            auction: auctionAddress,
+            bidder,
+            systemProgram: SystemProgram.programId,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const RevealSealedBid = async (
+    bidder: PublicKey,
+    auctionAddress: PublicKey,
+    bid: number,
+    nonce: number,
+) => {
+    const tx = await program.rpc.revealSealedBid(
+        new anchor.BN(bid * web3.LAMPORTS_PER_SOL),
+        new anchor.BN(nonce), {
+        accounts: {
+            auction: auctionAddress,
+            bidder,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const ReclaimSealedBid = async (
+    bidder: PublicKey,
+    auctionAddress: PublicKey,
+) => {
+    const tx = await program.rpc.reclaimSealedBid({
+        accounts: {
+            auction: auctionAddress,
+            bidder,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const WithdrawItemSealed = async (
+    winner: PublicKey,
+    auctionAddress: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getSealedAuctionState(auctionAddress);
+    let nft_mint = auctionState.mint;
+
+    const tx = await program.rpc.withdrawItemSealed({
+        accounts: {
+            auction: auctionAddress,
+            auctionAta: await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram),
+            highestBidder: winner,
+            highestBidderAta: await getAssociatedTokenAccount(winner, nft_mint, tokenProgram),
+            mint: nft_mint,
+            tokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const WithdrawWinningBidSealed = async (
+    owner: PublicKey,
+    auctionAddress: PublicKey,
+) => {
+    const tx = await program.rpc.withdrawWinningBidSealed({
+        accounts: {
+            auction: auctionAddress,
+            owner,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const ReclaimItemSealed = async (
+    owner: PublicKey,
+    auctionAddress: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getSealedAuctionState(auctionAddress);
+    let nft_mint = auctionState.mint;
+
+    const tx = await program.rpc.reclaimItemSealed({
+        accounts: {
+            auction: auctionAddress,
+            auctionAta: await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram),
+            owner,
+            ownerAta: await getAssociatedTokenAccount(owner, nft_mint, tokenProgram),
+            mint: nft_mint,
+            tokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const CreateDutchAuction = async (
+    owner: PublicKey,
+    nft_mint: PublicKey,
+    token_mint: PublicKey,
+    auctionTitle: string,
+    startPrice: number,
+    floorPrice: number,
+    startTime: number,
+    endTime: number,
+    stepInterval: number,
+    amount: number,
+    project_id: number,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    const [auctionAddress, bump] = await getDutchAuctionKey(owner, auctionTitle);
+    const [config] = await getHouseConfigKey();
+    const [projectConfig] = await getProjectConfigKey(project_id);
+    let auctionAta = await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram);
+    let ownerAta = await getAssociatedTokenAccount(owner, nft_mint, tokenProgram);
+    let DECIMALS = await getDecimals(token_mint);
+
+    const tx = await program.rpc.createDutchAuction(
+        bump,
+        auctionTitle,
+        new anchor.BN(startPrice * DECIMALS),
+        new anchor.BN(floorPrice * DECIMALS),
+        new anchor.BN(startTime),
+        new anchor.BN(endTime),
+        new anchor.BN(stepInterval),
+        new anchor.BN(amount),
+        project_id, {
+        accounts: {
+            auction: auctionAddress,
+            config,
+            projectConfig,
+            auctionAta,
+            owner,
+            ownerAta,
+            mint: nft_mint,
+            tokenMint: token_mint,
+            tokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const CancelDutchAuction = async (
+    owner: PublicKey,
+    auctionAddress: PublicKey
+) => {
+    const tx = await program.rpc.cancelDutchAuction({
+        accounts: {
+            auction: auctionAddress,
+            owner,
+            systemProgram: SystemProgram.programId,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+// amount is the most the buyer will pay, in whole bid tokens.
+export const BuyDutch = async (
+    buyer: PublicKey,
+    auctionAddress: PublicKey,
+    amount: number,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    bidTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getDutchAuctionState(auctionAddress);
+    let nft_mint = auctionState.mint;
+    let token_mint = auctionState.tokenMint;
+    let DECIMALS = await getDecimals(token_mint);
+
+    const tx = await program.rpc.buyDutch(
+        new anchor.BN(amount * DECIMALS), {
+        accounts: {
+            auction: auctionAddress,
+            auctionAta: await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram),
+            auctionTokenAta: await getAssociatedTokenAccount(auctionAddress, token_mint, bidTokenProgram),
+            buyer,
+            buyerAta: await getAssociatedTokenAccount(buyer, nft_mint, tokenProgram),
+            buyerTokenAta: await getAssociatedTokenAccount(buyer, token_mint, bidTokenProgram),
+            owner: auctionState.owner,
+            ownerAta: await getAssociatedTokenAccount(auctionState.owner, token_mint, bidTokenProgram),
+            mint: nft_mint,
+            tokenMint: token_mint,
+            tokenProgram,
+            bidTokenProgram,
+            ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rentSysvar: SYSVAR_RENT_PUBKEY,
+        },
+        signers: [],
+    });
+    await solConnection.confirmTransaction(tx, "confirmed");
+
+    console.log("txHash =", tx);
+}
+
+export const ReclaimItemDutch = async (
+    owner: PublicKey,
+    auctionAddress: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+) => {
+    let auctionState = await getDutchAuctionState(auctionAddress);
+    let nft_mint = auctionState.mint;
+
+    const tx = await program.rpc.reclaimItemDutch({
+        accounts: {
+            auction: auctionAddress,
+            auctionAta: await getAssociatedTokenAccount(auctionAddress, nft_mint, tokenProgram),
+            owner,
+            ownerAta: await getAssociatedTokenAccount(owner, nft_mint, tokenProgram),
+            mint: nft_mint,
+            tokenProgram,
             ataProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
             rentSysvar: SYSVAR_RENT_PUBKEY,
@@ -281,33 +1210,84 @@ export const ReclaimItemOpen = async (
 
 export const getAuctionKey = async (
     nft_mint: PublicKey,
-    bidderCap: number
 ): Promise<PublicKey | null> => {
-    let poolAccounts = await solConnection.getParsedProgramAccounts(
-        program.programId,
+    // the item mint follows the 8 byte discriminator and the owner
+    let poolAccounts = await program.account.openAuction.all([
         {
-            filters: [
-                {
-                    dataSize: 400 + 8 + 40 * bidderCap
-                },
-                {
-                    memcmp: {
-                        "offset": 40,
-                        "bytes": nft_mint.toBase58()
-                    }
-                }
-            ]
+            memcmp: {
+                "offset": 40,
+                "bytes": nft_mint.toBase58()
+            }
         }
-    );
+    ]);
 
     if (poolAccounts.length !== 0) {
-        let rentalKey = poolAccounts[0].pubkey;
+        let rentalKey = poolAccounts[0].publicKey;
         return rentalKey;
     } else {
         return null;
     }
 }
 
+export const getHouseConfigKey = async (): Promise<[PublicKey, number]> => {
+    return await PublicKey.findProgramAddress(
+        [Buffer.from("house config")],
+        program.programId
+    );
+}
+
+export const getProjectConfigKey = async (project_id: number): Promise<[PublicKey, number]> => {
+    let projectId = Buffer.alloc(2);
+    projectId.writeUInt16LE(project_id);
+    return await PublicKey.findProgramAddress(
+        [Buffer.from("project config"), projectId],
+        program.programId
+    );
+}
+
+// Titles longer than 32 bytes are seeded by their first 32 bytes.
+export const getOpenAuctionKey = async (owner: PublicKey, title: string): Promise<[PublicKey, number]> => {
+    return await PublicKey.findProgramAddress(
+        [Buffer.from("open auction"), owner.toBytes(), Buffer.from(title).slice(0, 32)],
+        program.programId
+    );
+}
+
+export const getSealedAuctionKey = async (owner: PublicKey, title: string): Promise<[PublicKey, number]> => {
+    return await PublicKey.findProgramAddress(
+        [Buffer.from("sealed auction"), owner.toBytes(), Buffer.from(title).slice(0, 32)],
+        program.programId
+    );
+}
+
+export const getDutchAuctionKey = async (owner: PublicKey, title: string): Promise<[PublicKey, number]> => {
+    return await PublicKey.findProgramAddress(
+        [Buffer.from("dutch auction"), owner.toBytes(), Buffer.from(title).slice(0, 32)],
+        program.programId
+    );
+}
+
+export const getBidRecordKey = async (auctionAddress: PublicKey, bidder: PublicKey): Promise<[PublicKey, number]> => {
+    return await PublicKey.findProgramAddress(
+        [Buffer.from("bid"), auctionAddress.toBytes(), bidder.toBytes()],
+        program.programId
+    );
+}
+
+export const getReferralKey = async (referrer: PublicKey, token_mint: PublicKey): Promise<[PublicKey, number]> => {
+    return await PublicKey.findProgramAddress(
+        [Buffer.from("referral"), referrer.toBytes(), token_mint.toBytes()],
+        program.programId
+    );
+}
+
+export const getMetadataKey = async (mint: PublicKey): Promise<PublicKey> => {
+    return (await PublicKey.findProgramAddress(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        METADATA_PROGRAM_ID
+    ))[0];
+}
+
 export const getOpenAuctionState = async (
     auctionAddress: PublicKey
 ): Promise<OpenAuction | null> => {
@@ -319,11 +1299,97 @@ export const getOpenAuctionState = async (
     }
 }
 
-export const getDecimals = async (owner: PublicKey, tokenMint: PublicKey): Promise<number | null> => {
+export const getBidRecordState = async (
+    bidRecord: PublicKey
+): Promise<BidRecord | null> => {
+    try {
+        let bidState = await program.account.bidRecord.fetch(bidRecord);
+        return bidState as BidRecord;
+    } catch {
+        return null;
+    }
+}
+
+export const getReferralState = async (
+    referral: PublicKey
+): Promise<ReferralRecord | null> => {
+    try {
+        let referralState = await program.account.referralRecord.fetch(referral);
+        return referralState as ReferralRecord;
+    } catch {
+        return null;
+    }
+}
+
+export const getSealedAuctionState = async (
+    auctionAddress: PublicKey
+): Promise<SealedAuction | null> => {
+    try {
+        let auctionState = await program.account.sealedAuction.fetch(auctionAddress);
+        return auctionState as SealedAuction;
+    } catch {
+        return null;
+    }
+}
+
+export const getDutchAuctionState = async (
+    auctionAddress: PublicKey
+): Promise<DutchAuction | null> => {
+    try {
+        let auctionState = await program.account.dutchAuction.fetch(auctionAddress);
+        return auctionState as DutchAuction;
+    } catch {
+        return null;
+    }
+}
+
+export const getHouseConfigState = async (): Promise<HouseConfig | null> => {
+    try {
+        const [config] = await getHouseConfigKey();
+        let configState = await program.account.houseConfig.fetch(config);
+        return configState as HouseConfig;
+    } catch {
+        return null;
+    }
+}
+
+export const getProjectConfigState = async (
+    project_id: number
+): Promise<ProjectConfig | null> => {
+    try {
+        const [projectConfig] = await getProjectConfigKey(project_id);
+        let configState = await program.account.projectConfig.fetch(projectConfig);
+        return configState as ProjectConfig;
+    } catch {
+        return null;
+    }
+}
+
+// The commitments the program checks: keccak of a domain tag followed by the
+// value in base units and nonce as little-endian u64s.
+const computeCommitment = (tag: string, value: number, nonce: number): number[] => {
+    let data = Buffer.alloc(16);
+    data.writeBigUInt64LE(BigInt(value), 0);
+    data.writeBigUInt64LE(BigInt(nonce), 8);
+    return keccak_256.array(Buffer.concat([Buffer.from(tag), data]));
+}
+
+export const computeSealedBidHash = (bid: number, nonce: number): number[] => {
+    return computeCommitment("auctionhouse sealed bid", bid, nonce);
+}
+
+export const computeReserveHash = (reserve: number, nonce: number): number[] => {
+    return computeCommitment("auctionhouse reserve", reserve, nonce);
+}
+
+export const computeMaxBidHash = (maxBid: number, nonce: number): number[] => {
+    return computeCommitment("auctionhouse max bid", maxBid, nonce);
+}
+
+export const getDecimals = async (tokenMint: PublicKey): Promise<number | null> => {
     try {
-        let ownerTokenAccount = await getAssociatedTokenAccount(owner, tokenMint);
-        const tokenAccount = await solConnection.getParsedAccountInfo(ownerTokenAccount);
-        let decimal = (tokenAccount.value?.data as ParsedAccountData).parsed.info.tokenAmount.decimals;
+        const mintAccount = await solConnection.getParsedAccountInfo(tokenMint);
+        let decimal = (mintAccount.value?.data as ParsedAccountData).parsed.info.decimals;
         let DECIMALS = Math.pow(10, decimal);
         return DECIMALS;
     } catch {
@@ -331,11 +1397,15 @@ export const getDecimals = async (owner: PublicKey, tokenMint: PublicKey): Promi
     }
 }
 
-const getAssociatedTokenAccount = async (ownerPubkey: PublicKey, mintPk: PublicKey): Promise<PublicKey> => {
+const getAssociatedTokenAccount = async (
+    ownerPubkey: PublicKey,
+    mintPk: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+): Promise<PublicKey> => {
     let associatedTokenAccountPubkey = (await PublicKey.findProgramAddress(
         [
             ownerPubkey.toBuffer(),
-            TOKEN_PROGRAM_ID.toBuffer(),
+            tokenProgram.toBuffer(),
             mintPk.toBuffer(), // mint address
         ],
         ASSOCIATED_TOKEN_PROGRAM_ID
@@ -343,6 +1413,63 @@ const getAssociatedTokenAccount = async (ownerPubkey: PublicKey, mintPk: PublicK
     return associatedTokenAccountPubkey;
 }
 
+// The account holding wallet's bid tokens: the wallet itself when bids are in
+// native SOL, otherwise its associated token account of token_mint.
+const getBidAccount = async (
+    wallet: PublicKey,
+    token_mint: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+): Promise<PublicKey> => {
+    if (token_mint.equals(NATIVE_MINT)) {
+        return wallet;
+    }
+    return await getAssociatedTokenAccount(wallet, token_mint, tokenProgram);
+}
+
+// The config, treasury and referral accounts a winning bid or buy now price
+// is paid out through.
+const getPayoutAccounts = async (
+    auctionState: OpenAuction,
+    bidReferrer: PublicKey,
+    bidTokenProgram: PublicKey,
+) => {
+    let token_mint = auctionState.tokenMint;
+    let projectId = auctionState.projectId.toNumber();
+    const [config] = await getHouseConfigKey();
+    const [projectConfig] = await getProjectConfigKey(projectId);
+    let houseState = await getHouseConfigState();
+    let projectState = await getProjectConfigState(projectId);
+    const [bidReferral] = await getReferralKey(bidReferrer, token_mint);
+    const [creationReferral] = await getReferralKey(auctionState.referrer, token_mint);
+    return {
+        config,
+        projectConfig,
+        houseTreasuryAta: await getBidAccount(houseState.treasury, token_mint, bidTokenProgram),
+        projectTreasuryAta: await getBidAccount(projectState.treasury, token_mint, bidTokenProgram),
+        bidReferral,
+        bidReferralAta: await getBidAccount(bidReferral, token_mint, bidTokenProgram),
+        creationReferral,
+        creationReferralAta: await getBidAccount(creationReferral, token_mint, bidTokenProgram),
+    };
+}
+
+// The metadata of mint followed by the bid account of each verified creator,
+// in metadata order.
+const getRoyaltyAccounts = async (
+    mint: PublicKey,
+    creators: PublicKey[],
+    token_mint: PublicKey,
+    tokenProgram: PublicKey,
+): Promise<AccountMeta[]> => {
+    let accounts: AccountMeta[] = [
+        { pubkey: await getMetadataKey(mint), isSigner: false, isWritable: false },
+    ];
+    for (const creator of creators) {
+        accounts.push({ pubkey: await getBidAccount(creator, token_mint, tokenProgram), isSigner: false, isWritable: true });
+    }
+    return accounts;
+}
+
 export const getATokenAccountsNeedCreate = async (
     connection: anchor.web3.Connection,
     walletAddress: anchor.web3.PublicKey,
@@ -8,17 +8,42 @@ export interface OpenAuction {
     tokenAmount: anchor.BN,
 
     startTime: anchor.BN,
-    cancelled: Boolean,
     endTime: anchor.BN,
+    cancelled: Boolean,
+    settled: Boolean,
+
+    extensionWindow: anchor.BN,
+    extensionAmount: anchor.BN,
+    maxEndTime: anchor.BN,
+    extensionCount: anchor.BN,
+
+    buyNowPrice: anchor.BN,
+
+    reserveHash: number[],
+    reserveRevealWindow: anchor.BN,
+    reservePrice: anchor.BN,
+    reserveRevealed: Boolean,
+
+    retractCutoff: anchor.BN,
+    leaderCanRetract: Boolean,
+    maxBidRevealWindow: anchor.BN,
+
+    allowlistRoot: number[],
+    gatingCollection: PublicKey,
+    referrer: PublicKey,
 
     title: String,
 
     bidderCap: anchor.BN,
-    bidders: PublicKey[],
-    bids: anchor.BN[],
+    bidderCount: anchor.BN,
+    outstandingBids: anchor.BN,
+    bidRecords: anchor.BN,
 
     highestBidder: PublicKey,
     highestBid: anchor.BN,
+    highestMaxBid: anchor.BN,
+    highestMaxBidAt: anchor.BN,
+    winningBidPaid: Boolean,
 
     bidFloor: anchor.BN,
     minBidIncrement: anchor.BN,
@@ -27,6 +52,32 @@ export interface OpenAuction {
     projectId: anchor.BN,
 }
 
+export interface BidRecord {
+    auction: PublicKey,
+    bidder: PublicKey,
+    amount: anchor.BN,
+
+    createdAt: anchor.BN,
+    updatedAt: anchor.BN,
+    claimed: Boolean,
+
+    referrer: PublicKey,
+    maxHash: number[],
+
+    bump: anchor.BN,
+}
+
+export interface ReferralRecord {
+    referrer: PublicKey,
+    tokenMint: PublicKey,
+
+    totalEarned: anchor.BN,
+    totalClaimed: anchor.BN,
+    referralCount: anchor.BN,
+
+    bump: anchor.BN,
+}
+
 export interface SealedAuction {
     firstPrice: Boolean,
 
@@ -43,7 +94,7 @@ export interface SealedAuction {
 
     bidderCap: anchor.BN,
     bidders: PublicKey[],
-    sealedBids: Uint8Array[],
+    sealedBids: number[][],
 
     fakeBids: anchor.BN[],
 
@@ -56,3 +107,52 @@ export interface SealedAuction {
 
     bump: anchor.BN
 }
+
+export interface DutchAuction {
+    owner: PublicKey,
+    mint: PublicKey,
+    tokenMint: PublicKey,
+    tokenAmount: anchor.BN,
+
+    startTime: anchor.BN,
+    endTime: anchor.BN,
+    cancelled: Boolean,
+
+    title: String,
+
+    startPrice: anchor.BN,
+    floorPrice: anchor.BN,
+    stepInterval: anchor.BN,
+
+    buyer: PublicKey,
+    salePrice: anchor.BN,
+
+    bump: anchor.BN,
+    projectId: anchor.BN,
+}
+
+export interface HouseConfig {
+    authority: PublicKey,
+    admins: PublicKey[],
+    treasury: PublicKey,
+
+    feeBps: anchor.BN,
+    bidReferralBps: anchor.BN,
+    creationReferralBps: anchor.BN,
+
+    bump: anchor.BN,
+}
+
+export interface ProjectConfig {
+    projectId: anchor.BN,
+    authority: PublicKey,
+    admins: PublicKey[],
+    treasury: PublicKey,
+
+    feeAmount: anchor.BN,
+    tokenMints: PublicKey[],
+    crankReward: anchor.BN,
+    feeBps: anchor.BN,
+
+    bump: anchor.BN,
+}
@@ -0,0 +1,91 @@
+//! Mapping of custom program error codes back to `AuctionError`.
+
+use anchor_lang::__private::ERROR_CODE_OFFSET;
+use auctionhouse::error::AuctionError;
+use solana_program::program_error::ProgramError;
+
+/// Every `AuctionError` variant.
+pub const AUCTION_ERRORS: &[AuctionError] = &[
+    AuctionError::TitleOverflow,
+    AuctionError::InvalidIncrement,
+    AuctionError::InvalidStartTime,
+    AuctionError::InvalidEndTime,
+    AuctionError::InvalidBidFloor,
+    AuctionError::InvalidRevealPeriod,
+    AuctionError::InvalidTokenAmount,
+    AuctionError::UnderBidFloor,
+    AuctionError::InsufficientBid,
+    AuctionError::AuctionCancelled,
+    AuctionError::BidBeforeStart,
+    AuctionError::BidAfterClose,
+    AuctionError::BidderCapReached,
+    AuctionError::OwnerCannotBid,
+    AuctionError::AuctionNotOver,
+    AuctionError::NotBidder,
+    AuctionError::NoWinningBid,
+    AuctionError::WinnerCannotWithdrawBid,
+    AuctionError::AlreadyWithdrewBid,
+    AuctionError::DuplicateSealedBid,
+    AuctionError::MustSendSol,
+    AuctionError::RevealPeriodOver,
+    AuctionError::RevealPeriodNotOver,
+    AuctionError::HashMismatch,
+    AuctionError::CannotCancelRevealPeriod,
+    AuctionError::CannotCancelAfterClose,
+    AuctionError::InsufficientSol,
+    AuctionError::InvalidAdmin,
+    AuctionError::TooManyAdmins,
+    AuctionError::TooManyTokenMints,
+    AuctionError::InvalidTokenMint,
+    AuctionError::AlreadySettled,
+    AuctionError::InvalidRefundAccount,
+    AuctionError::AuctionNotEmpty,
+    AuctionError::InvalidPrice,
+    AuctionError::InvalidStepInterval,
+    AuctionError::AlreadySold,
+    AuctionError::InvalidBuyNowPrice,
+    AuctionError::BuyNowUnavailable,
+    AuctionError::ReserveNotRevealed,
+    AuctionError::RetractionClosed,
+    AuctionError::LeaderCannotRetract,
+    AuctionError::InvalidBidRecord,
+    AuctionError::NotAllowlisted,
+    AuctionError::AuctionAlreadyStarted,
+    AuctionError::InvalidMetadata,
+    AuctionError::NotCollectionHolder,
+    AuctionError::InvalidCreatorAccount,
+    AuctionError::InvalidFeeBps,
+    AuctionError::InvalidReferralAccount,
+    AuctionError::NothingToClaim,
+    AuctionError::InvalidBidAccount,
+    AuctionError::InvalidTokenAccount,
+    AuctionError::NothingToReclaim,
+    AuctionError::NotUpgradeAuthority,
+    AuctionError::ArithmeticOverflow,
+    AuctionError::NoReserveToReveal,
+    AuctionError::BidNotReclaimed,
+    AuctionError::NoMaxBidToReveal,
+    AuctionError::MaxBidOverEscrow,
+    AuctionError::InvalidMaxBidHash,
+    AuctionError::HiddenMaxBidConflict,
+];
+
+pub fn error_code(error: AuctionError) -> u32 {
+    ERROR_CODE_OFFSET + error as u32
+}
+
+/// The `AuctionError` of a custom program error code, None when the code is
+/// not one of the program's.
+pub fn auction_error(code: u32) -> Option<AuctionError> {
+    AUCTION_ERRORS
+        .iter()
+        .copied()
+        .find(|error| error_code(*error) == code)
+}
+
+pub fn auction_error_from_program_error(error: &ProgramError) -> Option<AuctionError> {
+    match error {
+        ProgramError::Custom(code) => auction_error(*code),
+        _ => None,
+    }
+}
@@ -0,0 +1,992 @@
+//! Instruction builders for every auctionhouse instruction. PDAs, bumps and
+//! token accounts are derived from the keys passed in, and instructions on an
+//! existing auction read what they need from its decoded state.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use auctionhouse::account::{DutchAuction, OpenAuction, SealedAuction};
+use auctionhouse::{accounts, instruction as ix};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::{system_instruction, system_program, sysvar};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: auctionhouse::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Arguments of create_open_auction, see the program for their meaning.
+#[derive(Clone, Debug, Default)]
+pub struct CreateOpenAuctionParams {
+    pub title: String,
+    pub floor: u64,
+    pub increment: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub bidder_cap: u64,
+    pub token_amount: u64,
+    pub project_id: u16,
+    pub extension_window: u64,
+    pub extension_amount: u64,
+    pub max_end_time: u64,
+    pub buy_now_price: u64,
+    pub reserve_hash: [u8; 32],
+    pub reserve_reveal_window: u64,
+    pub retract_cutoff: u64,
+    pub leader_can_retract: bool,
+    pub max_bid_reveal_window: u64,
+    pub allowlist_root: [u8; 32],
+    pub gating_collection: Pubkey,
+    pub referrer: Pubkey,
+}
+
+/// Arguments of create_sealed_auction.
+#[derive(Clone, Debug, Default)]
+pub struct CreateSealedAuctionParams {
+    pub title: String,
+    pub floor: u64,
+    pub first_price: bool,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub reveal_period: u64,
+    pub bidder_cap: u64,
+    pub token_amount: u64,
+}
+
+/// Arguments of create_dutch_auction.
+#[derive(Clone, Debug, Default)]
+pub struct CreateDutchAuctionParams {
+    pub title: String,
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub step_interval: u64,
+    pub token_amount: u64,
+    pub project_id: u16,
+}
+
+/// The referral PDA of referrer for token_mint and the account holding its
+/// rewards.
+fn referral_accounts(
+    referrer: &Pubkey,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> (Pubkey, Pubkey) {
+    let referral = pda::referral(referrer, token_mint).0;
+    (
+        referral,
+        pda::bid_account(&referral, token_mint, token_program),
+    )
+}
+
+/// The metadata of mint followed by the bid account of each verified creator,
+/// in metadata order, as withdraw_winning_bid_open, settle_open_auction and
+/// buy_now_open expect them in remaining_accounts.
+pub fn royalty_accounts(
+    mint: &Pubkey,
+    creators: &[Pubkey],
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Vec<AccountMeta> {
+    let mut metas = vec![AccountMeta::new_readonly(pda::metadata(mint), false)];
+    metas.extend(creators.iter().map(|creator| {
+        AccountMeta::new(pda::bid_account(creator, token_mint, token_program), false)
+    }));
+    metas
+}
+
+pub fn initialize_house_config(
+    authority: &Pubkey,
+    admins: Vec<Pubkey>,
+    treasury: Pubkey,
+    fee_bps: u16,
+    bid_referral_bps: u16,
+    creation_referral_bps: u16,
+) -> Instruction {
+    let (config, bump) = pda::house_config();
+    build(
+        accounts::InitializeHouseConfig {
+            config,
+            authority: *authority,
+            program_data: pda::program_data(),
+            system_program: system_program::ID,
+        },
+        ix::InitializeHouseConfig {
+            bump,
+            admins,
+            treasury,
+            fee_bps,
+            bid_referral_bps,
+            creation_referral_bps,
+        },
+    )
+}
+
+pub fn update_house_config(
+    authority: &Pubkey,
+    new_authority: Pubkey,
+    admins: Vec<Pubkey>,
+    treasury: Pubkey,
+    fee_bps: u16,
+    bid_referral_bps: u16,
+    creation_referral_bps: u16,
+) -> Instruction {
+    build(
+        accounts::UpdateHouseConfig {
+            config: pda::house_config().0,
+            authority: *authority,
+        },
+        ix::UpdateHouseConfig {
+            authority: new_authority,
+            admins,
+            treasury,
+            fee_bps,
+            bid_referral_bps,
+            creation_referral_bps,
+        },
+    )
+}
+
+/// Built for the house config authority, who signs as authority.
+pub fn initialize_project_config(
+    house_authority: &Pubkey,
+    project_id: u16,
+    authority: Pubkey,
+    admins: Vec<Pubkey>,
+    treasury: Pubkey,
+    fee_amount: u64,
+    token_mints: Vec<Pubkey>,
+    crank_reward: u64,
+    fee_bps: u16,
+) -> Instruction {
+    let (project_config, bump) = pda::project_config(project_id);
+    build(
+        accounts::InitializeProjectConfig {
+            config: pda::house_config().0,
+            project_config,
+            authority: *house_authority,
+            system_program: system_program::ID,
+        },
+        ix::InitializeProjectConfig {
+            bump,
+            project_id,
+            authority,
+            admins,
+            treasury,
+            fee_amount,
+            token_mints,
+            crank_reward,
+            fee_bps,
+        },
+    )
+}
+
+pub fn update_project_config(
+    authority: &Pubkey,
+    project_id: u16,
+    new_authority: Pubkey,
+    admins: Vec<Pubkey>,
+    treasury: Pubkey,
+    fee_amount: u64,
+    token_mints: Vec<Pubkey>,
+    crank_reward: u64,
+    fee_bps: u16,
+) -> Instruction {
+    build(
+        accounts::UpdateProjectConfig {
+            project_config: pda::project_config(project_id).0,
+            authority: *authority,
+        },
+        ix::UpdateProjectConfig {
+            authority: new_authority,
+            admins,
+            treasury,
+            fee_amount,
+            token_mints,
+            crank_reward,
+            fee_bps,
+        },
+    )
+}
+
+/// token_program is the token program of mint.
+pub fn create_open_auction(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+    params: CreateOpenAuctionParams,
+) -> Instruction {
+    let (auction, bump) = pda::open_auction(owner, &params.title);
+    build(
+        accounts::CreateOpenAuction {
+            auction,
+            config: pda::house_config().0,
+            project_config: pda::project_config(params.project_id).0,
+            auction_ata: pda::token_account(&auction, mint, token_program),
+            owner: *owner,
+            owner_ata: pda::token_account(owner, mint, token_program),
+            mint: *mint,
+            token_mint: *token_mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::CreateOpenAuction {
+            bump,
+            title: params.title,
+            floor: params.floor,
+            increment: params.increment,
+            start_time: params.start_time,
+            end_time: params.end_time,
+            bidder_cap: params.bidder_cap,
+            token_amount: params.token_amount,
+            project_id: params.project_id,
+            extension_window: params.extension_window,
+            extension_amount: params.extension_amount,
+            max_end_time: params.max_end_time,
+            buy_now_price: params.buy_now_price,
+            reserve_hash: params.reserve_hash,
+            reserve_reveal_window: params.reserve_reveal_window,
+            retract_cutoff: params.retract_cutoff,
+            leader_can_retract: params.leader_can_retract,
+            max_bid_reveal_window: params.max_bid_reveal_window,
+            allowlist_root: params.allowlist_root,
+            gating_collection: params.gating_collection,
+            referrer: params.referrer,
+        },
+    )
+}
+
+pub fn cancel_open_auction(auction: &Pubkey, owner: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelOpenAuction {
+            auction: *auction,
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        ix::CancelOpenAuction {},
+    )
+}
+
+pub fn update_allowlist_open(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    admin: &Pubkey,
+    allowlist_root: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::UpdateAllowlistOpen {
+            auction: *auction,
+            config: pda::house_config().0,
+            project_config: pda::project_config(state.project_id).0,
+            admin: *admin,
+        },
+        ix::UpdateAllowlistOpen { allowlist_root },
+    )
+}
+
+pub fn reveal_reserve_open(
+    auction: &Pubkey,
+    owner: &Pubkey,
+    reserve: u64,
+    nonce: u64,
+) -> Instruction {
+    build(
+        accounts::RevealReserveOpen {
+            auction: *auction,
+            owner: *owner,
+        },
+        ix::RevealReserveOpen { reserve, nonce },
+    )
+}
+
+/// token_program is the token program of the auction's token_mint.
+/// max_hash is compute_max_bid_hash of the bidder's maximum when the auction
+/// hides maximums, all zero otherwise.
+/// collection_nft is the bidder's token account of an NFT in the auction's
+/// gating collection and that NFT's mint, when the auction is gated.
+pub fn make_open_bid(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    bidder: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+    referrer: Pubkey,
+    max_hash: [u8; 32],
+    collection_nft: Option<(Pubkey, Pubkey)>,
+) -> Instruction {
+    let (bid_record, bump) = pda::bid_record(auction, bidder);
+    let mut instruction = build(
+        accounts::MakeOpenBid {
+            auction: *auction,
+            bid_record,
+            auction_ata: pda::bid_account(auction, &state.token_mint, token_program),
+            bidder: *bidder,
+            bidder_ata: pda::bid_account(bidder, &state.token_mint, token_program),
+            token_mint: state.token_mint,
+            system_program: system_program::ID,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::MakeOpenBid {
+            bump,
+            amount,
+            proof,
+            referrer,
+            max_hash,
+        },
+    );
+    if let Some((nft_account, nft_mint)) = collection_nft {
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(nft_account, false),
+            AccountMeta::new_readonly(pda::metadata(&nft_mint), false),
+        ]);
+    }
+    instruction
+}
+
+/// treasury is the treasury of the auction's project config.
+pub fn reclaim_open_bid(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    bidder: &Pubkey,
+    treasury: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ReclaimOpenBid {
+            auction: *auction,
+            project_config: pda::project_config(state.project_id).0,
+            bid_record: pda::bid_record(auction, bidder).0,
+            auction_ata: pda::bid_account(auction, &state.token_mint, token_program),
+            bidder: *bidder,
+            bidder_ata: pda::bid_account(bidder, &state.token_mint, token_program),
+            treasury_wallet: *treasury,
+            token_mint: state.token_mint,
+            token_program: *token_program,
+            system_program: system_program::ID,
+        },
+        ix::ReclaimOpenBid {},
+    )
+}
+
+/// other_bidders are the other bidders with an outstanding bid, only needed
+/// when the highest bidder retracts.
+pub fn retract_open_bid(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    bidder: &Pubkey,
+    token_program: &Pubkey,
+    other_bidders: &[Pubkey],
+) -> Instruction {
+    let mut instruction = build(
+        accounts::RetractOpenBid {
+            auction: *auction,
+            bid_record: pda::bid_record(auction, bidder).0,
+            auction_ata: pda::bid_account(auction, &state.token_mint, token_program),
+            bidder: *bidder,
+            bidder_ata: pda::bid_account(bidder, &state.token_mint, token_program),
+            token_mint: state.token_mint,
+            token_program: *token_program,
+        },
+        ix::RetractOpenBid {},
+    );
+    instruction.accounts.extend(
+        other_bidders
+            .iter()
+            .map(|other| AccountMeta::new_readonly(pda::bid_record(auction, other).0, false)),
+    );
+    instruction
+}
+
+pub fn initialize_referral(
+    referrer: &Pubkey,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let (referral, bump) = pda::referral(referrer, token_mint);
+    build(
+        accounts::InitializeReferral {
+            referral,
+            referral_ata: pda::bid_account(&referral, token_mint, token_program),
+            referrer: *referrer,
+            token_mint: *token_mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::InitializeReferral { bump },
+    )
+}
+
+pub fn claim_referral_rewards(
+    referrer: &Pubkey,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let (referral, referral_ata) = referral_accounts(referrer, token_mint, token_program);
+    build(
+        accounts::ClaimReferralRewards {
+            referral,
+            referral_ata,
+            referrer: *referrer,
+            referrer_ata: pda::bid_account(referrer, token_mint, token_program),
+            token_mint: *token_mint,
+            token_program: *token_program,
+        },
+        ix::ClaimReferralRewards {},
+    )
+}
+
+pub fn reveal_open_bid(auction: &Pubkey, bidder: &Pubkey, max_bid: u64, nonce: u64) -> Instruction {
+    build(
+        accounts::RevealOpenBid {
+            auction: *auction,
+            bid_record: pda::bid_record(auction, bidder).0,
+            bidder: *bidder,
+        },
+        ix::RevealOpenBid { max_bid, nonce },
+    )
+}
+
+/// Refunds the bids of bidders, paying the cranker.
+pub fn refund_open_bids(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    cranker: &Pubkey,
+    token_program: &Pubkey,
+    bidders: &[Pubkey],
+) -> Instruction {
+    let mut instruction = build(
+        accounts::RefundOpenBids {
+            auction: *auction,
+            project_config: pda::project_config(state.project_id).0,
+            auction_ata: pda::bid_account(auction, &state.token_mint, token_program),
+            cranker: *cranker,
+            token_mint: state.token_mint,
+            token_program: *token_program,
+        },
+        ix::RefundOpenBids {},
+    );
+    for bidder in bidders {
+        instruction.accounts.extend([
+            AccountMeta::new(pda::bid_record(auction, bidder).0, false),
+            AccountMeta::new(
+                pda::bid_account(bidder, &state.token_mint, token_program),
+                false,
+            ),
+        ]);
+    }
+    instruction
+}
+
+/// Closes the spent bid record of bidder, returning its rent to them.
+pub fn close_open_bid(auction: &Pubkey, bidder: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseOpenBid {
+            auction: *auction,
+            bid_record: pda::bid_record(auction, bidder).0,
+            bidder: *bidder,
+        },
+        ix::CloseOpenBid {},
+    )
+}
+
+/// Sends lamports to a project config to fund its crank rewards.
+pub fn fund_crank_rewards(payer: &Pubkey, project_id: u16, lamports: u64) -> Instruction {
+    system_instruction::transfer(payer, &pda::project_config(project_id).0, lamports)
+}
+
+/// token_program is the token program of the auction's mint.
+pub fn withdraw_item_open(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::WithdrawItemOpen {
+            auction: *auction,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            highest_bidder: state.highest_bidder,
+            highest_bidder_ata: pda::token_account(
+                &state.highest_bidder,
+                &state.mint,
+                token_program,
+            ),
+            mint: state.mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::WithdrawItemOpen {},
+    )
+}
+
+/// house_treasury and project_treasury are the treasuries of the house and
+/// the auction's project config, bid_referrer the referrer of the winning
+/// bid_record and creators the verified creators of the item in metadata
+/// order. token_program is the token program of the auction's token_mint.
+pub fn withdraw_winning_bid_open(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    house_treasury: &Pubkey,
+    project_treasury: &Pubkey,
+    bid_referrer: &Pubkey,
+    creators: &[Pubkey],
+    token_program: &Pubkey,
+) -> Instruction {
+    let token_mint = &state.token_mint;
+    let (bid_referral, bid_referral_ata) =
+        referral_accounts(bid_referrer, token_mint, token_program);
+    let (creation_referral, creation_referral_ata) =
+        referral_accounts(&state.referrer, token_mint, token_program);
+    let mut instruction = build(
+        accounts::WithdrawWinningBidOpen {
+            auction: *auction,
+            winning_bid_record: pda::bid_record(auction, &state.highest_bidder).0,
+            config: pda::house_config().0,
+            project_config: pda::project_config(state.project_id).0,
+            house_treasury_ata: pda::bid_account(house_treasury, token_mint, token_program),
+            project_treasury_ata: pda::bid_account(project_treasury, token_mint, token_program),
+            bid_referral,
+            bid_referral_ata,
+            creation_referral,
+            creation_referral_ata,
+            owner: state.owner,
+            auction_ata: pda::bid_account(auction, token_mint, token_program),
+            owner_ata: pda::bid_account(&state.owner, token_mint, token_program),
+            token_mint: *token_mint,
+            token_program: *token_program,
+        },
+        ix::WithdrawWinningBidOpen {},
+    );
+    instruction.accounts.extend(royalty_accounts(
+        &state.mint,
+        creators,
+        token_mint,
+        token_program,
+    ));
+    instruction
+}
+
+/// Same accounts as withdraw_winning_bid_open, with token_program and
+/// bid_token_program the token programs of mint and token_mint.
+pub fn settle_open_auction(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    payer: &Pubkey,
+    house_treasury: &Pubkey,
+    project_treasury: &Pubkey,
+    bid_referrer: &Pubkey,
+    creators: &[Pubkey],
+    token_program: &Pubkey,
+    bid_token_program: &Pubkey,
+) -> Instruction {
+    let token_mint = &state.token_mint;
+    let (bid_referral, bid_referral_ata) =
+        referral_accounts(bid_referrer, token_mint, bid_token_program);
+    let (creation_referral, creation_referral_ata) =
+        referral_accounts(&state.referrer, token_mint, bid_token_program);
+    let mut instruction = build(
+        accounts::SettleOpenAuction {
+            auction: *auction,
+            winning_bid_record: pda::bid_record(auction, &state.highest_bidder).0,
+            config: pda::house_config().0,
+            project_config: pda::project_config(state.project_id).0,
+            house_treasury_ata: pda::bid_account(house_treasury, token_mint, bid_token_program),
+            project_treasury_ata: pda::bid_account(project_treasury, token_mint, bid_token_program),
+            bid_referral,
+            bid_referral_ata,
+            creation_referral,
+            creation_referral_ata,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            auction_token_ata: pda::bid_account(auction, token_mint, bid_token_program),
+            payer: *payer,
+            highest_bidder: state.highest_bidder,
+            highest_bidder_ata: pda::token_account(
+                &state.highest_bidder,
+                &state.mint,
+                token_program,
+            ),
+            highest_bidder_token_ata: pda::bid_account(
+                &state.highest_bidder,
+                token_mint,
+                bid_token_program,
+            ),
+            owner: state.owner,
+            owner_ata: pda::bid_account(&state.owner, token_mint, bid_token_program),
+            mint: state.mint,
+            token_mint: *token_mint,
+            token_program: *token_program,
+            bid_token_program: *bid_token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::SettleOpenAuction {},
+    );
+    instruction.accounts.extend(royalty_accounts(
+        &state.mint,
+        creators,
+        token_mint,
+        bid_token_program,
+    ));
+    instruction
+}
+
+/// token_program and bid_token_program are the token programs of mint and
+/// token_mint. The buy now price is paid out like a winning bid, see
+/// settle_open_auction. collection_nft is as for make_open_bid.
+pub fn buy_now_open(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    buyer: &Pubkey,
+    house_treasury: &Pubkey,
+    project_treasury: &Pubkey,
+    proof: Vec<[u8; 32]>,
+    referrer: Pubkey,
+    collection_nft: Option<(Pubkey, Pubkey)>,
+    creators: &[Pubkey],
+    token_program: &Pubkey,
+    bid_token_program: &Pubkey,
+) -> Instruction {
+    let token_mint = &state.token_mint;
+    let (bid_referral, bid_referral_ata) =
+        referral_accounts(&referrer, token_mint, bid_token_program);
+    let (creation_referral, creation_referral_ata) =
+        referral_accounts(&state.referrer, token_mint, bid_token_program);
+    let mut instruction = build(
+        accounts::BuyNowOpen {
+            auction: *auction,
+            config: pda::house_config().0,
+            project_config: pda::project_config(state.project_id).0,
+            house_treasury_ata: pda::bid_account(house_treasury, token_mint, bid_token_program),
+            project_treasury_ata: pda::bid_account(project_treasury, token_mint, bid_token_program),
+            bid_referral,
+            bid_referral_ata,
+            creation_referral,
+            creation_referral_ata,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            auction_token_ata: pda::bid_account(auction, token_mint, bid_token_program),
+            buyer: *buyer,
+            buyer_ata: pda::token_account(buyer, &state.mint, token_program),
+            buyer_token_ata: pda::bid_account(buyer, token_mint, bid_token_program),
+            owner: state.owner,
+            owner_ata: pda::bid_account(&state.owner, token_mint, bid_token_program),
+            mint: state.mint,
+            token_mint: *token_mint,
+            token_program: *token_program,
+            bid_token_program: *bid_token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::BuyNowOpen { proof, referrer },
+    );
+    if let Some((nft_account, nft_mint)) = collection_nft {
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(nft_account, false),
+            AccountMeta::new_readonly(pda::metadata(&nft_mint), false),
+        ]);
+    }
+    instruction.accounts.extend(royalty_accounts(
+        &state.mint,
+        creators,
+        token_mint,
+        bid_token_program,
+    ));
+    instruction
+}
+
+pub fn reclaim_item_open(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ReclaimItemOpen {
+            auction: *auction,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            owner: state.owner,
+            owner_ata: pda::token_account(&state.owner, &state.mint, token_program),
+            mint: state.mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::ReclaimItemOpen {},
+    )
+}
+
+pub fn close_open_auction(
+    auction: &Pubkey,
+    state: &OpenAuction,
+    token_program: &Pubkey,
+    bid_token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CloseOpenAuction {
+            auction: *auction,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            auction_token_ata: pda::bid_account(auction, &state.token_mint, bid_token_program),
+            owner: state.owner,
+            mint: state.mint,
+            token_mint: state.token_mint,
+            token_program: *token_program,
+            bid_token_program: *bid_token_program,
+        },
+        ix::CloseOpenAuction {},
+    )
+}
+
+/// token_program is the token program of mint.
+pub fn create_sealed_auction(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    params: CreateSealedAuctionParams,
+) -> Instruction {
+    let (auction, bump) = pda::sealed_auction(owner, &params.title);
+    build(
+        accounts::CreateSealedAuction {
+            auction,
+            config: pda::house_config().0,
+            auction_ata: pda::token_account(&auction, mint, token_program),
+            owner: *owner,
+            owner_ata: pda::token_account(owner, mint, token_program),
+            mint: *mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::CreateSealedAuction {
+            bump,
+            title: params.title,
+            floor: params.floor,
+            first_price: params.first_price,
+            start_time: params.start_time,
+            end_time: params.end_time,
+            reveal_period: params.reveal_period,
+            bidder_cap: params.bidder_cap,
+            token_amount: params.token_amount,
+        },
+    )
+}
+
+pub fn cancel_sealed_auction(auction: &Pubkey, owner: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelSealedAuction {
+            auction: *auction,
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        ix::CancelSealedAuction {},
+    )
+}
+
+pub fn make_sealed_bid(
+    auction: &Pubkey,
+    bidder: &Pubkey,
+    sealed_bid: [u8; 32],
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::MakeSealedBid {
+            auction: *auction,
+            bidder: *bidder,
+            system_program: system_program::ID,
+        },
+        ix::MakeSealedBid { sealed_bid, amount },
+    )
+}
+
+pub fn reveal_sealed_bid(auction: &Pubkey, bidder: &Pubkey, bid: u64, nonce: u64) -> Instruction {
+    build(
+        accounts::RevealSealedBid {
+            auction: *auction,
+            bidder: *bidder,
+        },
+        ix::RevealSealedBid { bid, nonce },
+    )
+}
+
+pub fn reclaim_sealed_bid(auction: &Pubkey, bidder: &Pubkey) -> Instruction {
+    build(
+        accounts::ReclaimSealedBid {
+            auction: *auction,
+            bidder: *bidder,
+        },
+        ix::ReclaimSealedBid {},
+    )
+}
+
+pub fn withdraw_item_sealed(
+    auction: &Pubkey,
+    state: &SealedAuction,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::WithdrawItemSealed {
+            auction: *auction,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            highest_bidder: state.highest_bidder,
+            highest_bidder_ata: pda::token_account(
+                &state.highest_bidder,
+                &state.mint,
+                token_program,
+            ),
+            mint: state.mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::WithdrawItemSealed {},
+    )
+}
+
+pub fn withdraw_winning_bid_sealed(auction: &Pubkey, state: &SealedAuction) -> Instruction {
+    build(
+        accounts::WithdrawWinningBidSealed {
+            auction: *auction,
+            owner: state.owner,
+        },
+        ix::WithdrawWinningBidSealed {},
+    )
+}
+
+pub fn reclaim_item_sealed(
+    auction: &Pubkey,
+    state: &SealedAuction,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ReclaimItemSealed {
+            auction: *auction,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            owner: state.owner,
+            owner_ata: pda::token_account(&state.owner, &state.mint, token_program),
+            mint: state.mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::ReclaimItemSealed {},
+    )
+}
+
+/// token_program is the token program of mint.
+pub fn create_dutch_auction(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+    params: CreateDutchAuctionParams,
+) -> Instruction {
+    let (auction, bump) = pda::dutch_auction(owner, &params.title);
+    build(
+        accounts::CreateDutchAuction {
+            auction,
+            config: pda::house_config().0,
+            project_config: pda::project_config(params.project_id).0,
+            auction_ata: pda::token_account(&auction, mint, token_program),
+            owner: *owner,
+            owner_ata: pda::token_account(owner, mint, token_program),
+            mint: *mint,
+            token_mint: *token_mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::CreateDutchAuction {
+            bump,
+            title: params.title,
+            start_price: params.start_price,
+            floor_price: params.floor_price,
+            start_time: params.start_time,
+            end_time: params.end_time,
+            step_interval: params.step_interval,
+            token_amount: params.token_amount,
+            project_id: params.project_id,
+        },
+    )
+}
+
+pub fn cancel_dutch_auction(auction: &Pubkey, owner: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelDutchAuction {
+            auction: *auction,
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        ix::CancelDutchAuction {},
+    )
+}
+
+/// token_program and bid_token_program are the token programs of mint and
+/// token_mint.
+pub fn buy_dutch(
+    auction: &Pubkey,
+    state: &DutchAuction,
+    buyer: &Pubkey,
+    amount: u64,
+    token_program: &Pubkey,
+    bid_token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::BuyDutch {
+            auction: *auction,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            auction_token_ata: pda::token_account(auction, &state.token_mint, bid_token_program),
+            buyer: *buyer,
+            buyer_ata: pda::token_account(buyer, &state.mint, token_program),
+            buyer_token_ata: pda::token_account(buyer, &state.token_mint, bid_token_program),
+            owner: state.owner,
+            owner_ata: pda::token_account(&state.owner, &state.token_mint, bid_token_program),
+            mint: state.mint,
+            token_mint: state.token_mint,
+            token_program: *token_program,
+            bid_token_program: *bid_token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::BuyDutch { amount },
+    )
+}
+
+pub fn reclaim_item_dutch(
+    auction: &Pubkey,
+    state: &DutchAuction,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ReclaimItemDutch {
+            auction: *auction,
+            auction_ata: pda::token_account(auction, &state.mint, token_program),
+            owner: state.owner,
+            owner_ata: pda::token_account(&state.owner, &state.mint, token_program),
+            mint: state.mint,
+            token_program: *token_program,
+            ata_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            rent_sysvar: sysvar::rent::ID,
+        },
+        ix::ReclaimItemDutch {},
+    )
+}
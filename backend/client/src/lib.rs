@@ -0,0 +1,10 @@
+//! Rust client for the auctionhouse program: PDA derivation, instruction
+//! builders, account decoding and error codes.
+#![allow(clippy::too_many_arguments)]
+
+pub mod error;
+pub mod instruction;
+pub mod pda;
+pub mod state;
+
+pub use auctionhouse::ID;
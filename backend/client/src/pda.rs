@@ -0,0 +1,78 @@
+//! Program derived addresses of the auctionhouse program, derived from the
+//! same seeds the program checks.
+
+use auctionhouse::utils::{get_ata_address, get_program_data_address, is_native_mint, name_seed};
+use mpl_token_metadata::pda::find_metadata_account;
+use solana_program::pubkey::Pubkey;
+
+pub fn house_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"house config"], &auctionhouse::ID)
+}
+
+pub fn program_data() -> Pubkey {
+    get_program_data_address()
+}
+
+pub fn project_config(project_id: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"project config", project_id.to_le_bytes().as_ref()],
+        &auctionhouse::ID,
+    )
+}
+
+pub fn open_auction(owner: &Pubkey, title: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"open auction", owner.as_ref(), name_seed(title)],
+        &auctionhouse::ID,
+    )
+}
+
+pub fn sealed_auction(owner: &Pubkey, title: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"sealed auction", owner.as_ref(), name_seed(title)],
+        &auctionhouse::ID,
+    )
+}
+
+pub fn dutch_auction(owner: &Pubkey, title: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"dutch auction", owner.as_ref(), name_seed(title)],
+        &auctionhouse::ID,
+    )
+}
+
+pub fn bid_record(auction: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"bid", auction.as_ref(), bidder.as_ref()],
+        &auctionhouse::ID,
+    )
+}
+
+pub fn referral(referrer: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"referral", referrer.as_ref(), token_mint.as_ref()],
+        &auctionhouse::ID,
+    )
+}
+
+/// The Metaplex metadata account of mint.
+pub fn metadata(mint: &Pubkey) -> Pubkey {
+    find_metadata_account(mint).0
+}
+
+/// The associated token account of wallet for mint under token_program, the
+/// SPL Token or Token-2022 program.
+pub fn token_account(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_ata_address(wallet, mint, token_program)
+}
+
+/// The account holding wallet's bid tokens: the wallet itself when bids are in
+/// native SOL, otherwise its associated token account of token_mint. For an
+/// auction or referral PDA this is its escrow.
+pub fn bid_account(wallet: &Pubkey, token_mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    if is_native_mint(token_mint) {
+        *wallet
+    } else {
+        token_account(wallet, token_mint, token_program)
+    }
+}
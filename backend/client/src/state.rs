@@ -0,0 +1,75 @@
+//! Decoding of the auctionhouse program accounts.
+
+use anchor_lang::AccountDeserialize;
+use auctionhouse::account::{
+    BidRecord, DutchAuction, HouseConfig, OpenAuction, ProjectConfig, ReferralRecord, SealedAuction,
+};
+use solana_program::program_error::ProgramError;
+
+/// Decodes account data, checking its Anchor discriminator.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    let mut data = data;
+    T::try_deserialize(&mut data)
+}
+
+pub fn decode_open_auction(data: &[u8]) -> Result<OpenAuction, ProgramError> {
+    decode(data)
+}
+
+pub fn decode_bid_record(data: &[u8]) -> Result<BidRecord, ProgramError> {
+    decode(data)
+}
+
+pub fn decode_referral(data: &[u8]) -> Result<ReferralRecord, ProgramError> {
+    decode(data)
+}
+
+pub fn decode_sealed_auction(data: &[u8]) -> Result<SealedAuction, ProgramError> {
+    decode(data)
+}
+
+pub fn decode_dutch_auction(data: &[u8]) -> Result<DutchAuction, ProgramError> {
+    decode(data)
+}
+
+pub fn decode_house_config(data: &[u8]) -> Result<HouseConfig, ProgramError> {
+    decode(data)
+}
+
+pub fn decode_project_config(data: &[u8]) -> Result<ProjectConfig, ProgramError> {
+    decode(data)
+}
+
+/// Where an open auction is in its life at cur_time, mirroring the checks the
+/// program makes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenAuctionStatus {
+    Cancelled,
+    NotStarted,
+    Live,
+    // ended, waiting on the reserve to be revealed
+    AwaitingReserve,
+    // ended without a winner, the item can be reclaimed
+    Unsold,
+    // ended with a winner who has not been fully settled yet
+    Ended,
+    Settled,
+}
+
+pub fn open_auction_status(auction: &OpenAuction, cur_time: u64) -> OpenAuctionStatus {
+    if auction.cancelled {
+        OpenAuctionStatus::Cancelled
+    } else if auction.settled {
+        OpenAuctionStatus::Settled
+    } else if cur_time <= auction.start_time {
+        OpenAuctionStatus::NotStarted
+    } else if cur_time <= auction.end_time {
+        OpenAuctionStatus::Live
+    } else if auction.reserve_forfeited(cur_time) || auction.highest_bid == 0 {
+        OpenAuctionStatus::Unsold
+    } else if auction.has_reserve() && !auction.reserve_revealed {
+        OpenAuctionStatus::AwaitingReserve
+    } else {
+        OpenAuctionStatus::Ended
+    }
+}
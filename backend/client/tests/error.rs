@@ -0,0 +1,44 @@
+use auctionhouse::error::AuctionError;
+use auctionhouse_client::error::{
+    auction_error, auction_error_from_program_error, error_code, AUCTION_ERRORS,
+};
+use solana_program::program_error::ProgramError;
+
+// every variant of the enum carries a message
+const ERROR_SOURCE: &str = include_str!("../../programs/auctionhouse/src/error.rs");
+
+// AuctionError has no PartialEq
+fn discriminant(error: Option<AuctionError>) -> Option<u32> {
+    error.map(|error| error as u32)
+}
+
+#[test]
+fn every_variant_listed_in_order() {
+    assert_eq!(AUCTION_ERRORS.len(), ERROR_SOURCE.matches("#[msg(").count());
+    for (index, error) in AUCTION_ERRORS.iter().enumerate() {
+        assert_eq!(*error as usize, index, "{:?} is out of order", error);
+    }
+}
+
+#[test]
+fn codes_round_trip() {
+    for error in AUCTION_ERRORS.iter().copied() {
+        let code = error_code(error);
+        assert_eq!(discriminant(auction_error(code)), Some(error as u32));
+        assert_eq!(
+            discriminant(auction_error_from_program_error(&ProgramError::Custom(
+                code
+            ))),
+            Some(error as u32)
+        );
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+    }
+}
+
+#[test]
+fn other_codes_are_not_auction_errors() {
+    let last = *AUCTION_ERRORS.last().unwrap();
+    assert!(auction_error(error_code(last) + 1).is_none());
+    assert!(auction_error(error_code(AuctionError::TitleOverflow) - 1).is_none());
+    assert!(auction_error_from_program_error(&ProgramError::InvalidArgument).is_none());
+}
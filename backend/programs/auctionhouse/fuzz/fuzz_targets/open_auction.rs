@@ -0,0 +1,387 @@
+//! Drives arbitrary sequences of open auction instructions against the
+//! in-process bank of the integration tests and checks after every step that
+//! escrow holds exactly the outstanding bids, the item is in exactly one place
+//! and no lamports or tokens were created.
+#![no_main]
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use arbitrary::Arbitrary;
+use auctionhouse::account::{BidRecord, OpenAuction};
+use auctionhouse::utils::compute_max_bid_hash;
+use auctionhouse_client::instruction::{self as ix, CreateOpenAuctionParams};
+use auctionhouse_client::pda;
+use common::{Bank, SOL};
+use libfuzzer_sys::fuzz_target;
+use solana_program::instruction::Instruction;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+
+const BIDDERS: usize = 3;
+const BID_TOKENS: u64 = 100_000;
+const PROJECT_ID: u16 = 1;
+const TITLE: &str = "fuzz";
+const NONCE: u64 = 7;
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    Create {
+        floor: u16,
+        increment: u8,
+        start_in: u8,
+        duration: u16,
+        bidder_cap: u8,
+        extension_window: u8,
+        extension_amount: u8,
+        max_extension: Option<u8>,
+        retract_cutoff: u8,
+        leader_can_retract: bool,
+        max_bid_reveal_window: u8,
+    },
+    // max_bid is committed when the auction hides maximums
+    Bid {
+        bidder: u8,
+        amount: u16,
+        max_bid: Option<u16>,
+    },
+    Reveal {
+        bidder: u8,
+        max_bid: u16,
+    },
+    Cancel,
+    Reclaim {
+        bidder: u8,
+    },
+    // records holds one bit per bidder whose bid record is passed, the
+    // retracting bidder's own included
+    Retract {
+        bidder: u8,
+        records: u8,
+    },
+    CloseBid {
+        bidder: u8,
+    },
+    WithdrawItem,
+    WithdrawWinningBid,
+    Settle,
+    ReclaimItem,
+    Warp {
+        seconds: u16,
+    },
+}
+
+struct World {
+    bank: Bank,
+    owner: Pubkey,
+    house_treasury: Pubkey,
+    project_treasury: Pubkey,
+    mint: Pubkey,
+    token_mint: Pubkey,
+    bidders: Vec<Pubkey>,
+    auction: Pubkey,
+    lamports: u128,
+}
+
+impl World {
+    fn new() -> Self {
+        let mut bank = Bank::new();
+        let authority = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mint_authority = Pubkey::new_unique();
+        let house_treasury = Pubkey::new_unique();
+        let project_treasury = Pubkey::new_unique();
+        let bidders: Vec<Pubkey> = (0..BIDDERS).map(|_| Pubkey::new_unique()).collect();
+        for wallet in [authority, owner].iter().chain(bidders.iter()) {
+            bank.airdrop(wallet, 10 * SOL);
+        }
+        bank.set_upgrade_authority(&authority);
+
+        let mint = bank.create_mint(&mint_authority, 0);
+        let token_mint = bank.create_mint(&mint_authority, 6);
+        bank.mint_to(&mint, &mint_authority, &owner, 1);
+        for bidder in bidders.iter() {
+            bank.mint_to(&token_mint, &mint_authority, bidder, BID_TOKENS);
+        }
+        for wallet in [owner, house_treasury, project_treasury] {
+            bank.mint_to(&token_mint, &mint_authority, &wallet, 0);
+        }
+
+        bank.process(
+            &[
+                ix::initialize_house_config(&authority, vec![owner], house_treasury, 250, 0, 0),
+                ix::initialize_project_config(
+                    &authority,
+                    PROJECT_ID,
+                    authority,
+                    vec![],
+                    project_treasury,
+                    5_000,
+                    vec![token_mint],
+                    0,
+                    100,
+                ),
+            ],
+            &[authority],
+        )
+        .unwrap();
+
+        let lamports = bank.total_lamports();
+        World {
+            bank,
+            owner,
+            house_treasury,
+            project_treasury,
+            mint,
+            token_mint,
+            bidders,
+            auction: pda::open_auction(&owner, TITLE).0,
+            lamports,
+        }
+    }
+
+    fn state(&self) -> Option<OpenAuction> {
+        self.bank
+            .account(&self.auction)
+            .map(|_| self.bank.get(&self.auction))
+    }
+
+    fn bid_record(&self, bidder: &Pubkey) -> Option<BidRecord> {
+        let bid_record = pda::bid_record(&self.auction, bidder).0;
+        self.bank
+            .account(&bid_record)
+            .map(|_| self.bank.get(&bid_record))
+    }
+
+    fn balance(&self, wallet: &Pubkey, mint: &Pubkey) -> u64 {
+        self.bank
+            .token_balance(&pda::token_account(wallet, mint, &spl_token::ID))
+    }
+
+    fn supply(&self, mint: &Pubkey) -> u64 {
+        let account = self.bank.account(mint).unwrap();
+        spl_token::state::Mint::unpack(&account.data)
+            .unwrap()
+            .supply
+    }
+
+    fn instruction(&self, action: &Action) -> Option<(Instruction, Pubkey)> {
+        if let Action::Create {
+            floor,
+            increment,
+            start_in,
+            duration,
+            bidder_cap,
+            extension_window,
+            extension_amount,
+            max_extension,
+            retract_cutoff,
+            leader_can_retract,
+            max_bid_reveal_window,
+        } = *action
+        {
+            let start_time = self.bank.now() + start_in as u64;
+            let end_time = start_time + duration as u64;
+            let params = CreateOpenAuctionParams {
+                title: TITLE.to_string(),
+                floor: floor as u64,
+                increment: increment as u64,
+                start_time,
+                end_time,
+                bidder_cap: bidder_cap as u64,
+                token_amount: 1,
+                project_id: PROJECT_ID,
+                extension_window: extension_window as u64,
+                extension_amount: extension_amount as u64,
+                max_end_time: max_extension.map_or(0, |extension| end_time + extension as u64),
+                retract_cutoff: retract_cutoff as u64,
+                leader_can_retract,
+                max_bid_reveal_window: max_bid_reveal_window as u64,
+                ..CreateOpenAuctionParams::default()
+            };
+            let instruction = ix::create_open_auction(
+                &self.owner,
+                &self.mint,
+                &self.token_mint,
+                &spl_token::ID,
+                params,
+            );
+            return Some((instruction, self.owner));
+        }
+
+        let state = self.state()?;
+        let auction = &self.auction;
+        let bidder = |index: u8| self.bidders[index as usize % BIDDERS];
+        let instruction = match *action {
+            Action::Bid {
+                bidder: index,
+                amount,
+                max_bid,
+            } => {
+                let max_hash = max_bid.map_or([0; 32], |max_bid| {
+                    compute_max_bid_hash(max_bid as u64, NONCE)
+                });
+                let instruction = ix::make_open_bid(
+                    auction,
+                    &state,
+                    &bidder(index),
+                    &spl_token::ID,
+                    amount as u64,
+                    vec![],
+                    Pubkey::default(),
+                    max_hash,
+                    None,
+                );
+                (instruction, bidder(index))
+            }
+            Action::Reveal {
+                bidder: index,
+                max_bid,
+            } => {
+                let instruction =
+                    ix::reveal_open_bid(auction, &bidder(index), max_bid as u64, NONCE);
+                (instruction, bidder(index))
+            }
+            Action::Cancel => (ix::cancel_open_auction(auction, &self.owner), self.owner),
+            Action::Reclaim { bidder: index } => {
+                let instruction = ix::reclaim_open_bid(
+                    auction,
+                    &state,
+                    &bidder(index),
+                    &self.project_treasury,
+                    &spl_token::ID,
+                );
+                (instruction, bidder(index))
+            }
+            Action::Retract {
+                bidder: index,
+                records,
+            } => {
+                let others: Vec<Pubkey> = (0..BIDDERS)
+                    .filter(|other| records & (1 << other) != 0)
+                    .map(|other| self.bidders[other])
+                    .collect();
+                let instruction =
+                    ix::retract_open_bid(auction, &state, &bidder(index), &spl_token::ID, &others);
+                (instruction, bidder(index))
+            }
+            Action::CloseBid { bidder: index } => {
+                (ix::close_open_bid(auction, &bidder(index)), self.owner)
+            }
+            Action::WithdrawItem => (
+                ix::withdraw_item_open(auction, &state, &spl_token::ID),
+                state.highest_bidder,
+            ),
+            Action::WithdrawWinningBid => (
+                ix::withdraw_winning_bid_open(
+                    auction,
+                    &state,
+                    &self.house_treasury,
+                    &self.project_treasury,
+                    &Pubkey::default(),
+                    &[],
+                    &spl_token::ID,
+                ),
+                self.owner,
+            ),
+            Action::Settle => (
+                ix::settle_open_auction(
+                    auction,
+                    &state,
+                    &self.bidders[0],
+                    &self.house_treasury,
+                    &self.project_treasury,
+                    &Pubkey::default(),
+                    &[],
+                    &spl_token::ID,
+                    &spl_token::ID,
+                ),
+                self.bidders[0],
+            ),
+            Action::ReclaimItem => (
+                ix::reclaim_item_open(auction, &state, &spl_token::ID),
+                self.owner,
+            ),
+            Action::Create { .. } | Action::Warp { .. } => return None,
+        };
+        Some(instruction)
+    }
+
+    fn check_invariants(&self) {
+        assert_eq!(
+            self.bank.total_lamports(),
+            self.lamports,
+            "lamports created"
+        );
+
+        // the item is in exactly one place
+        let mut holders = vec![self.owner, self.auction];
+        holders.extend(self.bidders.iter());
+        let items: Vec<u64> = holders
+            .iter()
+            .map(|wallet| self.balance(wallet, &self.mint))
+            .collect();
+        assert_eq!(self.supply(&self.mint), 1);
+        assert_eq!(items.iter().sum::<u64>(), 1, "item at {:?}", items);
+
+        // every bid token is accounted for
+        holders.extend([self.house_treasury, self.project_treasury]);
+        let tokens: u64 = holders
+            .iter()
+            .map(|wallet| self.balance(wallet, &self.token_mint))
+            .sum();
+        assert_eq!(self.supply(&self.token_mint), BID_TOKENS * BIDDERS as u64);
+        assert_eq!(tokens, self.supply(&self.token_mint), "bid tokens lost");
+
+        let state = match self.state() {
+            Some(state) => state,
+            None => return,
+        };
+        let bids: Vec<BidRecord> = self
+            .bidders
+            .iter()
+            .filter_map(|bidder| self.bid_record(bidder))
+            .collect();
+        let escrowed: u64 = bids.iter().map(|bid| bid.amount).sum();
+        let outstanding = bids
+            .iter()
+            .filter(|bid| !bid.claimed && bid.amount > 0)
+            .count();
+        assert_eq!(
+            self.balance(&self.auction, &self.token_mint),
+            escrowed,
+            "escrow does not match the outstanding bids"
+        );
+        assert_eq!(state.outstanding_bids, outstanding as u64);
+        assert_eq!(state.bid_records, bids.len() as u64);
+        assert!(state.highest_bid <= state.highest_max_bid);
+        // until it is paid out the winning bid stays escrowed in full
+        if state.is_winning_bid_locked(&state.highest_bidder, self.bank.now()) {
+            let leader = self.bid_record(&state.highest_bidder).unwrap();
+            assert!(!leader.claimed, "leader's bid left escrow");
+            // a hidden maximum may sit under a larger escrow
+            if state.hides_max_bids() {
+                assert!(leader.amount >= state.highest_max_bid);
+            } else {
+                assert_eq!(leader.amount, state.highest_max_bid);
+            }
+        }
+        if state.token_amount > 0 {
+            assert_eq!(self.balance(&self.auction, &self.mint), state.token_amount);
+        }
+    }
+}
+
+fuzz_target!(|actions: Vec<Action>| {
+    let mut world = World::new();
+
+    for action in actions.iter().take(64) {
+        if let Action::Warp { seconds } = *action {
+            world.bank.warp_by(seconds as u64);
+        } else if let Some((instruction, signer)) = world.instruction(action) {
+            // failing is fine, the bank rolls the transaction back
+            let _ = world.bank.process(&[instruction], &[signer]);
+        }
+        world.check_invariants();
+    }
+});
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+use crate::engine;
+use crate::error::AuctionError;
+
 #[account]
 pub struct OpenAuction {
     pub owner: Pubkey,
@@ -10,15 +13,45 @@ pub struct OpenAuction {
     pub start_time: u64,
     pub end_time: u64,
     pub cancelled: bool,
+    pub settled: bool,
+
+    pub extension_window: u64,
+    pub extension_amount: u64,
+    pub max_end_time: u64,
+    pub extension_count: u64,
+
+    pub buy_now_price: u64,
+
+    pub reserve_hash: [u8; 32],
+    pub reserve_reveal_window: u64,
+    pub reserve_price: u64,
+    pub reserve_revealed: bool,
+
+    pub retract_cutoff: u64,
+    pub leader_can_retract: bool,
+
+    // 0 when maximums are public
+    pub max_bid_reveal_window: u64,
+
+    pub allowlist_root: [u8; 32],
+    pub gating_collection: Pubkey,
+    pub referrer: Pubkey,
 
     pub title: String,
 
     pub bidder_cap: u64,
-    pub bidders: Vec<Pubkey>,
-    pub bids: Vec<u64>,
+    pub bidder_count: u64,
+    pub outstanding_bids: u64,
+    // bid records still open, the auction only closes once there are none
+    pub bid_records: u64,
 
     pub highest_bidder: Pubkey,
     pub highest_bid: u64,
+    // the leader's maximum, public like every escrow unless the auction
+    // hides maximums until bidding closes
+    pub highest_max_bid: u64,
+    pub highest_max_bid_at: u64,
+    pub winning_bid_paid: bool,
 
     pub bid_floor: u64,
     pub min_bid_increment: u64,
@@ -27,6 +60,115 @@ pub struct OpenAuction {
     pub project_id: u16,
 }
 
+#[account]
+pub struct BidRecord {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    // the bidder's escrowed maximum bid
+    pub amount: u64,
+
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub claimed: bool,
+    pub referrer: Pubkey,
+    // the commitment to a hidden maximum until it is revealed, else all zero
+    pub max_hash: [u8; 32],
+
+    pub bump: u8,
+}
+
+#[account]
+pub struct ReferralRecord {
+    pub referrer: Pubkey,
+    pub token_mint: Pubkey,
+    pub total_earned: u64,
+    pub total_claimed: u64,
+    pub referral_count: u64,
+
+    pub bump: u8,
+}
+
+#[account]
+pub struct SealedAuction {
+    pub first_price: bool,
+
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_amount: u64,
+
+    pub start_time: u64,
+    pub end_time: u64,
+    pub reveal_period: u64,
+    pub cancelled: bool,
+
+    pub title: String,
+
+    pub bidder_cap: u64,
+    pub bidders: Vec<Pubkey>,
+    pub sealed_bids: Vec<[u8; 32]>,
+
+    pub fake_bids: Vec<u64>,
+
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+    pub second_highest_bid: u64,
+
+    pub bid_floor: u64,
+    pub winning_bid_withdrawn: bool,
+
+    pub bump: u8,
+}
+
+#[account]
+pub struct DutchAuction {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_amount: u64,
+
+    pub start_time: u64,
+    pub end_time: u64,
+    pub cancelled: bool,
+
+    pub title: String,
+
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub step_interval: u64,
+
+    pub buyer: Pubkey,
+    pub sale_price: u64,
+
+    pub bump: u8,
+    pub project_id: u16,
+}
+
+#[account]
+pub struct HouseConfig {
+    pub authority: Pubkey,
+    pub admins: Vec<Pubkey>,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+    pub bid_referral_bps: u16,
+    pub creation_referral_bps: u16,
+
+    pub bump: u8,
+}
+
+#[account]
+pub struct ProjectConfig {
+    pub project_id: u16,
+    pub authority: Pubkey,
+    pub admins: Vec<Pubkey>,
+    pub treasury: Pubkey,
+    pub fee_amount: u64,
+    pub token_mints: Vec<Pubkey>,
+    pub crank_reward: u64,
+    pub fee_bps: u16,
+
+    pub bump: u8,
+}
+
 pub const DISCRIMINATOR_LENGTH: usize = 8;
 pub const PUBLIC_KEY_LENGTH: usize = 32;
 pub const U64_LENGTH: usize = 8;
@@ -37,6 +179,13 @@ pub const BOOL_LENGTH: usize = 1;
 pub const STRING_LENGTH_PREFIX: usize = 4;
 pub const MAX_TITLE_LENGTH: usize = 50 * 4;
 pub const VECTOR_LENGTH_PREFIX: usize = 4;
+pub const HASH_LENGTH: usize = 32;
+pub const MAX_ADMINS: usize = 10;
+pub const MAX_TOKEN_MINTS: usize = 10;
+pub const MAX_BPS: u16 = 10000;
+// split so the house and project fees together never exceed the winning bid
+pub const MAX_HOUSE_FEE_BPS: u16 = 5000;
+pub const MAX_PROJECT_FEE_BPS: u16 = MAX_BPS - MAX_HOUSE_FEE_BPS;
 
 impl OpenAuction {
     pub const LEN: usize = DISCRIMINATOR_LENGTH
@@ -47,12 +196,288 @@ impl OpenAuction {
         + U64_LENGTH // start time
         + U64_LENGTH // end time
         + BOOL_LENGTH // cancelled
+        + BOOL_LENGTH // settled
+        + U64_LENGTH // extension window
+        + U64_LENGTH // extension amount
+        + U64_LENGTH // max end time
+        + U64_LENGTH // extension count
+        + U64_LENGTH // buy now price
+        + HASH_LENGTH // reserve hash
+        + U64_LENGTH // reserve reveal window
+        + U64_LENGTH // reserve price
+        + BOOL_LENGTH // reserve revealed
+        + U64_LENGTH // retract cutoff
+        + BOOL_LENGTH // leader can retract
+        + U64_LENGTH // max bid reveal window
+        + HASH_LENGTH // allowlist root
+        + PUBLIC_KEY_LENGTH // gating collection
+        + PUBLIC_KEY_LENGTH // referrer
         + STRING_LENGTH_PREFIX + MAX_TITLE_LENGTH // title
         + U64_LENGTH // bidder cap
+        + U64_LENGTH // bidder count
+        + U64_LENGTH // outstanding bids
+        + U64_LENGTH // bid records
         + PUBLIC_KEY_LENGTH // highest bidder
         + U64_LENGTH // highest bid
+        + U64_LENGTH // highest max bid
+        + U64_LENGTH // highest max bid at
+        + BOOL_LENGTH // winning bid paid
         + U64_LENGTH // bid floor
         + U64_LENGTH // min bid increment
         + U8_LENGTH // bump
         + U16_LENGTH; // project_id
 }
+
+impl OpenAuction {
+    /// Whether bids are escrowed as lamports in the auction PDA instead of
+    /// SPL tokens.
+    pub fn is_native(&self) -> bool {
+        self.token_mint == spl_token::native_mint::id()
+    }
+
+    pub fn has_allowlist(&self) -> bool {
+        self.allowlist_root != [0u8; 32]
+    }
+
+    pub fn has_reserve(&self) -> bool {
+        self.reserve_hash != [0u8; 32]
+    }
+
+    /// Whether the owner let the reserve reveal window pass without revealing
+    /// the reserve, which voids the sale.
+    pub fn reserve_forfeited(&self, cur_time: u64) -> bool {
+        self.engine().reserve_forfeited(cur_time)
+    }
+
+    pub fn hides_max_bids(&self) -> bool {
+        self.max_bid_reveal_window > 0
+    }
+
+    /// Whether every hidden maximum that will be revealed has been, so the
+    /// winner is known. Always true when maximums are public.
+    pub fn max_bids_revealed(&self, cur_time: u64) -> bool {
+        self.engine().max_bids_revealed(cur_time)
+    }
+
+    /// Whether the bidder's escrow is still held as the winning bid.
+    pub fn is_winning_bid_locked(&self, bidder: &Pubkey, cur_time: u64) -> bool {
+        self.engine().is_winning_bid_locked(bidder, cur_time)
+    }
+
+    /// The lowest bid that can open the auction.
+    pub fn min_opening_bid(&self) -> u64 {
+        self.engine().min_opening_bid()
+    }
+
+    /// The lowest maximum a new bidder must escrow to take the lead.
+    pub fn min_next_bid(&self) -> Result<u64, AuctionError> {
+        self.engine().min_next_bid()
+    }
+
+    /// Whether bids can still be retracted, which closes retract_cutoff
+    /// seconds before the end time.
+    pub fn is_retraction_open(&self, cur_time: u64) -> bool {
+        self.engine().is_retraction_open(cur_time)
+    }
+
+    /// The bidding state the engine runs on.
+    pub fn engine(&self) -> engine::Auction {
+        engine::Auction {
+            owner: self.owner,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            cancelled: self.cancelled,
+            extension_window: self.extension_window,
+            extension_amount: self.extension_amount,
+            max_end_time: self.max_end_time,
+            extension_count: self.extension_count,
+            has_reserve: self.has_reserve(),
+            reserve_revealed: self.reserve_revealed,
+            reserve_reveal_window: self.reserve_reveal_window,
+            bidder_cap: self.bidder_cap,
+            bidder_count: self.bidder_count,
+            outstanding_bids: self.outstanding_bids,
+            retract_cutoff: self.retract_cutoff,
+            leader_can_retract: self.leader_can_retract,
+            max_bid_reveal_window: self.max_bid_reveal_window,
+            highest_bidder: self.highest_bidder,
+            highest_bid: self.highest_bid,
+            highest_max_bid: self.highest_max_bid,
+            highest_max_bid_at: self.highest_max_bid_at,
+            winning_bid_paid: self.winning_bid_paid,
+            bid_floor: self.bid_floor,
+            min_bid_increment: self.min_bid_increment,
+        }
+    }
+
+    /// Stores what an engine transition changed.
+    pub fn apply(&mut self, state: &engine::Auction) {
+        self.end_time = state.end_time;
+        self.cancelled = state.cancelled;
+        self.extension_count = state.extension_count;
+        self.bidder_count = state.bidder_count;
+        self.outstanding_bids = state.outstanding_bids;
+        self.highest_bidder = state.highest_bidder;
+        self.highest_bid = state.highest_bid;
+        self.highest_max_bid = state.highest_max_bid;
+        self.highest_max_bid_at = state.highest_max_bid_at;
+        self.winning_bid_paid = state.winning_bid_paid;
+    }
+}
+
+impl BidRecord {
+    pub const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // auction
+        + PUBLIC_KEY_LENGTH // bidder
+        + U64_LENGTH // amount
+        + U64_LENGTH // created at
+        + U64_LENGTH // updated at
+        + BOOL_LENGTH // claimed
+        + PUBLIC_KEY_LENGTH // referrer
+        + HASH_LENGTH // max hash
+        + U8_LENGTH; // bump
+
+    pub fn engine(&self) -> engine::Bid {
+        engine::Bid {
+            bidder: self.bidder,
+            amount: self.amount,
+            claimed: self.claimed,
+            updated_at: self.updated_at,
+            max_hidden: self.max_hash != [0u8; 32],
+        }
+    }
+
+    pub fn apply(&mut self, bid: &engine::Bid) {
+        self.bidder = bid.bidder;
+        self.amount = bid.amount;
+        self.claimed = bid.claimed;
+        self.updated_at = bid.updated_at;
+        if !bid.max_hidden {
+            self.max_hash = [0u8; 32];
+        }
+    }
+}
+
+impl ReferralRecord {
+    pub const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // referrer
+        + PUBLIC_KEY_LENGTH // token mint
+        + U64_LENGTH // total earned
+        + U64_LENGTH // total claimed
+        + U64_LENGTH // referral count
+        + U8_LENGTH; // bump
+}
+
+impl HouseConfig {
+    pub const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // authority
+        + VECTOR_LENGTH_PREFIX + MAX_ADMINS * PUBLIC_KEY_LENGTH // admins
+        + PUBLIC_KEY_LENGTH // treasury
+        + U16_LENGTH // fee bps
+        + U16_LENGTH // bid referral bps
+        + U16_LENGTH // creation referral bps
+        + U8_LENGTH; // bump
+
+    pub fn is_admin(&self, key: &Pubkey) -> bool {
+        self.admins.contains(key)
+    }
+}
+
+impl ProjectConfig {
+    pub const LEN: usize = DISCRIMINATOR_LENGTH
+        + U16_LENGTH // project id
+        + PUBLIC_KEY_LENGTH // authority
+        + VECTOR_LENGTH_PREFIX + MAX_ADMINS * PUBLIC_KEY_LENGTH // admins
+        + PUBLIC_KEY_LENGTH // treasury
+        + U64_LENGTH // fee amount
+        + VECTOR_LENGTH_PREFIX + MAX_TOKEN_MINTS * PUBLIC_KEY_LENGTH // token mints
+        + U64_LENGTH // crank reward
+        + U16_LENGTH // fee bps
+        + U8_LENGTH; // bump
+
+    pub fn is_admin(&self, key: &Pubkey) -> bool {
+        self.admins.contains(key)
+    }
+
+    pub fn is_allowed_token_mint(&self, token_mint: &Pubkey) -> bool {
+        self.token_mints.contains(token_mint)
+    }
+}
+
+impl SealedAuction {
+    pub const LEN: usize = DISCRIMINATOR_LENGTH
+        + BOOL_LENGTH // first price
+        + PUBLIC_KEY_LENGTH // owner
+        + PUBLIC_KEY_LENGTH // mint
+        + U64_LENGTH // token amount
+        + U64_LENGTH // start time
+        + U64_LENGTH // end time
+        + U64_LENGTH // reveal period
+        + BOOL_LENGTH // cancelled
+        + STRING_LENGTH_PREFIX + MAX_TITLE_LENGTH // title
+        + U64_LENGTH // bidder cap
+        + PUBLIC_KEY_LENGTH // highest bidder
+        + U64_LENGTH // highest bid
+        + U64_LENGTH // second highest bid
+        + U64_LENGTH // bid floor
+        + BOOL_LENGTH // winning bid withdrawn
+        + U8_LENGTH; // bump
+
+    /// The amount the winner pays: their own bid in a first-price auction,
+    /// otherwise the second highest bid (never less than the floor).
+    pub fn winning_price(&self) -> u64 {
+        if self.first_price {
+            self.highest_bid
+        } else if self.second_highest_bid > self.bid_floor {
+            self.second_highest_bid
+        } else {
+            self.bid_floor
+        }
+    }
+}
+
+impl DutchAuction {
+    pub const LEN: usize = DISCRIMINATOR_LENGTH
+        + PUBLIC_KEY_LENGTH // owner
+        + PUBLIC_KEY_LENGTH // mint
+        + PUBLIC_KEY_LENGTH // token_mint
+        + U64_LENGTH // token amount
+        + U64_LENGTH // start time
+        + U64_LENGTH // end time
+        + BOOL_LENGTH // cancelled
+        + STRING_LENGTH_PREFIX + MAX_TITLE_LENGTH // title
+        + U64_LENGTH // start price
+        + U64_LENGTH // floor price
+        + U64_LENGTH // step interval
+        + PUBLIC_KEY_LENGTH // buyer
+        + U64_LENGTH // sale price
+        + U8_LENGTH // bump
+        + U16_LENGTH; // project_id
+
+    /// The price at `cur_time`, falling from `start_price` to `floor_price`
+    /// between the start and end time. It falls continuously when
+    /// `step_interval` is 0, otherwise once every `step_interval` seconds.
+    pub fn current_price(&self, cur_time: u64) -> u64 {
+        if cur_time <= self.start_time {
+            return self.start_price;
+        }
+        if cur_time >= self.end_time {
+            return self.floor_price;
+        }
+
+        let elapsed = cur_time - self.start_time;
+        let duration = self.end_time - self.start_time;
+        // stepwise decay counts whole steps instead of seconds
+        let (elapsed, duration) = match (
+            elapsed.checked_div(self.step_interval),
+            duration.checked_div(self.step_interval),
+        ) {
+            (Some(steps), Some(total_steps)) => (steps, total_steps),
+            _ => (elapsed, duration),
+        };
+
+        let drop =
+            (self.start_price - self.floor_price) as u128 * elapsed as u128 / duration as u128;
+        self.start_price - drop as u64
+    }
+}
@@ -2,7 +2,59 @@ use crate::account::*;
 use crate::utils::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{system_program, sysvar};
-use anchor_spl::token::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct InitializeHouseConfig<'info> {
+    #[account(init,
+        seeds=[b"house config"],
+        bump = bump,
+        payer = authority,
+        space = HouseConfig::LEN)]
+    pub config: Account<'info, HouseConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(address = get_program_data_address())]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateHouseConfig<'info> {
+    #[account(mut, seeds = [b"house config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, HouseConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(bump: u8, project_id: u16)]
+pub struct InitializeProjectConfig<'info> {
+    #[account(seeds = [b"house config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, HouseConfig>,
+    #[account(init,
+        seeds=[b"project config".as_ref(), project_id.to_le_bytes().as_ref()],
+        bump = bump,
+        payer = authority,
+        space = ProjectConfig::LEN)]
+    pub project_config: Account<'info, ProjectConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProjectConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"project config".as_ref(), project_config.project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
+        has_one = authority,
+    )]
+    pub project_config: Account<'info, ProjectConfig>,
+    pub authority: Signer<'info>,
+}
 
 #[derive(Accounts)]
 #[instruction(
@@ -13,26 +65,34 @@ use anchor_spl::token::{Mint, TokenAccount};
     start_time: u64,
     end_time: u64,
     bidder_cap: u64,
-    token_amount: u64
+    token_amount: u64,
+    project_id: u16
 )]
 pub struct CreateOpenAuction<'info> {
     #[account(init,
         seeds=[b"open auction", owner.to_account_info().key.as_ref(), name_seed(&title)],
         bump = bump,
         payer = owner,
-        space = OpenAuction::LEN +
-        VECTOR_LENGTH_PREFIX + (bidder_cap as usize)*PUBLIC_KEY_LENGTH +
-        VECTOR_LENGTH_PREFIX + (bidder_cap as usize)*U64_LENGTH)]
+        space = OpenAuction::LEN)]
     pub auction: Account<'info, OpenAuction>,
-    // #[account(mut)]
+    #[account(seeds = [b"house config"], bump = config.bump)]
+    pub config: Account<'info, HouseConfig>,
+    #[account(
+        seeds = [b"project config".as_ref(), project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
+    )]
+    pub project_config: Account<'info, ProjectConfig>,
+    #[account(mut)]
     pub auction_ata: AccountInfo<'info>,
     #[account(mut)]
     pub owner: Signer<'info>,
     #[account(mut)]
     pub owner_ata: AccountInfo<'info>,
-    pub mint: Account<'info, Mint>,
-    pub token_mint: Account<'info, Mint>,
-    #[account(address = anchor_spl::token::ID)]
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_mint.owner))]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
     pub token_program: AccountInfo<'info>,
     #[account(address = spl_associated_token_account::ID)]
     pub ata_program: AccountInfo<'info>,
@@ -52,23 +112,49 @@ pub struct CancelOpenAuction<'info> {
 }
 
 #[derive(Accounts)]
+pub struct UpdateAllowlistOpen<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, OpenAuction>,
+    #[account(seeds = [b"house config"], bump = config.bump)]
+    pub config: Account<'info, HouseConfig>,
+    #[account(
+        seeds = [b"project config".as_ref(), auction.project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
+    )]
+    pub project_config: Account<'info, ProjectConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealReserveOpen<'info> {
+    #[account(mut, has_one = owner)]
+    pub auction: Account<'info, OpenAuction>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
 pub struct MakeOpenBid<'info> {
     #[account(mut, has_one = token_mint)]
     pub auction: Account<'info, OpenAuction>,
+    #[account(init_if_needed,
+        seeds=[b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bump,
+        payer = bidder,
+        space = BidRecord::LEN)]
+    pub bid_record: Account<'info, BidRecord>,
+    #[account(mut)]
     pub auction_ata: AccountInfo<'info>,
     #[account(mut)]
     pub bidder: Signer<'info>,
-    #[account(
-        mut,
-        constraint = bidder_ata.mint == *token_mint.to_account_info().key,
-        constraint = bidder_ata.owner == *bidder.key,
-    )]
-    pub bidder_ata: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub bidder_ata: AccountInfo<'info>,
 
-    pub token_mint: Account<'info, Mint>,
+    #[account(owner = token_program.key())]
+    pub token_mint: AccountInfo<'info>,
     #[account(address = system_program::ID)]
     pub system_program: AccountInfo<'info>,
-    #[account(address = anchor_spl::token::ID)]
+    #[account(constraint = is_token_program(token_program.key))]
     pub token_program: AccountInfo<'info>,
     #[account(address = spl_associated_token_account::ID)]
     pub ata_program: AccountInfo<'info>,
@@ -80,51 +166,165 @@ pub struct MakeOpenBid<'info> {
 pub struct ReclaimOpenBid<'info> {
     #[account(mut, has_one = token_mint)]
     pub auction: Account<'info, OpenAuction>,
-    // #[account(
-    //     mut,
-    //     constraint = auction_ata.mint == *token_mint.to_account_info().key,
-    // )]
+    #[account(
+        seeds = [b"project config".as_ref(), auction.project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
+    )]
+    pub project_config: Account<'info, ProjectConfig>,
+    #[account(
+        mut,
+        seeds = [b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_record.bump,
+    )]
+    pub bid_record: Account<'info, BidRecord>,
+    #[account(mut)]
     pub auction_ata: AccountInfo<'info>,
     #[account(mut)]
     pub bidder: Signer<'info>,
+    #[account(mut)]
+    pub bidder_ata: AccountInfo<'info>,
+    #[account(mut, address = project_config.treasury)]
+    pub treasury_wallet: AccountInfo<'info>,
+
+    #[account(owner = token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RetractOpenBid<'info> {
+    #[account(mut, has_one = token_mint)]
+    pub auction: Account<'info, OpenAuction>,
     #[account(
         mut,
-        constraint = bidder_ata.mint == *token_mint.to_account_info().key,
-        constraint = bidder_ata.owner == *bidder.key,
+        seeds = [b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_record.bump,
     )]
-    pub bidder_ata: Account<'info, TokenAccount>,
+    pub bid_record: Account<'info, BidRecord>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    pub bidder: Signer<'info>,
+    #[account(mut)]
+    pub bidder_ata: AccountInfo<'info>,
+
+    #[account(owner = token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct InitializeReferral<'info> {
+    #[account(init,
+        seeds=[b"referral", referrer.key().as_ref(), token_mint.key().as_ref()],
+        bump = bump,
+        payer = referrer,
+        space = ReferralRecord::LEN)]
+    pub referral: Account<'info, ReferralRecord>,
+    #[account(mut)]
+    pub referral_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(owner = token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
     #[account(
         mut,
-        constraint = treasury_wallet.key() == TREASURY_WALLET.parse::<Pubkey>().unwrap(),
+        seeds = [b"referral", referrer.key().as_ref(), token_mint.key().as_ref()],
+        bump = referral.bump,
+        has_one = referrer,
+        has_one = token_mint,
     )]
-    pub treasury_wallet: AccountInfo<'info>,
+    pub referral: Account<'info, ReferralRecord>,
+    #[account(mut)]
+    pub referral_ata: AccountInfo<'info>,
+    pub referrer: Signer<'info>,
+    #[account(mut)]
+    pub referrer_ata: AccountInfo<'info>,
 
-    pub token_mint: Account<'info, Mint>,
-    #[account(address = anchor_spl::token::ID)]
+    #[account(owner = token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
     pub token_program: AccountInfo<'info>,
-    #[account(address = system_program::ID)]
-    pub system_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawItemOpen<'info> {
-    #[account(mut, has_one = highest_bidder.key(), has_one = mint)]
+pub struct RevealOpenBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, OpenAuction>,
+    #[account(
+        mut,
+        seeds = [b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_record.bump,
+    )]
+    pub bid_record: Account<'info, BidRecord>,
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundOpenBids<'info> {
+    #[account(mut, has_one = token_mint)]
     pub auction: Account<'info, OpenAuction>,
     #[account(
         mut,
-        constraint = auction_ata.mint == *mint.to_account_info().key,
+        seeds = [b"project config".as_ref(), auction.project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
     )]
-    pub auction_ata: Account<'info, TokenAccount>,
+    pub project_config: Account<'info, ProjectConfig>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(owner = token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseOpenBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, OpenAuction>,
+    #[account(
+        mut,
+        seeds = [b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid_record.bump,
+        close = bidder,
+    )]
+    pub bid_record: Account<'info, BidRecord>,
+    #[account(mut)]
+    pub bidder: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawItemOpen<'info> {
+    #[account(mut, has_one = highest_bidder.key(), has_one = mint)]
+    pub auction: Account<'info, OpenAuction>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
     #[account(mut)]
     pub highest_bidder: Signer<'info>,
-    // #[account(
-    //     mut,
-    //     constraint = highest_bidder_ata.mint == *mint.to_account_info().key,
-    //     constraint = highest_bidder_ata.owner == *highest_bidder.key,
-    // )]
+    #[account(mut)]
     pub highest_bidder_ata: AccountInfo<'info>,
-    pub mint: Account<'info, Mint>,
-    #[account(address = anchor_spl::token::ID)]
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
     pub token_program: AccountInfo<'info>,
     #[account(address = spl_associated_token_account::ID)]
     pub ata_program: AccountInfo<'info>,
@@ -138,44 +338,438 @@ pub struct WithdrawItemOpen<'info> {
 pub struct WithdrawWinningBidOpen<'info> {
     #[account(mut, has_one = owner, has_one = token_mint)]
     pub auction: Account<'info, OpenAuction>,
+    #[account(
+        mut,
+        seeds = [b"bid", auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump = winning_bid_record.bump,
+    )]
+    pub winning_bid_record: Account<'info, BidRecord>,
+    #[account(seeds = [b"house config"], bump = config.bump)]
+    pub config: Account<'info, HouseConfig>,
+    #[account(
+        seeds = [b"project config".as_ref(), auction.project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
+    )]
+    pub project_config: Account<'info, ProjectConfig>,
+    #[account(mut)]
+    pub house_treasury_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub project_treasury_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub bid_referral: AccountInfo<'info>,
+    #[account(mut)]
+    pub bid_referral_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub creation_referral: AccountInfo<'info>,
+    #[account(mut)]
+    pub creation_referral_ata: AccountInfo<'info>,
     #[account(mut)]
     pub owner: Signer<'info>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+
+    #[account(owner = token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleOpenAuction<'info> {
     #[account(
         mut,
-        constraint = auction_ata.mint == *token_mint.to_account_info().key,
+        has_one = owner,
+        has_one = highest_bidder,
+        has_one = mint,
+        has_one = token_mint,
     )]
-    pub auction_ata: Account<'info, TokenAccount>,
+    pub auction: Account<'info, OpenAuction>,
     #[account(
         mut,
-        constraint = owner_ata.mint == *token_mint.to_account_info().key,
-        constraint = owner_ata.owner == *owner.key,
+        seeds = [b"bid", auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump = winning_bid_record.bump,
     )]
-    pub owner_ata: Account<'info, TokenAccount>,
+    pub winning_bid_record: Account<'info, BidRecord>,
+    #[account(seeds = [b"house config"], bump = config.bump)]
+    pub config: Account<'info, HouseConfig>,
+    #[account(
+        seeds = [b"project config".as_ref(), auction.project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
+    )]
+    pub project_config: Account<'info, ProjectConfig>,
+    #[account(mut)]
+    pub house_treasury_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub project_treasury_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub bid_referral: AccountInfo<'info>,
+    #[account(mut)]
+    pub bid_referral_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub creation_referral: AccountInfo<'info>,
+    #[account(mut)]
+    pub creation_referral_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction_token_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub highest_bidder: AccountInfo<'info>,
+    #[account(mut)]
+    pub highest_bidder_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub highest_bidder_token_ata: AccountInfo<'info>,
+    pub owner: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(owner = bid_token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(constraint = is_token_program(bid_token_program.key))]
+    pub bid_token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
+}
 
-    pub token_mint: Account<'info, Mint>,
-    #[account(address = anchor_spl::token::ID)]
+#[derive(Accounts)]
+pub struct BuyNowOpen<'info> {
+    #[account(mut, has_one = owner, has_one = mint, has_one = token_mint)]
+    pub auction: Account<'info, OpenAuction>,
+    #[account(seeds = [b"house config"], bump = config.bump)]
+    pub config: Account<'info, HouseConfig>,
+    #[account(
+        seeds = [b"project config".as_ref(), auction.project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
+    )]
+    pub project_config: Account<'info, ProjectConfig>,
+    #[account(mut)]
+    pub house_treasury_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub project_treasury_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub bid_referral: AccountInfo<'info>,
+    #[account(mut)]
+    pub bid_referral_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub creation_referral: AccountInfo<'info>,
+    #[account(mut)]
+    pub creation_referral_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction_token_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut)]
+    pub buyer_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub buyer_token_ata: AccountInfo<'info>,
+    pub owner: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(owner = bid_token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
     pub token_program: AccountInfo<'info>,
+    #[account(constraint = is_token_program(bid_token_program.key))]
+    pub bid_token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
 pub struct ReclaimItemOpen<'info> {
     #[account(mut, has_one = owner.key(), has_one = mint.key())]
     pub auction: Account<'info, OpenAuction>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    bump: u8,
+    title: String,
+    floor: u64,
+    first_price: bool,
+    start_time: u64,
+    end_time: u64,
+    reveal_period: u64,
+    bidder_cap: u64,
+    token_amount: u64
+)]
+pub struct CreateSealedAuction<'info> {
+    #[account(init,
+        seeds=[b"sealed auction", owner.to_account_info().key.as_ref(), name_seed(&title)],
+        bump = bump,
+        payer = owner,
+        space = SealedAuction::LEN +
+        VECTOR_LENGTH_PREFIX + (bidder_cap as usize)*PUBLIC_KEY_LENGTH +
+        VECTOR_LENGTH_PREFIX + (bidder_cap as usize)*HASH_LENGTH +
+        VECTOR_LENGTH_PREFIX + (bidder_cap as usize)*U64_LENGTH)]
+    pub auction: Account<'info, SealedAuction>,
+    #[account(seeds = [b"house config"], bump = config.bump)]
+    pub config: Account<'info, HouseConfig>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSealedAuction<'info> {
+    #[account(mut, has_one = owner)]
+    pub auction: Account<'info, SealedAuction>,
+    pub owner: Signer<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MakeSealedBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, SealedAuction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealSealedBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, SealedAuction>,
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimSealedBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, SealedAuction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawItemSealed<'info> {
+    #[account(mut, has_one = highest_bidder.key(), has_one = mint)]
+    pub auction: Account<'info, SealedAuction>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub highest_bidder: Signer<'info>,
+    #[account(mut)]
+    pub highest_bidder_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWinningBidSealed<'info> {
+    #[account(mut, has_one = owner)]
+    pub auction: Account<'info, SealedAuction>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimItemSealed<'info> {
+    #[account(mut, has_one = owner.key(), has_one = mint.key())]
+    pub auction: Account<'info, SealedAuction>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseOpenAuction<'info> {
     #[account(
         mut,
-        constraint = auction_ata.mint == *mint.to_account_info().key,
+        has_one = owner,
+        has_one = mint,
+        has_one = token_mint,
+        close = owner,
     )]
-    pub auction_ata: Account<'info, TokenAccount>,
+    pub auction: Account<'info, OpenAuction>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction_token_ata: AccountInfo<'info>,
     #[account(mut)]
     pub owner: Signer<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(owner = bid_token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(constraint = is_token_program(bid_token_program.key))]
+    pub bid_token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    bump: u8,
+    title: String,
+    start_price: u64,
+    floor_price: u64,
+    start_time: u64,
+    end_time: u64,
+    step_interval: u64,
+    token_amount: u64,
+    project_id: u16
+)]
+pub struct CreateDutchAuction<'info> {
+    #[account(init,
+        seeds=[b"dutch auction", owner.to_account_info().key.as_ref(), name_seed(&title)],
+        bump = bump,
+        payer = owner,
+        space = DutchAuction::LEN)]
+    pub auction: Account<'info, DutchAuction>,
+    #[account(seeds = [b"house config"], bump = config.bump)]
+    pub config: Account<'info, HouseConfig>,
     #[account(
-        mut,
-        constraint = owner_ata.mint == *mint.to_account_info().key,
-        constraint = owner_ata.owner == *owner.key,
+        seeds = [b"project config".as_ref(), project_id.to_le_bytes().as_ref()],
+        bump = project_config.bump,
     )]
-    pub owner_ata: Account<'info, TokenAccount>,
-    pub mint: Account<'info, Mint>,
-    #[account(address = anchor_spl::token::ID)]
+    pub project_config: Account<'info, ProjectConfig>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_mint.owner))]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelDutchAuction<'info> {
+    #[account(mut, has_one = owner)]
+    pub auction: Account<'info, DutchAuction>,
+    pub owner: Signer<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BuyDutch<'info> {
+    #[account(mut, has_one = owner, has_one = mint, has_one = token_mint)]
+    pub auction: Account<'info, DutchAuction>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub auction_token_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut)]
+    pub buyer_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub buyer_token_ata: AccountInfo<'info>,
+    pub owner: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(owner = bid_token_program.key())]
+    pub token_mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
+    pub token_program: AccountInfo<'info>,
+    #[account(constraint = is_token_program(bid_token_program.key))]
+    pub bid_token_program: AccountInfo<'info>,
+    #[account(address = spl_associated_token_account::ID)]
+    pub ata_program: AccountInfo<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+    #[account(address = sysvar::rent::ID)]
+    pub rent_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimItemDutch<'info> {
+    #[account(mut, has_one = owner.key(), has_one = mint.key())]
+    pub auction: Account<'info, DutchAuction>,
+    #[account(mut)]
+    pub auction_ata: AccountInfo<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub owner_ata: AccountInfo<'info>,
+    #[account(owner = token_program.key())]
+    pub mint: AccountInfo<'info>,
+    #[account(constraint = is_token_program(token_program.key))]
     pub token_program: AccountInfo<'info>,
     #[account(address = spl_associated_token_account::ID)]
     pub ata_program: AccountInfo<'info>,
@@ -0,0 +1,481 @@
+//! The bidding rules of open auctions as a pure state machine.
+//!
+//! Nothing here touches accounts, CPIs or sysvars, only `core`: the handlers
+//! pass in the current time and what actually reached escrow, run one
+//! transition and then move the tokens the returned outcome describes. A
+//! transition either fails with an `AuctionError` and leaves the state as it
+//! was or applies completely.
+
+use core::cmp::{max, min};
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::error::AuctionError;
+
+/// The part of an `OpenAuction` the bidding rules read and change.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Auction {
+    pub owner: Pubkey,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub cancelled: bool,
+
+    pub extension_window: u64,
+    pub extension_amount: u64,
+    pub max_end_time: u64,
+    pub extension_count: u64,
+
+    pub has_reserve: bool,
+    pub reserve_revealed: bool,
+    pub reserve_reveal_window: u64,
+
+    pub bidder_cap: u64,
+    pub bidder_count: u64,
+    pub outstanding_bids: u64,
+
+    pub retract_cutoff: u64,
+    pub leader_can_retract: bool,
+
+    // 0 when maximums are public
+    pub max_bid_reveal_window: u64,
+
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+    pub highest_max_bid: u64,
+    // when the leader committed their hidden maximum
+    pub highest_max_bid_at: u64,
+    pub winning_bid_paid: bool,
+
+    pub bid_floor: u64,
+    pub min_bid_increment: u64,
+}
+
+/// A bidder's escrow in an auction, as kept in their `BidRecord`. The bidder
+/// is the default key until their first bid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bid {
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub claimed: bool,
+    // when the escrow last changed, which breaks ties between equal maximums
+    pub updated_at: u64,
+    // whether the escrow hides a maximum that has not been revealed
+    pub max_hidden: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BidPlaced {
+    // what was added to the bidder's escrow
+    pub amount: u64,
+    // the bidder's maximum bid
+    pub total_bid: u64,
+    pub previous_leader: Pubkey,
+    pub new_bidder: bool,
+    pub extension: Option<Extension>,
+}
+
+/// An anti-snipe extension of the end time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Extension {
+    pub previous_end_time: u64,
+    pub end_time: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reclaimed {
+    // what to send back to the bidder
+    pub amount: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settlement {
+    // what to pay out of escrow as fees, royalties and proceeds
+    pub winning_bid: u64,
+    // what the winner escrowed over the winning bid, still theirs to reclaim
+    pub excess: u64,
+}
+
+impl Auction {
+    /// Whether the owner let the reserve reveal window pass without revealing
+    /// the reserve, which voids the sale.
+    pub fn reserve_forfeited(&self, cur_time: u64) -> bool {
+        self.has_reserve
+            && !self.reserve_revealed
+            && cur_time > self.end_time.saturating_add(self.reserve_reveal_window)
+    }
+
+    /// Whether bids commit to a hidden maximum, revealed once bidding closes.
+    pub fn hides_max_bids(&self) -> bool {
+        self.max_bid_reveal_window > 0
+    }
+
+    /// Whether every hidden maximum that will be revealed has been, so the
+    /// winner is known. Always true when maximums are public.
+    pub fn max_bids_revealed(&self, cur_time: u64) -> bool {
+        !self.hides_max_bids()
+            || cur_time > self.end_time.saturating_add(self.max_bid_reveal_window)
+    }
+
+    /// Whether the bidder's escrow is still held as the winning bid.
+    pub fn is_winning_bid_locked(&self, bidder: &Pubkey, cur_time: u64) -> bool {
+        *bidder == self.highest_bidder
+            && !self.cancelled
+            && !self.winning_bid_paid
+            && !self.reserve_forfeited(cur_time)
+    }
+
+    /// The lowest bid that can open the auction.
+    pub fn min_opening_bid(&self) -> u64 {
+        max(self.bid_floor.saturating_add(1), self.min_bid_increment)
+    }
+
+    /// The lowest maximum a new bidder must escrow to take the lead.
+    pub fn min_next_bid(&self) -> Result<u64, AuctionError> {
+        if self.highest_bidder == Pubkey::default() {
+            Ok(self.min_opening_bid())
+        } else {
+            checked_add(self.highest_bid, self.min_bid_increment)
+        }
+    }
+
+    /// Whether bids can still be retracted, which closes retract_cutoff
+    /// seconds before the end time.
+    pub fn is_retraction_open(&self, cur_time: u64) -> bool {
+        self.retract_cutoff > 0 && cur_time.saturating_add(self.retract_cutoff) < self.end_time
+    }
+
+    /// The checks on a bid that come before any tokens are escrowed.
+    pub fn check_bid(&self, bidder: &Pubkey, cur_time: u64) -> Result<(), AuctionError> {
+        if self.cancelled {
+            return Err(AuctionError::AuctionCancelled);
+        }
+        if cur_time <= self.start_time {
+            return Err(AuctionError::BidBeforeStart);
+        }
+        if cur_time >= self.end_time {
+            return Err(AuctionError::BidAfterClose);
+        }
+        if *bidder == self.owner {
+            return Err(AuctionError::OwnerCannotBid);
+        }
+        Ok(())
+    }
+
+    /// Adds received to the bidder's escrow, which is their maximum bid. A
+    /// new leader's standing bid is one increment over the previous leader's
+    /// maximum, and a bid the leader's maximum covers raises the standing bid
+    /// on their behalf. When maximums are hidden the escrow only bounds the
+    /// committed maximum and nobody leads until maximums are revealed. Bids
+    /// inside the extension window push the end time out, up to max_end_time.
+    pub fn place_bid(
+        &mut self,
+        bid: &mut Bid,
+        bidder: &Pubkey,
+        received: u64,
+        cur_time: u64,
+    ) -> Result<BidPlaced, AuctionError> {
+        self.check_bid(bidder, cur_time)?;
+
+        let new_bidder = bid.bidder == Pubkey::default();
+        if new_bidder && self.bidder_count >= self.bidder_cap {
+            return Err(AuctionError::BidderCapReached);
+        }
+
+        let total_bid = checked_add(bid.amount, received)?;
+        let previous_leader = self.highest_bidder;
+        let extension = self.extension(cur_time)?;
+        if self.hides_max_bids() {
+            if total_bid <= self.bid_floor {
+                return Err(AuctionError::UnderBidFloor);
+            }
+        } else if *bidder == self.highest_bidder {
+            // the leader raises their maximum, the standing bid stays put
+            if received == 0 {
+                return Err(AuctionError::InsufficientBid);
+            }
+
+            self.highest_max_bid = total_bid;
+        } else {
+            let min_bid = self.min_next_bid()?;
+            if total_bid <= self.bid_floor {
+                return Err(AuctionError::UnderBidFloor);
+            }
+            if total_bid < min_bid {
+                return Err(AuctionError::InsufficientBid);
+            }
+
+            if total_bid > self.highest_max_bid {
+                // the new maximum beats the leader's, which sets the standing bid
+                let outbid = checked_add(self.highest_max_bid, self.min_bid_increment)?;
+                self.highest_bidder = *bidder;
+                self.highest_bid = max(min_bid, min(total_bid, outbid));
+                self.highest_max_bid = total_bid;
+            } else {
+                // the leader's maximum covers this bid, so bid for them
+                let outbid = checked_add(total_bid, self.min_bid_increment)?;
+                self.highest_bid = min(self.highest_max_bid, outbid);
+            }
+        }
+
+        if new_bidder {
+            bid.bidder = *bidder;
+            self.bidder_count += 1;
+        }
+        // a bidder who reclaimed an outbid bid can bid again
+        if new_bidder || bid.claimed {
+            bid.claimed = false;
+            self.outstanding_bids += 1;
+        }
+        bid.amount = total_bid;
+        bid.updated_at = cur_time;
+        bid.max_hidden = self.hides_max_bids();
+
+        if let Some(extension) = extension {
+            self.end_time = extension.end_time;
+            self.extension_count += 1;
+        }
+
+        Ok(BidPlaced {
+            amount: received,
+            total_bid,
+            previous_leader,
+            new_bidder,
+            extension,
+        })
+    }
+
+    /// The anti-snipe extension a bid at cur_time earns: bids inside the
+    /// extension window push the end time out, up to max_end_time.
+    fn extension(&self, cur_time: u64) -> Result<Option<Extension>, AuctionError> {
+        if self.end_time - cur_time >= self.extension_window {
+            return Ok(None);
+        }
+
+        let mut end_time = checked_add(self.end_time, self.extension_amount)?;
+        if self.max_end_time != 0 && end_time > self.max_end_time {
+            end_time = self.max_end_time;
+        }
+        Ok((end_time > self.end_time).then_some(Extension {
+            previous_end_time: self.end_time,
+            end_time,
+        }))
+    }
+
+    /// Stops bidding. Every bid, the leader's included, can then be
+    /// reclaimed.
+    pub fn cancel(&mut self, cur_time: u64) -> Result<(), AuctionError> {
+        if cur_time >= self.end_time {
+            return Err(AuctionError::CannotCancelAfterClose);
+        }
+
+        self.cancelled = true;
+        Ok(())
+    }
+
+    /// Empties the bidder's escrow, unless it is held as the winning bid.
+    pub fn reclaim(&mut self, bid: &mut Bid, cur_time: u64) -> Result<Reclaimed, AuctionError> {
+        if bid.claimed || bid.amount == 0 {
+            return Err(AuctionError::NotBidder);
+        }
+        if !self.cancelled && !self.max_bids_revealed(cur_time) {
+            return Err(AuctionError::RevealPeriodNotOver);
+        }
+        if self.is_winning_bid_locked(&bid.bidder, cur_time) {
+            return Err(AuctionError::WinnerCannotWithdrawBid);
+        }
+
+        let amount = bid.amount;
+        bid.amount = 0;
+        bid.claimed = true;
+        self.outstanding_bids -= 1;
+
+        Ok(Reclaimed { amount })
+    }
+
+    /// Opens the bidder's hidden maximum within the reveal window after the
+    /// end. The highest maximum leads, on equal maximums whoever committed
+    /// first, and the standing bid is one increment over the second highest
+    /// maximum as if every bid had been placed openly, whatever the order of
+    /// the reveals.
+    pub fn reveal_max_bid(
+        &mut self,
+        bid: &mut Bid,
+        max_bid: u64,
+        cur_time: u64,
+    ) -> Result<(), AuctionError> {
+        if self.cancelled {
+            return Err(AuctionError::AuctionCancelled);
+        }
+        if cur_time <= self.end_time {
+            return Err(AuctionError::AuctionNotOver);
+        }
+        if self.max_bids_revealed(cur_time) {
+            return Err(AuctionError::RevealPeriodOver);
+        }
+        if bid.claimed || bid.amount == 0 || !bid.max_hidden {
+            return Err(AuctionError::NoMaxBidToReveal);
+        }
+        if max_bid > bid.amount {
+            return Err(AuctionError::MaxBidOverEscrow);
+        }
+        if max_bid < self.min_opening_bid() {
+            return Err(AuctionError::UnderBidFloor);
+        }
+
+        if self.highest_bidder == Pubkey::default() {
+            self.highest_bid = self.min_opening_bid();
+            self.take_lead(bid, max_bid);
+        } else if max_bid > self.highest_max_bid
+            || (max_bid == self.highest_max_bid && bid.updated_at < self.highest_max_bid_at)
+        {
+            let outbid = checked_add(self.highest_max_bid, self.min_bid_increment)?;
+            self.highest_bid = max(self.min_opening_bid(), min(max_bid, outbid));
+            self.take_lead(bid, max_bid);
+        } else {
+            let outbid = checked_add(max_bid, self.min_bid_increment)?;
+            self.highest_bid = max(self.highest_bid, min(self.highest_max_bid, outbid));
+        }
+        bid.max_hidden = false;
+
+        Ok(())
+    }
+
+    fn take_lead(&mut self, bid: &Bid, max_bid: u64) {
+        self.highest_bidder = bid.bidder;
+        self.highest_max_bid = max_bid;
+        self.highest_max_bid_at = bid.updated_at;
+    }
+
+    /// Empties the bidder's escrow before the retract cutoff. When the leader
+    /// retracts, others must be every other outstanding bid, from which the
+    /// new leader and standing bid are recomputed as if the leader had never
+    /// bid. On equal maximums the bidder who got there first leads.
+    pub fn retract(
+        &mut self,
+        bid: &mut Bid,
+        others: &[Bid],
+        cur_time: u64,
+    ) -> Result<Reclaimed, AuctionError> {
+        if self.cancelled {
+            return Err(AuctionError::AuctionCancelled);
+        }
+        if !self.is_retraction_open(cur_time) {
+            return Err(AuctionError::RetractionClosed);
+        }
+        if bid.claimed || bid.amount == 0 {
+            return Err(AuctionError::NotBidder);
+        }
+        let outstanding_bids = checked_sub(self.outstanding_bids, 1)?;
+
+        let mut leader = (Pubkey::default(), 0, 0);
+        if bid.bidder == self.highest_bidder {
+            if !self.leader_can_retract {
+                return Err(AuctionError::LeaderCannotRetract);
+            }
+            if others.len() as u64 != outstanding_bids {
+                return Err(AuctionError::InvalidBidRecord);
+            }
+
+            let mut leading: Option<&Bid> = None;
+            let mut second_max_bid = 0;
+            for (index, other) in others.iter().enumerate() {
+                let duplicate = others[..index]
+                    .iter()
+                    .any(|seen| seen.bidder == other.bidder);
+                if other.bidder == bid.bidder
+                    || other.bidder == Pubkey::default()
+                    || other.claimed
+                    || other.amount == 0
+                    || duplicate
+                {
+                    return Err(AuctionError::InvalidBidRecord);
+                }
+
+                match leading {
+                    Some(cur)
+                        if other.amount < cur.amount
+                            || (other.amount == cur.amount
+                                && other.updated_at >= cur.updated_at) =>
+                    {
+                        second_max_bid = max(second_max_bid, other.amount);
+                    }
+                    _ => {
+                        if let Some(cur) = leading {
+                            second_max_bid = cur.amount;
+                        }
+                        leading = Some(other);
+                    }
+                }
+            }
+
+            if let Some(new_leader) = leading {
+                let outbid = max(
+                    self.min_opening_bid(),
+                    checked_add(second_max_bid, self.min_bid_increment)?,
+                );
+                leader = (
+                    new_leader.bidder,
+                    min(new_leader.amount, outbid),
+                    new_leader.amount,
+                );
+            }
+        } else {
+            leader = (self.highest_bidder, self.highest_bid, self.highest_max_bid);
+        }
+
+        let amount = bid.amount;
+        bid.amount = 0;
+        bid.claimed = true;
+        bid.updated_at = cur_time;
+        self.outstanding_bids = outstanding_bids;
+        (self.highest_bidder, self.highest_bid, self.highest_max_bid) = leader;
+
+        Ok(Reclaimed { amount })
+    }
+
+    /// Releases the standing bid out of the winner's escrow to be paid to the
+    /// owner, once the auction is over and any hidden maximums and reserve
+    /// revealed.
+    pub fn settle(&mut self, winning: &mut Bid, cur_time: u64) -> Result<Settlement, AuctionError> {
+        if self.cancelled {
+            return Err(AuctionError::AuctionCancelled);
+        }
+        if cur_time <= self.end_time {
+            return Err(AuctionError::AuctionNotOver);
+        }
+        if !self.max_bids_revealed(cur_time) {
+            return Err(AuctionError::RevealPeriodNotOver);
+        }
+        if self.has_reserve && !self.reserve_revealed {
+            return Err(AuctionError::ReserveNotRevealed);
+        }
+        if self.highest_bid == 0 {
+            return Err(AuctionError::NoWinningBid);
+        }
+        if self.winning_bid_paid {
+            return Err(AuctionError::AlreadyWithdrewBid);
+        }
+
+        let winning_bid = self.highest_bid;
+        let excess = checked_sub(winning.amount, winning_bid)?;
+        self.winning_bid_paid = true;
+        winning.amount = excess;
+        if winning.amount == 0 {
+            winning.claimed = true;
+            self.outstanding_bids -= 1;
+        }
+
+        Ok(Settlement {
+            winning_bid,
+            excess: winning.amount,
+        })
+    }
+}
+
+fn checked_add(a: u64, b: u64) -> Result<u64, AuctionError> {
+    a.checked_add(b).ok_or(AuctionError::ArithmeticOverflow)
+}
+
+fn checked_sub(a: u64, b: u64) -> Result<u64, AuctionError> {
+    a.checked_sub(b).ok_or(AuctionError::ArithmeticOverflow)
+}
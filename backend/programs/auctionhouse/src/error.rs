@@ -58,4 +58,72 @@ pub enum AuctionError {
     InsufficientSol,
     #[msg("You are not an admin.")]
     InvalidAdmin,
+    #[msg("Too many admins for the house config.")]
+    TooManyAdmins,
+    #[msg("Too many bid token mints for the project config.")]
+    TooManyTokenMints,
+    #[msg("Bid token mint is not allowed for this project.")]
+    InvalidTokenMint,
+    #[msg("Auction has already been settled.")]
+    AlreadySettled,
+    #[msg("Refund accounts must be bid records of this auction followed by their bidder's token account.")]
+    InvalidRefundAccount,
+    #[msg("Auction still holds the item, bids or escrowed tokens.")]
+    AuctionNotEmpty,
+    #[msg("Start price must be higher than the floor price.")]
+    InvalidPrice,
+    #[msg("Step interval must not be longer than the auction.")]
+    InvalidStepInterval,
+    #[msg("Item has already been sold.")]
+    AlreadySold,
+    #[msg("Buy now price must be higher than the bid floor.")]
+    InvalidBuyNowPrice,
+    #[msg("Buy now is not available for this auction.")]
+    BuyNowUnavailable,
+    #[msg("Reserve price has not been revealed.")]
+    ReserveNotRevealed,
+    #[msg("Bids can no longer be retracted.")]
+    RetractionClosed,
+    #[msg("The highest bidder cannot retract their bid.")]
+    LeaderCannotRetract,
+    #[msg("Remaining accounts must be every other outstanding bid record of this auction.")]
+    InvalidBidRecord,
+    #[msg("Bidder is not on the auction allowlist.")]
+    NotAllowlisted,
+    #[msg("Auction has already started.")]
+    AuctionAlreadyStarted,
+    #[msg("Metadata account is not the Metaplex metadata of the mint.")]
+    InvalidMetadata,
+    #[msg("Bidder does not hold an NFT of the gating collection.")]
+    NotCollectionHolder,
+    #[msg("Creator accounts must be the bid token accounts of the verified creators in metadata order.")]
+    InvalidCreatorAccount,
+    #[msg("Fees cannot be more than 10000 basis points of the winning bid.")]
+    InvalidFeeBps,
+    #[msg("Referral accounts must be the referral PDA of the referrer and its token account.")]
+    InvalidReferralAccount,
+    #[msg("No referral rewards to claim.")]
+    NothingToClaim,
+    #[msg("Bid account must be the owner's token account of the bid token mint, or their wallet for native SOL.")]
+    InvalidBidAccount,
+    #[msg("Token account must be the owner's token account of the mint.")]
+    InvalidTokenAccount,
+    #[msg("No escrow left to reclaim.")]
+    NothingToReclaim,
+    #[msg("Only the program's upgrade authority can initialize the house config.")]
+    NotUpgradeAuthority,
+    #[msg("Arithmetic overflow.")]
+    ArithmeticOverflow,
+    #[msg("Auction has no hidden reserve left to reveal.")]
+    NoReserveToReveal,
+    #[msg("Bid record still holds an escrowed bid.")]
+    BidNotReclaimed,
+    #[msg("Bid has no hidden maximum left to reveal.")]
+    NoMaxBidToReveal,
+    #[msg("Maximum bid cannot be higher than the escrowed bid.")]
+    MaxBidOverEscrow,
+    #[msg("Bids must commit to a hidden maximum exactly when the auction hides maximums.")]
+    InvalidMaxBidHash,
+    #[msg("Hidden maximums cannot be combined with a reserve or buy now price.")]
+    HiddenMaxBidConflict,
 }
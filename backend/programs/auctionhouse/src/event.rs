@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_amount: u64,
+    pub title: String,
+
+    pub start_time: u64,
+    pub end_time: u64,
+    pub max_end_time: u64,
+
+    pub bid_floor: u64,
+    pub min_bid_increment: u64,
+    pub bidder_cap: u64,
+    pub buy_now_price: u64,
+    pub max_bid_reveal_window: u64,
+
+    pub referrer: Pubkey,
+    pub project_id: u16,
+}
+
+#[event]
+pub struct SealedAuctionCreated {
+    pub auction: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_amount: u64,
+    pub title: String,
+
+    pub start_time: u64,
+    pub end_time: u64,
+    pub reveal_period: u64,
+
+    pub bid_floor: u64,
+    pub first_price: bool,
+    pub bidder_cap: u64,
+}
+
+#[event]
+pub struct DutchAuctionCreated {
+    pub auction: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_amount: u64,
+    pub title: String,
+
+    pub start_time: u64,
+    pub end_time: u64,
+
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub step_interval: u64,
+
+    pub project_id: u16,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    // the amount this bid added to the bidder's escrow
+    pub amount: u64,
+    // the bidder's maximum bid, all of their escrow
+    pub total_bid: u64,
+
+    pub previous_leader: Pubkey,
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+
+    pub end_time: u64,
+}
+
+#[event]
+pub struct SealedBidPlaced {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    // the lamports escrowed, which only bound the sealed bid
+    pub amount: u64,
+}
+
+#[event]
+pub struct SealedBidRevealed {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub bid: u64,
+
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+    pub second_highest_bid: u64,
+}
+
+#[event]
+pub struct MaxBidRevealed {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub max_bid: u64,
+
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+}
+
+#[event]
+pub struct AuctionExtended {
+    pub auction: Pubkey,
+    pub previous_end_time: u64,
+    pub end_time: u64,
+    pub extension_count: u64,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct AllowlistUpdated {
+    pub auction: Pubkey,
+    // all zero when anyone can bid
+    pub allowlist_root: [u8; 32],
+}
+
+#[event]
+pub struct ReserveRevealed {
+    pub auction: Pubkey,
+    pub reserve: u64,
+    // whether the leader's maximum covers the reserve, otherwise nobody wins
+    pub met: bool,
+}
+
+#[event]
+pub struct BidReclaimed {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    // lamports paid to the project treasury, 0 when no fee was charged
+    pub fee: u64,
+}
+
+#[event]
+pub struct ItemWithdrawn {
+    pub auction: Pubkey,
+    pub winner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WinningBidWithdrawn {
+    pub auction: Pubkey,
+    pub owner: Pubkey,
+    pub winner: Pubkey,
+    pub token_mint: Pubkey,
+    pub winning_bid: u64,
+
+    pub house_fee: u64,
+    pub bid_referral_fee: u64,
+    pub creation_referral_fee: u64,
+    pub project_fee: u64,
+    pub royalties: u64,
+    pub proceeds: u64,
+}
+
+#[event]
+pub struct ItemReclaimed {
+    pub auction: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
@@ -1,47 +1,2455 @@
 pub mod account;
 pub mod context;
+pub mod engine;
 pub mod error;
+pub mod event;
 pub mod utils;
 use account::*;
 use anchor_lang::prelude::*;
 use context::*;
 use error::*;
+use event::*;
 use utils::*;
 
 declare_id!("5JNnRQLNjzW21tJTPT6PaLnqiYN3d9jvtcCvHCfkoXuU");
 
+// handlers take every instruction argument positionally
+#[allow(clippy::too_many_arguments)]
 #[program]
 pub mod auctionhouse {
     use super::*;
+    /**
+     * @dev Initialize house config
+     * The singleton config holding the admins who can create auctions, the
+     * treasury wallet and the house fee. Only the upgrade authority of the
+     * program can sign, and becomes the config's authority.
+     * @param bump: The bump used in config
+     * @param admins: The wallets allowed to create auctions
+     * @param treasury: The wallet address to receive fees
+     * @param fee_bps: The basis points of each winning bid paid to the
+     * treasury, at most MAX_HOUSE_FEE_BPS
+     * @param bid_referral_bps: The basis points of that fee paid to the
+     * referrer of the winning bid
+     * @param creation_referral_bps: The basis points of that fee paid to the
+     * referrer of the auction
+     */
+    pub fn initialize_house_config(
+        ctx: Context<InitializeHouseConfig>,
+        bump: u8,
+        admins: Vec<Pubkey>,
+        treasury: Pubkey,
+        fee_bps: u16,
+        bid_referral_bps: u16,
+        creation_referral_bps: u16,
+    ) -> ProgramResult {
+        let config: &mut Account<HouseConfig> = &mut ctx.accounts.config;
+        let authority: &Signer = &ctx.accounts.authority;
+
+        require!(
+            ctx.accounts.program_data.upgrade_authority_address == Some(*authority.key),
+            Err(AuctionError::NotUpgradeAuthority.into())
+        );
+        require!(
+            admins.len() <= MAX_ADMINS,
+            Err(AuctionError::TooManyAdmins.into())
+        );
+        require!(
+            fee_bps <= MAX_HOUSE_FEE_BPS,
+            Err(AuctionError::InvalidFeeBps.into())
+        );
+        require!(
+            bid_referral_bps as u32 + creation_referral_bps as u32 <= MAX_BPS as u32,
+            Err(AuctionError::InvalidFeeBps.into())
+        );
+
+        config.authority = *authority.key;
+        config.admins = admins;
+        config.treasury = treasury;
+        config.fee_bps = fee_bps;
+        config.bid_referral_bps = bid_referral_bps;
+        config.creation_referral_bps = creation_referral_bps;
+        config.bump = bump;
+
+        Ok(())
+    }
+
+    /**
+     * @dev Update house config
+     * Only the config authority can replace the admins, treasury and fee or
+     * hand the config over to a new authority.
+     * @param authority: The new config authority
+     * @param admins: The wallets allowed to create auctions
+     * @param treasury: The wallet address to receive fees
+     * @param fee_bps: The basis points of each winning bid paid to the
+     * treasury, at most MAX_HOUSE_FEE_BPS
+     * @param bid_referral_bps: The basis points of that fee paid to the
+     * referrer of the winning bid
+     * @param creation_referral_bps: The basis points of that fee paid to the
+     * referrer of the auction
+     */
+    pub fn update_house_config(
+        ctx: Context<UpdateHouseConfig>,
+        authority: Pubkey,
+        admins: Vec<Pubkey>,
+        treasury: Pubkey,
+        fee_bps: u16,
+        bid_referral_bps: u16,
+        creation_referral_bps: u16,
+    ) -> ProgramResult {
+        let config: &mut Account<HouseConfig> = &mut ctx.accounts.config;
+
+        require!(
+            admins.len() <= MAX_ADMINS,
+            Err(AuctionError::TooManyAdmins.into())
+        );
+        require!(
+            fee_bps <= MAX_HOUSE_FEE_BPS,
+            Err(AuctionError::InvalidFeeBps.into())
+        );
+        require!(
+            bid_referral_bps as u32 + creation_referral_bps as u32 <= MAX_BPS as u32,
+            Err(AuctionError::InvalidFeeBps.into())
+        );
+
+        config.authority = authority;
+        config.admins = admins;
+        config.treasury = treasury;
+        config.fee_bps = fee_bps;
+        config.bid_referral_bps = bid_referral_bps;
+        config.creation_referral_bps = creation_referral_bps;
+
+        Ok(())
+    }
+
+    /**
+     * @dev Initialize project config
+     * The house authority registers a partner project with its own admins,
+     * treasury, reclaim fee and allowed bid token mints.
+     * @param bump: The bump used in project config
+     * @param project_id: The project id used by the project's auctions
+     * @param authority: The wallet allowed to update this project config
+     * @param admins: The wallets allowed to create auctions for this project
+     * @param treasury: The wallet address to receive this project's fees
+     * @param fee_amount: The lamports charged when reclaiming a bid
+     * @param token_mints: The bid token mints allowed for this project
+     * @param crank_reward: The lamports paid from the project config for each
+     * bid refunded by refund_open_bids, out of lamports sent to the config
+     * @param fee_bps: The basis points of each winning bid paid to the
+     * project treasury, at most MAX_PROJECT_FEE_BPS
+     */
+    pub fn initialize_project_config(
+        ctx: Context<InitializeProjectConfig>,
+        bump: u8,
+        project_id: u16,
+        authority: Pubkey,
+        admins: Vec<Pubkey>,
+        treasury: Pubkey,
+        fee_amount: u64,
+        token_mints: Vec<Pubkey>,
+        crank_reward: u64,
+        fee_bps: u16,
+    ) -> ProgramResult {
+        let project_config: &mut Account<ProjectConfig> = &mut ctx.accounts.project_config;
+
+        require!(
+            admins.len() <= MAX_ADMINS,
+            Err(AuctionError::TooManyAdmins.into())
+        );
+        require!(
+            fee_bps <= MAX_PROJECT_FEE_BPS,
+            Err(AuctionError::InvalidFeeBps.into())
+        );
+        require!(
+            token_mints.len() <= MAX_TOKEN_MINTS,
+            Err(AuctionError::TooManyTokenMints.into())
+        );
+
+        project_config.project_id = project_id;
+        project_config.authority = authority;
+        project_config.admins = admins;
+        project_config.treasury = treasury;
+        project_config.fee_amount = fee_amount;
+        project_config.token_mints = token_mints;
+        project_config.crank_reward = crank_reward;
+        project_config.fee_bps = fee_bps;
+        project_config.bump = bump;
+
+        Ok(())
+    }
+
+    /**
+     * @dev Update project config
+     * Only the project authority can update its config.
+     * @param authority: The new project config authority
+     * @param admins: The wallets allowed to create auctions for this project
+     * @param treasury: The wallet address to receive this project's fees
+     * @param fee_amount: The lamports charged when reclaiming a bid
+     * @param token_mints: The bid token mints allowed for this project
+     * @param crank_reward: The lamports paid from the project config for each
+     * bid refunded by refund_open_bids, out of lamports sent to the config
+     * @param fee_bps: The basis points of each winning bid paid to the
+     * project treasury, at most MAX_PROJECT_FEE_BPS
+     */
+    pub fn update_project_config(
+        ctx: Context<UpdateProjectConfig>,
+        authority: Pubkey,
+        admins: Vec<Pubkey>,
+        treasury: Pubkey,
+        fee_amount: u64,
+        token_mints: Vec<Pubkey>,
+        crank_reward: u64,
+        fee_bps: u16,
+    ) -> ProgramResult {
+        let project_config: &mut Account<ProjectConfig> = &mut ctx.accounts.project_config;
+
+        require!(
+            admins.len() <= MAX_ADMINS,
+            Err(AuctionError::TooManyAdmins.into())
+        );
+        require!(
+            fee_bps <= MAX_PROJECT_FEE_BPS,
+            Err(AuctionError::InvalidFeeBps.into())
+        );
+        require!(
+            token_mints.len() <= MAX_TOKEN_MINTS,
+            Err(AuctionError::TooManyTokenMints.into())
+        );
+
+        project_config.authority = authority;
+        project_config.admins = admins;
+        project_config.treasury = treasury;
+        project_config.fee_amount = fee_amount;
+        project_config.token_mints = token_mints;
+        project_config.crank_reward = crank_reward;
+        project_config.fee_bps = fee_bps;
+
+        Ok(())
+    }
+
     /**
      * @dev Create open auction
      * In this function the NFT is sent to the auction PDA and add main variables
-     * In the Context<CreateOpenAuction>, auction_ata and owner_ata is the NFT ata
-     * of auction and owner.
-     * mint and token_mint is mint address of NFT and bid token.
+     * In the Context<CreateOpenAuction>, auction_ata and owner_ata is the NFT ata
+     * of auction and owner.
+     * mint and token_mint is mint address of NFT and bid token. With the native
+     * mint as token_mint bids are made in SOL and escrowed as lamports in the
+     * auction PDA, and every bid token ata of the auction instructions is the
+     * wallet itself, or the auction for the auction's escrow.
+     * @param bump: The bump used in auction
+     * @param title: The auction's title
+     * @param floor: The floor price in the auction
+     * @param increment: The minimum bid increasement price
+     * @param start_time: The start time of this auction
+     * @param end_time: The end time of this auction
+     * @param bidder_cap: The Maximum bidders
+     * @param token_amount: The token amount to be auctioned
+     * @param project_id: The project id for each different project, the
+     * auction's project config must exist
+     * @param extension_window: A bid within this many seconds of the end time
+     * extends the auction
+     * @param extension_amount: The seconds added to the end time by such a bid
+     * @param max_end_time: The end time can never be extended past this, 0 for
+     * no limit
+     * @param buy_now_price: The price to buy the item immediately, 0 to
+     * disable buy now
+     * @param reserve_hash: compute_reserve_hash of the hidden reserve price
+     * and a nonce, all zero for no reserve
+     * @param reserve_reveal_window: The seconds after the end time the owner
+     * has to reveal the reserve
+     * @param retract_cutoff: Bids can be retracted until this many seconds
+     * before the end time, 0 to disable retraction
+     * @param leader_can_retract: Whether the highest bidder can retract too
+     * @param max_bid_reveal_window: When set, bids commit to a hidden maximum
+     * that bidders reveal within this many seconds after the end time, 0 for
+     * public maximums. Cannot be combined with a reserve or buy now price.
+     * @param allowlist_root: The keccak merkle root of the bidders allowed to
+     * bid, all zero to let anyone bid
+     * @param gating_collection: Only holders of a verified NFT of this
+     * collection can bid, the default pubkey to let anyone bid
+     * @param referrer: The wallet that referred this auction, the default
+     * pubkey for none
+     */
+    pub fn create_open_auction(
+        ctx: Context<CreateOpenAuction>,
+        bump: u8,
+        title: String,
+        floor: u64,
+        increment: u64,
+        start_time: u64,
+        end_time: u64,
+        bidder_cap: u64,
+        token_amount: u64,
+        project_id: u16,
+        extension_window: u64,
+        extension_amount: u64,
+        max_end_time: u64,
+        buy_now_price: u64,
+        reserve_hash: [u8; 32],
+        reserve_reveal_window: u64,
+        retract_cutoff: u64,
+        leader_can_retract: bool,
+        max_bid_reveal_window: u64,
+        allowlist_root: [u8; 32],
+        gating_collection: Pubkey,
+        referrer: Pubkey,
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let config = &ctx.accounts.config;
+        let project_config = &ctx.accounts.project_config;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let owner = &ctx.accounts.owner;
+        let owner_ata = &ctx.accounts.owner_ata;
+        let mint = &ctx.accounts.mint;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+        require!(
+            config.is_admin(owner.key) || project_config.is_admin(owner.key),
+            Err(AuctionError::InvalidAdmin.into())
+        );
+        require!(
+            project_config.is_allowed_token_mint(&token_mint.key()),
+            Err(AuctionError::InvalidTokenMint.into())
+        );
+
+        require!(
+            title.chars().count() <= 50,
+            Err(AuctionError::TitleOverflow.into())
+        );
+        require!(increment != 0, Err(AuctionError::InvalidIncrement.into()));
+        require!(
+            token_amount != 0,
+            Err(AuctionError::InvalidTokenAmount.into())
+        );
+        require!(
+            start_time < end_time,
+            Err(AuctionError::InvalidStartTime.into())
+        );
+        require!(
+            cur_time < start_time,
+            Err(AuctionError::InvalidStartTime.into())
+        );
+        require!(
+            cur_time < end_time,
+            Err(AuctionError::InvalidEndTime.into())
+        );
+        require!(floor > 0, Err(AuctionError::InvalidBidFloor.into()));
+        require!(
+            max_end_time == 0 || end_time <= max_end_time,
+            Err(AuctionError::InvalidEndTime.into())
+        );
+        require!(
+            buy_now_price == 0 || buy_now_price > floor,
+            Err(AuctionError::InvalidBuyNowPrice.into())
+        );
+        require!(
+            max_bid_reveal_window == 0 || (buy_now_price == 0 && reserve_hash == [0u8; 32]),
+            Err(AuctionError::HiddenMaxBidConflict.into())
+        );
+
+        auction.owner = *owner.key;
+        auction.mint = mint.key();
+        auction.token_mint = token_mint.key();
+
+        auction.start_time = if start_time == 0 {
+            cur_time
+        } else {
+            start_time
+        };
+        auction.end_time = end_time;
+        auction.cancelled = false;
+        auction.settled = false;
+
+        auction.extension_window = extension_window;
+        auction.extension_amount = extension_amount;
+        auction.max_end_time = max_end_time;
+        auction.extension_count = 0;
+
+        auction.buy_now_price = buy_now_price;
+
+        auction.reserve_hash = reserve_hash;
+        auction.reserve_reveal_window = reserve_reveal_window;
+        auction.reserve_price = 0;
+        auction.reserve_revealed = false;
+
+        auction.retract_cutoff = retract_cutoff;
+        auction.leader_can_retract = leader_can_retract;
+
+        auction.max_bid_reveal_window = max_bid_reveal_window;
+
+        auction.allowlist_root = allowlist_root;
+        auction.gating_collection = gating_collection;
+        // creators cannot refer themselves
+        auction.referrer = if referrer == *owner.key {
+            Pubkey::default()
+        } else {
+            referrer
+        };
+
+        auction.title = title;
+
+        auction.bidder_cap = bidder_cap;
+        auction.bidder_count = 0;
+        auction.outstanding_bids = 0;
+        auction.bid_records = 0;
+        auction.highest_bid = 0;
+        auction.highest_max_bid = 0;
+        auction.highest_max_bid_at = 0;
+        auction.winning_bid_paid = false;
+        auction.bid_floor = floor;
+        auction.min_bid_increment = increment;
+
+        auction.bump = bump;
+        auction.project_id = project_id;
+
+        create_ata(
+            owner.to_account_info(),
+            auction.to_account_info(),
+            mint.to_account_info(),
+            auction_ata.to_account_info(),
+            token_program.to_account_info(),
+            ata_program.to_account_info(),
+            system_program.to_account_info(),
+            rent_sysvar.to_account_info(),
+        )?;
+
+        // a Token-2022 transfer fee leaves less than token_amount in escrow
+        auction.token_amount = transfer_spl_received(
+            owner.to_account_info(),
+            owner_ata.to_account_info(),
+            auction_ata.to_account_info(),
+            mint.to_account_info(),
+            token_amount,
+            token_program.to_account_info(),
+            &[],
+        )?;
+
+        emit!(AuctionCreated {
+            auction: auction.key(),
+            owner: auction.owner,
+            mint: auction.mint,
+            token_mint: auction.token_mint,
+            token_amount: auction.token_amount,
+            title: auction.title.clone(),
+            start_time: auction.start_time,
+            end_time: auction.end_time,
+            max_end_time: auction.max_end_time,
+            bid_floor: auction.bid_floor,
+            min_bid_increment: auction.min_bid_increment,
+            bidder_cap: auction.bidder_cap,
+            buy_now_price: auction.buy_now_price,
+            max_bid_reveal_window: auction.max_bid_reveal_window,
+            referrer: auction.referrer,
+            project_id: auction.project_id,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Cancel Open Auction
+     * In this function the owner of the auction can cancel his auction
+     */
+    pub fn cancel_open_auction(ctx: Context<CancelOpenAuction>) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        let mut state = auction.engine();
+        state.cancel(cur_time)?;
+        auction.apply(&state);
+
+        emit!(AuctionCancelled {
+            auction: auction.key(),
+            owner: auction.owner,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Update Allowlist Open
+     * A house or project admin can rotate the allowlist root of an auction
+     * before it starts.
+     * @param allowlist_root: The new keccak merkle root of the bidders allowed
+     * to bid, all zero to let anyone bid
+     */
+    pub fn update_allowlist_open(
+        ctx: Context<UpdateAllowlistOpen>,
+        allowlist_root: [u8; 32],
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let config = &ctx.accounts.config;
+        let project_config = &ctx.accounts.project_config;
+        let admin = &ctx.accounts.admin;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            config.is_admin(admin.key) || project_config.is_admin(admin.key),
+            Err(AuctionError::InvalidAdmin.into())
+        );
+        require!(
+            !auction.cancelled,
+            Err(AuctionError::AuctionCancelled.into())
+        );
+        require!(
+            cur_time < auction.start_time,
+            Err(AuctionError::AuctionAlreadyStarted.into())
+        );
+
+        auction.allowlist_root = allowlist_root;
+
+        emit!(AllowlistUpdated {
+            auction: auction.key(),
+            allowlist_root,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Reveal Reserve Open
+     * After the auction ends the auction creator reveals the hidden reserve
+     * price within the reserve reveal window. If the leader's maximum bid is
+     * below the reserve there is no winner, so the NFT goes back to the creator and
+     * every bid can be reclaimed.
+     * @param reserve: The hidden reserve price
+     * @param nonce: The nonce the reserve was committed with in
+     * compute_reserve_hash
+     */
+    pub fn reveal_reserve_open(
+        ctx: Context<RevealReserveOpen>,
+        reserve: u64,
+        nonce: u64,
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            !auction.cancelled,
+            Err(AuctionError::AuctionCancelled.into())
+        );
+        require!(
+            auction.has_reserve() && !auction.reserve_revealed,
+            Err(AuctionError::NoReserveToReveal.into())
+        );
+        require!(
+            cur_time > auction.end_time,
+            Err(AuctionError::AuctionNotOver.into())
+        );
+        let reveal_end = auction
+            .end_time
+            .checked_add(auction.reserve_reveal_window)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        require!(
+            cur_time <= reveal_end,
+            Err(AuctionError::RevealPeriodOver.into())
+        );
+        require!(
+            compute_reserve_hash(reserve, nonce) == auction.reserve_hash,
+            Err(AuctionError::HashMismatch.into())
+        );
+
+        auction.reserve_price = reserve;
+        auction.reserve_revealed = true;
+
+        let met = auction.highest_bidder != Pubkey::default() && auction.highest_max_bid >= reserve;
+        if met {
+            // the leader's maximum covers the reserve
+            if auction.highest_bid < reserve {
+                auction.highest_bid = reserve;
+            }
+        } else {
+            auction.highest_bidder = Pubkey::default();
+            auction.highest_bid = 0;
+            auction.highest_max_bid = 0;
+        }
+
+        emit!(ReserveRevealed {
+            auction: auction.key(),
+            reserve,
+            met,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Make open auction bid
+     * In this function the spl_token(for bid) is sent to the auction PDA and
+     * add bid amount
+     * The bidder's escrow is their maximum bid. The standing bid is raised by
+     * min_bid_increment against competing bids up to the leader's maximum, and
+     * the excess escrow is refunded once the winning bid is paid.
+     * The maximum is not hidden: like any escrow it can be read on chain.
+     * In the Context<MakeOpenBid>, auction_ata and bidder_ata is the bid token
+     * ata of auction and bidder.
+     * token_mint is mint address of bid token.
+     * bid_record is the bidder's bid PDA, created on their first bid.
+     * When the auction is gated by a collection, remaining_accounts holds the
+     * bidder's token account of an NFT in the collection and its Metaplex
+     * metadata.
+     * @param bump: The bump used in bid_record
+     * @param amount: The bid amount of the user for this auction
+     * @param proof: The merkle proof of the bidder in the auction allowlist,
+     * empty when the auction has no allowlist
+     * @param referrer: The wallet that referred this bidder, the default pubkey
+     * for none. Only the referrer of the first bid is kept.
+     * @param max_hash: In auctions with hidden maximums, compute_max_bid_hash
+     * of the bidder's maximum and a nonce, which the whole escrow must cover
+     * and which replaces any earlier commitment. All zero otherwise.
+     */
+    pub fn make_open_bid(
+        ctx: Context<MakeOpenBid>,
+        bump: u8,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+        referrer: Pubkey,
+        max_hash: [u8; 32],
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let bid_record: &mut Account<BidRecord> = &mut ctx.accounts.bid_record;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let bidder: &Signer = &ctx.accounts.bidder;
+        let bidder_ata = &ctx.accounts.bidder_ata;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let system_program = &ctx.accounts.system_program;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        let mut state = auction.engine();
+        state.check_bid(bidder.key, cur_time)?;
+        require!(
+            auction.hides_max_bids() == (max_hash != [0u8; 32]),
+            Err(AuctionError::InvalidMaxBidHash.into())
+        );
+
+        assert_bid_account(bidder_ata, bidder.key, &auction.token_mint)?;
+        assert_open_bidder_allowed(auction, bidder.key, &proof, ctx.remaining_accounts)?;
+
+        if !auction.is_native() && auction_ata.data_is_empty() {
+            create_ata(
+                bidder.to_account_info(),
+                auction.to_account_info(),
+                token_mint.to_account_info(),
+                auction_ata.to_account_info(),
+                token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+        assert_bid_account(auction_ata, &auction.key(), &auction.token_mint)?;
+        // bids count what reaches escrow, net of any Token-2022 transfer fee
+        let received = transfer_bid_in(
+            bidder.to_account_info(),
+            bidder_ata.to_account_info(),
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            token_mint.to_account_info(),
+            amount,
+            auction.is_native(),
+            token_program.to_account_info(),
+            system_program.to_account_info(),
+        )?;
+
+        let mut bid = bid_record.engine();
+        let placed = state.place_bid(&mut bid, bidder.key, received, cur_time)?;
+        auction.apply(&state);
+        bid_record.apply(&bid);
+        if bid.max_hidden {
+            bid_record.max_hash = max_hash;
+        }
+
+        if placed.new_bidder {
+            bid_record.auction = auction.key();
+            bid_record.created_at = cur_time;
+            // bidders cannot refer themselves
+            if referrer != *bidder.key {
+                bid_record.referrer = referrer;
+            }
+            bid_record.bump = bump;
+            auction.bid_records = auction
+                .bid_records
+                .checked_add(1)
+                .ok_or(AuctionError::ArithmeticOverflow)?;
+        }
+
+        if let Some(extension) = placed.extension {
+            emit!(AuctionExtended {
+                auction: auction.key(),
+                previous_end_time: extension.previous_end_time,
+                end_time: extension.end_time,
+                extension_count: auction.extension_count,
+            });
+        }
+
+        emit!(BidPlaced {
+            auction: auction.key(),
+            bidder: *bidder.key,
+            amount: placed.amount,
+            total_bid: placed.total_bid,
+            previous_leader: placed.previous_leader,
+            highest_bidder: auction.highest_bidder,
+            highest_bid: auction.highest_bid,
+            end_time: auction.end_time,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Reclaim Open Bid
+     * The users who are not a winner can reclaim their bids from the PDA
+     * In the Context<ReclaimOpenBid>, auction_ata and bidder_ata is the
+     * bid token ata of auction and bidder.
+     * treasury_wallet is the project config treasury to receive cancel fee- <dev fee>.
+     */
+    pub fn reclaim_open_bid(ctx: Context<ReclaimOpenBid>) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let bid_record: &mut Account<BidRecord> = &mut ctx.accounts.bid_record;
+        let project_config = &ctx.accounts.project_config;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let bidder: &Signer = &ctx.accounts.bidder;
+        let bidder_ata = &ctx.accounts.bidder_ata;
+        let treasury_wallet = &ctx.accounts.treasury_wallet;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+        let system_program = &ctx.accounts.system_program;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        let mut state = auction.engine();
+        let mut bid = bid_record.engine();
+        let reclaimed = state.reclaim(&mut bid, cur_time)?;
+        auction.apply(&state);
+        bid_record.apply(&bid);
+
+        assert_bid_account(bidder_ata, bidder.key, &auction.token_mint)?;
+
+        transfer_bid_out(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            bidder_ata.to_account_info(),
+            token_mint.to_account_info(),
+            reclaimed.amount,
+            auction.is_native(),
+            token_program.to_account_info(),
+            &[&[
+                b"open auction",
+                auction.owner.as_ref(),
+                name_seed(&auction.title),
+                &[auction.bump],
+            ]],
+        )?;
+
+        transfer_sol(
+            bidder.to_account_info(),
+            treasury_wallet.to_account_info(),
+            project_config.fee_amount,
+            system_program.to_account_info(),
+        )?;
+
+        emit!(BidReclaimed {
+            auction: auction.key(),
+            bidder: *bidder.key,
+            amount: reclaimed.amount,
+            fee: project_config.fee_amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Retract Open Bid
+     * A bidder can pull their bid back until retract_cutoff seconds before
+     * the auction ends. The highest bidder can only retract when the auction
+     * allows it, and then remaining_accounts must hold exactly the other
+     * outstanding bid_records of this auction, not the bidder's own, so the
+     * new leader and standing bid can be recomputed.
+     * In the Context<RetractOpenBid>, auction_ata and bidder_ata is the bid
+     * token ata of auction and bidder.
+     */
+    pub fn retract_open_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, RetractOpenBid<'info>>,
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let bid_record: &mut Account<BidRecord> = &mut ctx.accounts.bid_record;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let bidder: &Signer = &ctx.accounts.bidder;
+        let bidder_ata = &ctx.accounts.bidder_ata;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        // the leader's retraction is recomputed from every other outstanding
+        // bid, which cannot include the retracting bid itself
+        let mut others: Vec<engine::Bid> = Vec::new();
+        if *bidder.key == auction.highest_bidder {
+            for info in ctx.remaining_accounts.iter() {
+                require!(
+                    *info.key != bid_record.key(),
+                    Err(AuctionError::InvalidBidRecord.into())
+                );
+                let record: Account<BidRecord> = Account::try_from(info)?;
+                require!(
+                    record.auction == auction.key() && record.bidder != *bidder.key,
+                    Err(AuctionError::InvalidBidRecord.into())
+                );
+                others.push(record.engine());
+            }
+        }
+
+        let mut state = auction.engine();
+        let mut bid = bid_record.engine();
+        let retracted = state.retract(&mut bid, &others, cur_time)?;
+        auction.apply(&state);
+        bid_record.apply(&bid);
+
+        assert_bid_account(auction_ata, &auction.key(), &auction.token_mint)?;
+        assert_bid_account(bidder_ata, bidder.key, &auction.token_mint)?;
+
+        transfer_bid_out(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            bidder_ata.to_account_info(),
+            token_mint.to_account_info(),
+            retracted.amount,
+            auction.is_native(),
+            token_program.to_account_info(),
+            &[&[
+                b"open auction",
+                auction.owner.as_ref(),
+                name_seed(&auction.title),
+                &[auction.bump],
+            ]],
+        )?;
+
+        emit!(BidReclaimed {
+            auction: auction.key(),
+            bidder: *bidder.key,
+            amount: retracted.amount,
+            fee: 0,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Initialize Referral
+     * A referrer opens their referral PDA for a bid token mint and its token
+     * account, which collect their share of the house fee on auctions and
+     * winning bids they referred. Until then the house keeps that share.
+     * @param bump: The bump used in referral
+     */
+    pub fn initialize_referral(ctx: Context<InitializeReferral>, bump: u8) -> ProgramResult {
+        let referral: &mut Account<ReferralRecord> = &mut ctx.accounts.referral;
+        let referral_ata = &ctx.accounts.referral_ata;
+        let referrer: &Signer = &ctx.accounts.referrer;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        referral.referrer = *referrer.key;
+        referral.token_mint = token_mint.key();
+        referral.total_earned = 0;
+        referral.total_claimed = 0;
+        referral.referral_count = 0;
+        referral.bump = bump;
+
+        // native SOL rewards are held by the referral PDA itself
+        if !is_native_mint(&token_mint.key()) && referral_ata.data_is_empty() {
+            create_ata(
+                referrer.to_account_info(),
+                referral.to_account_info(),
+                token_mint.to_account_info(),
+                referral_ata.to_account_info(),
+                token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * @dev Claim Referral Rewards
+     * The referrer withdraws every reward earned and not yet claimed from
+     * their referral ata.
+     * In the Context<ClaimReferralRewards>, referral_ata and referrer_ata is
+     * the bid token ata of referral and referrer.
+     */
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> ProgramResult {
+        let referral: &mut Account<ReferralRecord> = &mut ctx.accounts.referral;
+        let referral_ata = &ctx.accounts.referral_ata;
+        let referrer_ata = &ctx.accounts.referrer_ata;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+
+        let amount = referral.total_earned - referral.total_claimed;
+        require!(amount > 0, Err(AuctionError::NothingToClaim.into()));
+        assert_bid_account(referrer_ata, &referral.referrer, &referral.token_mint)?;
+
+        if is_native_mint(&referral.token_mint) {
+            transfer_from_owned_account(
+                &mut referral.to_account_info(),
+                &mut referrer_ata.to_account_info(),
+                amount,
+            )?;
+        } else {
+            assert_bid_account(referral_ata, &referral.key(), &referral.token_mint)?;
+            transfer_spl(
+                referral.to_account_info(),
+                referral_ata.to_account_info(),
+                referrer_ata.to_account_info(),
+                token_mint.to_account_info(),
+                amount,
+                token_program.to_account_info(),
+                &[&[
+                    b"referral",
+                    referral.referrer.as_ref(),
+                    referral.token_mint.as_ref(),
+                    &[referral.bump],
+                ]],
+            )?;
+        }
+
+        referral.total_claimed += amount;
+
+        Ok(())
+    }
+
+    /**
+     * @dev Reveal Open Bid
+     * In auctions with hidden maximums, bidders reveal the maximum their bid
+     * committed to within the reveal window after the end time. The winner
+     * and standing bid follow from the revealed maximums, and bids left
+     * unrevealed cannot win.
+     * @param max_bid: The hidden maximum bid
+     * @param nonce: The nonce the maximum was committed with in
+     * compute_max_bid_hash
+     */
+    pub fn reveal_open_bid(ctx: Context<RevealOpenBid>, max_bid: u64, nonce: u64) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let bid_record: &mut Account<BidRecord> = &mut ctx.accounts.bid_record;
+        let bidder: &Signer = &ctx.accounts.bidder;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        let mut state = auction.engine();
+        let mut bid = bid_record.engine();
+        state.reveal_max_bid(&mut bid, max_bid, cur_time)?;
+        require!(
+            compute_max_bid_hash(max_bid, nonce) == bid_record.max_hash,
+            Err(AuctionError::HashMismatch.into())
+        );
+        auction.apply(&state);
+        bid_record.apply(&bid);
+
+        emit!(MaxBidRevealed {
+            auction: auction.key(),
+            bidder: *bidder.key,
+            max_bid,
+            highest_bidder: auction.highest_bidder,
+            highest_bid: auction.highest_bid,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Refund Open Bids
+     * Anyone can push losing bids back to their bidders after the auction ends
+     * or is cancelled. remaining_accounts holds pairs of a bid_record of this
+     * auction and the bidder's bid token account. Bids that are already
+     * refunded are skipped, and so is the winner's until it is paid out.
+     * The cranker is paid crank_reward lamports from the project config for
+     * each refunded bid while the config holds enough lamports above rent.
+     * The project funds these rewards by transferring lamports to its project
+     * config address with a plain system transfer.
+     */
+    pub fn refund_open_bids<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefundOpenBids<'info>>,
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let project_config = &ctx.accounts.project_config;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let cranker: &Signer = &ctx.accounts.cranker;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            cur_time > auction.end_time || auction.cancelled,
+            Err(AuctionError::AuctionNotOver.into())
+        );
+        require!(
+            auction.max_bids_revealed(cur_time) || auction.cancelled,
+            Err(AuctionError::RevealPeriodNotOver.into())
+        );
+        assert_bid_account(auction_ata, &auction.key(), &auction.token_mint)?;
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            pairs.remainder().is_empty(),
+            Err(AuctionError::InvalidRefundAccount.into())
+        );
+
+        let mut refunded: u64 = 0;
+        for accounts in pairs {
+            let mut bid_record: Account<BidRecord> = Account::try_from(&accounts[0])?;
+            let bidder_ata = &accounts[1];
+
+            require!(
+                bid_record.auction == auction.key(),
+                Err(AuctionError::InvalidRefundAccount.into())
+            );
+            require!(
+                assert_bid_account(bidder_ata, &bid_record.bidder, &auction.token_mint).is_ok(),
+                Err(AuctionError::InvalidRefundAccount.into())
+            );
+            if bid_record.claimed
+                || bid_record.amount == 0
+                || auction.is_winning_bid_locked(&bid_record.bidder, cur_time)
+            {
+                continue;
+            }
+
+            let mut state = auction.engine();
+            let mut bid = bid_record.engine();
+            let reclaimed = state.reclaim(&mut bid, cur_time)?;
+            auction.apply(&state);
+            bid_record.apply(&bid);
+            bid_record.exit(ctx.program_id)?;
+
+            transfer_bid_out(
+                auction.to_account_info(),
+                auction_ata.to_account_info(),
+                bidder_ata.to_account_info(),
+                token_mint.to_account_info(),
+                reclaimed.amount,
+                auction.is_native(),
+                token_program.to_account_info(),
+                &[&[
+                    b"open auction",
+                    auction.owner.as_ref(),
+                    name_seed(&auction.title),
+                    &[auction.bump],
+                ]],
+            )?;
+
+            emit!(BidReclaimed {
+                auction: auction.key(),
+                bidder: bid_record.bidder,
+                amount: reclaimed.amount,
+                fee: 0,
+            });
+
+            refunded += 1;
+        }
+
+        let reward = project_config
+            .crank_reward
+            .checked_mul(refunded)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+        let rent_exempt = Rent::get()?.minimum_balance(ProjectConfig::LEN);
+        if reward > 0 && project_config.to_account_info().lamports() >= rent_exempt + reward {
+            transfer_from_owned_account(
+                &mut project_config.to_account_info(),
+                &mut cranker.to_account_info(),
+                reward,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * @dev Close Open Bid
+     * Anyone can close a bid record whose escrow has gone back to its bidder
+     * or to the auction creator once the auction ends or is cancelled,
+     * returning its rent to the bidder. An auction can only be closed once all
+     * of its bid records are, so that an auction recreated at the same address
+     * starts without bidders.
+     */
+    pub fn close_open_bid(ctx: Context<CloseOpenBid>) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let bid_record = &ctx.accounts.bid_record;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            cur_time > auction.end_time || auction.cancelled,
+            Err(AuctionError::AuctionNotOver.into())
+        );
+        require!(
+            bid_record.claimed || bid_record.amount == 0,
+            Err(AuctionError::BidNotReclaimed.into())
+        );
+
+        auction.bid_records = auction
+            .bid_records
+            .checked_sub(1)
+            .ok_or(AuctionError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    /**
+     * @dev Withdraw Item Open
+     * The winner can claim their winner prize- NFT. The NFT will be sent to
+     * winner from the PDA.
+     * In the Context<WithdrawItemOpen>, auction_ata and highest_bidder_ata is
+     * the NFT ata of auction and highet_bidder(winner).
+     * mint is mint address of NFT token.
+     */
+    pub fn withdraw_item_open(ctx: Context<WithdrawItemOpen>) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let winner = &ctx.accounts.highest_bidder;
+        let winner_ata = &ctx.accounts.highest_bidder_ata;
+        let mint = &ctx.accounts.mint;
+        let token_program = &ctx.accounts.token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            !auction.cancelled,
+            Err(AuctionError::AuctionCancelled.into())
+        );
+        require!(
+            cur_time > auction.end_time,
+            Err(AuctionError::AuctionNotOver.into())
+        );
+        require!(
+            auction.max_bids_revealed(cur_time),
+            Err(AuctionError::RevealPeriodNotOver.into())
+        );
+        require!(
+            !auction.has_reserve() || auction.reserve_revealed,
+            Err(AuctionError::ReserveNotRevealed.into())
+        );
+
+        assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+
+        let amount = auction.token_amount;
+
+        if winner_ata.to_account_info().data_is_empty() {
+            create_ata(
+                winner.to_account_info(),
+                winner.to_account_info(),
+                mint.to_account_info(),
+                winner_ata.to_account_info(),
+                token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+
+        transfer_spl(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            winner_ata.to_account_info(),
+            mint.to_account_info(),
+            amount,
+            token_program.to_account_info(),
+            &[&[
+                b"open auction",
+                auction.owner.as_ref(),
+                name_seed(&auction.title),
+                &[auction.bump],
+            ]],
+        )?;
+        auction.token_amount = 0;
+
+        emit!(ItemWithdrawn {
+            auction: auction.key(),
+            winner: *winner.key,
+            mint: auction.mint,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Withdraw Winning Bid Open
+     * The auction creator can withdraw winning bid from the PDA.
+     * In the Context<WithdrawWinningBidOpen>, auction_ata and ownner_ata is the
+     * bid token ata of auction and auction_owner.
+     * The house and project fee_bps of the winning bid go to house_treasury_ata
+     * and project_treasury_ata. bid_referral and creation_referral are the
+     * referral PDAs of the winning bid's and the auction's referrer, which
+     * receive their share of the house fee in their referral ata.
+     * remaining_accounts holds the NFT's Metaplex metadata and the bid token
+     * account of each verified creator, who are paid their royalty first.
+     */
+    pub fn withdraw_winning_bid_open<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawWinningBidOpen<'info>>,
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let winning_bid_record: &mut Account<BidRecord> = &mut ctx.accounts.winning_bid_record;
+        let config = &ctx.accounts.config;
+        let project_config = &ctx.accounts.project_config;
+        let house_treasury_ata = &ctx.accounts.house_treasury_ata;
+        let project_treasury_ata = &ctx.accounts.project_treasury_ata;
+        let bid_referral = &ctx.accounts.bid_referral;
+        let bid_referral_ata = &ctx.accounts.bid_referral_ata;
+        let creation_referral = &ctx.accounts.creation_referral;
+        let creation_referral_ata = &ctx.accounts.creation_referral_ata;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let owner_ata = &ctx.accounts.owner_ata;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        // the excess over the standing bid stays reclaimable by the winner
+        let mut state = auction.engine();
+        let mut bid = winning_bid_record.engine();
+        let settlement = state.settle(&mut bid, cur_time)?;
+        auction.apply(&state);
+        winning_bid_record.apply(&bid);
+
+        let winning_bid = settlement.winning_bid;
+
+        assert_bid_account(auction_ata, &auction.key(), &auction.token_mint)?;
+        let payout = pay_winning_bid(
+            auction,
+            auction_ata,
+            config,
+            project_config,
+            house_treasury_ata,
+            project_treasury_ata,
+            &winning_bid_record.referrer,
+            bid_referral,
+            bid_referral_ata,
+            creation_referral,
+            creation_referral_ata,
+            owner_ata,
+            ctx.remaining_accounts,
+            token_mint,
+            token_program,
+            winning_bid,
+        )?;
+
+        emit!(WinningBidWithdrawn {
+            auction: auction.key(),
+            owner: auction.owner,
+            winner: auction.highest_bidder,
+            token_mint: auction.token_mint,
+            winning_bid,
+            house_fee: payout.house_fee,
+            bid_referral_fee: payout.bid_referral_fee,
+            creation_referral_fee: payout.creation_referral_fee,
+            project_fee: payout.project_fee,
+            royalties: payout.royalties,
+            proceeds: payout.proceeds,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Settle Open Auction
+     * Anyone can settle an auction after it ends. The NFT is sent to the
+     * highest bidder and the winning bid to the auction creator in one
+     * transaction, skipping whichever side was already withdrawn. The winner
+     * gets back whatever they escrowed over the winning bid.
+     * In the Context<SettleOpenAuction>, auction_ata and highest_bidder_ata is
+     * the NFT ata of auction and highest_bidder, auction_token_ata, owner_ata
+     * and highest_bidder_token_ata is the bid token ata of auction,
+     * auction_owner and highest_bidder.
+     * token_program and bid_token_program are the token programs of mint and
+     * token_mint.
+     * payer pays for any ata that needs to be created.
+     * The house and project fee_bps of the winning bid go to house_treasury_ata
+     * and project_treasury_ata. bid_referral and creation_referral are the
+     * referral PDAs of the winning bid's and the auction's referrer, which
+     * receive their share of the house fee in their referral ata.
+     * remaining_accounts holds the NFT's Metaplex metadata and the bid token
+     * account of each verified creator, who are paid their royalty first.
+     */
+    pub fn settle_open_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleOpenAuction<'info>>,
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let winning_bid_record: &mut Account<BidRecord> = &mut ctx.accounts.winning_bid_record;
+        let config = &ctx.accounts.config;
+        let project_config = &ctx.accounts.project_config;
+        let house_treasury_ata = &ctx.accounts.house_treasury_ata;
+        let project_treasury_ata = &ctx.accounts.project_treasury_ata;
+        let bid_referral = &ctx.accounts.bid_referral;
+        let bid_referral_ata = &ctx.accounts.bid_referral_ata;
+        let creation_referral = &ctx.accounts.creation_referral;
+        let creation_referral_ata = &ctx.accounts.creation_referral_ata;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let auction_token_ata = &ctx.accounts.auction_token_ata;
+        let payer: &Signer = &ctx.accounts.payer;
+        let winner = &ctx.accounts.highest_bidder;
+        let winner_ata = &ctx.accounts.highest_bidder_ata;
+        let winner_token_ata = &ctx.accounts.highest_bidder_token_ata;
+        let owner = &ctx.accounts.owner;
+        let owner_ata = &ctx.accounts.owner_ata;
+        let mint = &ctx.accounts.mint;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+        let bid_token_program = &ctx.accounts.bid_token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(!auction.settled, Err(AuctionError::AlreadySettled.into()));
+
+        let mut state = auction.engine();
+        let mut bid = winning_bid_record.engine();
+        // the winning bid may already have been withdrawn by the owner
+        let settlement = if auction.winning_bid_paid {
+            None
+        } else {
+            Some(state.settle(&mut bid, cur_time)?)
+        };
+        // and the winner gets back what they escrowed over it
+        let reclaimed = if bid.claimed {
+            None
+        } else {
+            Some(state.reclaim(&mut bid, cur_time)?)
+        };
+        auction.apply(&state);
+        winning_bid_record.apply(&bid);
+
+        let seeds: &[&[u8]] = &[
+            b"open auction",
+            auction.owner.as_ref(),
+            name_seed(&auction.title),
+            &[auction.bump],
+        ];
+
+        let amount = auction.token_amount;
+        if amount > 0 {
+            assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+
+            if winner_ata.data_is_empty() {
+                create_ata(
+                    payer.to_account_info(),
+                    winner.to_account_info(),
+                    mint.to_account_info(),
+                    winner_ata.to_account_info(),
+                    token_program.to_account_info(),
+                    ata_program.to_account_info(),
+                    system_program.to_account_info(),
+                    rent_sysvar.to_account_info(),
+                )?;
+            }
+            // anyone can settle, so the item must go to the winner's account
+            assert_token_account(winner_ata, winner.key, &auction.mint)?;
+
+            transfer_spl(
+                auction.to_account_info(),
+                auction_ata.to_account_info(),
+                winner_ata.to_account_info(),
+                mint.to_account_info(),
+                amount,
+                token_program.to_account_info(),
+                &[seeds],
+            )?;
+
+            emit!(ItemWithdrawn {
+                auction: auction.key(),
+                winner: *winner.key,
+                mint: auction.mint,
+                amount,
+            });
+        }
+
+        assert_bid_account(auction_token_ata, &auction.key(), &auction.token_mint)?;
+
+        if let Some(settlement) = settlement {
+            let winning_bid = settlement.winning_bid;
+
+            if !auction.is_native() && owner_ata.data_is_empty() {
+                create_ata(
+                    payer.to_account_info(),
+                    owner.to_account_info(),
+                    token_mint.to_account_info(),
+                    owner_ata.to_account_info(),
+                    bid_token_program.to_account_info(),
+                    ata_program.to_account_info(),
+                    system_program.to_account_info(),
+                    rent_sysvar.to_account_info(),
+                )?;
+            }
+
+            let payout = pay_winning_bid(
+                auction,
+                auction_token_ata,
+                config,
+                project_config,
+                house_treasury_ata,
+                project_treasury_ata,
+                &winning_bid_record.referrer,
+                bid_referral,
+                bid_referral_ata,
+                creation_referral,
+                creation_referral_ata,
+                owner_ata,
+                ctx.remaining_accounts,
+                token_mint,
+                bid_token_program,
+                winning_bid,
+            )?;
+
+            emit!(WinningBidWithdrawn {
+                auction: auction.key(),
+                owner: auction.owner,
+                winner: auction.highest_bidder,
+                token_mint: auction.token_mint,
+                winning_bid,
+                house_fee: payout.house_fee,
+                bid_referral_fee: payout.bid_referral_fee,
+                creation_referral_fee: payout.creation_referral_fee,
+                project_fee: payout.project_fee,
+                royalties: payout.royalties,
+                proceeds: payout.proceeds,
+            });
+        }
+
+        // refund what the winner escrowed over the winning bid
+        if let Some(reclaimed) = reclaimed {
+            let excess = reclaimed.amount;
+
+            if excess > 0 {
+                if !auction.is_native() && winner_token_ata.data_is_empty() {
+                    create_ata(
+                        payer.to_account_info(),
+                        winner.to_account_info(),
+                        token_mint.to_account_info(),
+                        winner_token_ata.to_account_info(),
+                        bid_token_program.to_account_info(),
+                        ata_program.to_account_info(),
+                        system_program.to_account_info(),
+                        rent_sysvar.to_account_info(),
+                    )?;
+                }
+
+                assert_bid_account(winner_token_ata, winner.key, &auction.token_mint)?;
+                transfer_bid_out(
+                    auction.to_account_info(),
+                    auction_token_ata.to_account_info(),
+                    winner_token_ata.to_account_info(),
+                    token_mint.to_account_info(),
+                    excess,
+                    auction.is_native(),
+                    bid_token_program.to_account_info(),
+                    &[seeds],
+                )?;
+
+                emit!(BidReclaimed {
+                    auction: auction.key(),
+                    bidder: *winner.key,
+                    amount: excess,
+                    fee: 0,
+                });
+            }
+        }
+
+        auction.token_amount = 0;
+        auction.settled = true;
+
+        Ok(())
+    }
+
+    /**
+     * @dev Buy Now Open
+     * The buyer pays the buy now price and receives the NFT, which ends the
+     * auction immediately. Buy now is only available while the highest bid is
+     * below the buy now price, and only to wallets that pass the auction's
+     * allowlist and collection gate. Every existing bid can then be reclaimed
+     * or refunded.
+     * The price is escrowed and paid out like a winning bid: the house and
+     * project fee_bps go to house_treasury_ata and project_treasury_ata,
+     * bid_referral and creation_referral are the referral PDAs of the buyer's
+     * and the auction's referrer, and the verified creators get their royalty.
+     * In the Context<BuyNowOpen>, auction_ata and buyer_ata is the NFT ata of
+     * auction and buyer, auction_token_ata, buyer_token_ata and owner_ata is
+     * the bid token ata of auction, buyer and auction_owner.
+     * token_program and bid_token_program are the token programs of mint and
+     * token_mint.
+     * When the auction is gated by a collection, remaining_accounts starts
+     * with the buyer's token account of an NFT in the collection and its
+     * Metaplex metadata. It then holds the auctioned NFT's Metaplex metadata
+     * and the bid token account of each verified creator.
+     * @param proof: The merkle proof of the buyer in the auction allowlist,
+     * empty when the auction has no allowlist
+     * @param referrer: The wallet that referred this buyer, the default pubkey
+     * for none
+     */
+    pub fn buy_now_open<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyNowOpen<'info>>,
+        proof: Vec<[u8; 32]>,
+        referrer: Pubkey,
+    ) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let config = &ctx.accounts.config;
+        let project_config = &ctx.accounts.project_config;
+        let house_treasury_ata = &ctx.accounts.house_treasury_ata;
+        let project_treasury_ata = &ctx.accounts.project_treasury_ata;
+        let bid_referral = &ctx.accounts.bid_referral;
+        let bid_referral_ata = &ctx.accounts.bid_referral_ata;
+        let creation_referral = &ctx.accounts.creation_referral;
+        let creation_referral_ata = &ctx.accounts.creation_referral_ata;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let auction_token_ata = &ctx.accounts.auction_token_ata;
+        let buyer: &Signer = &ctx.accounts.buyer;
+        let buyer_ata = &ctx.accounts.buyer_ata;
+        let buyer_token_ata = &ctx.accounts.buyer_token_ata;
+        let owner = &ctx.accounts.owner;
+        let owner_ata = &ctx.accounts.owner_ata;
+        let mint = &ctx.accounts.mint;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+        let bid_token_program = &ctx.accounts.bid_token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            !auction.cancelled,
+            Err(AuctionError::AuctionCancelled.into())
+        );
+        require!(
+            cur_time > auction.start_time,
+            Err(AuctionError::BidBeforeStart.into())
+        );
+        require!(
+            cur_time < auction.end_time,
+            Err(AuctionError::BidAfterClose.into())
+        );
+        require!(
+            *buyer.key != auction.owner,
+            Err(AuctionError::OwnerCannotBid.into())
+        );
+        require!(
+            auction.buy_now_price > 0 && auction.highest_bid < auction.buy_now_price,
+            Err(AuctionError::BuyNowUnavailable.into())
+        );
+
+        let gating_len = if auction.gating_collection != Pubkey::default() {
+            ctx.remaining_accounts.len().min(2)
+        } else {
+            0
+        };
+        let (gating_accounts, creator_accounts) = ctx.remaining_accounts.split_at(gating_len);
+        assert_open_bidder_allowed(auction, buyer.key, &proof, gating_accounts)?;
+
+        if !auction.is_native() && auction_token_ata.data_is_empty() {
+            create_ata(
+                buyer.to_account_info(),
+                auction.to_account_info(),
+                token_mint.to_account_info(),
+                auction_token_ata.to_account_info(),
+                bid_token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+        if !auction.is_native() && owner_ata.data_is_empty() {
+            create_ata(
+                buyer.to_account_info(),
+                owner.to_account_info(),
+                token_mint.to_account_info(),
+                owner_ata.to_account_info(),
+                bid_token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+        if buyer_ata.data_is_empty() {
+            create_ata(
+                buyer.to_account_info(),
+                buyer.to_account_info(),
+                mint.to_account_info(),
+                buyer_ata.to_account_info(),
+                token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+
+        assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+        assert_token_account(buyer_ata, buyer.key, &auction.mint)?;
+        assert_bid_account(auction_token_ata, &auction.key(), &auction.token_mint)?;
+        assert_bid_account(buyer_token_ata, buyer.key, &auction.token_mint)?;
+        // the price counts what reaches escrow, net of any Token-2022 transfer fee
+        let price = transfer_bid_in(
+            buyer.to_account_info(),
+            buyer_token_ata.to_account_info(),
+            auction.to_account_info(),
+            auction_token_ata.to_account_info(),
+            token_mint.to_account_info(),
+            auction.buy_now_price,
+            auction.is_native(),
+            bid_token_program.to_account_info(),
+            system_program.to_account_info(),
+        )?;
+
+        // buyers cannot refer themselves
+        let bid_referrer = if referrer != *buyer.key {
+            referrer
+        } else {
+            Pubkey::default()
+        };
+        let payout = pay_winning_bid(
+            auction,
+            auction_token_ata,
+            config,
+            project_config,
+            house_treasury_ata,
+            project_treasury_ata,
+            &bid_referrer,
+            bid_referral,
+            bid_referral_ata,
+            creation_referral,
+            creation_referral_ata,
+            owner_ata,
+            creator_accounts,
+            token_mint,
+            bid_token_program,
+            price,
+        )?;
+
+        transfer_spl(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            buyer_ata.to_account_info(),
+            mint.to_account_info(),
+            auction.token_amount,
+            token_program.to_account_info(),
+            &[&[
+                b"open auction",
+                auction.owner.as_ref(),
+                name_seed(&auction.title),
+                &[auction.bump],
+            ]],
+        )?;
+
+        emit!(ItemWithdrawn {
+            auction: auction.key(),
+            winner: *buyer.key,
+            mint: auction.mint,
+            amount: auction.token_amount,
+        });
+        emit!(WinningBidWithdrawn {
+            auction: auction.key(),
+            owner: auction.owner,
+            winner: *buyer.key,
+            token_mint: auction.token_mint,
+            winning_bid: price,
+            house_fee: payout.house_fee,
+            bid_referral_fee: payout.bid_referral_fee,
+            creation_referral_fee: payout.creation_referral_fee,
+            project_fee: payout.project_fee,
+            royalties: payout.royalties,
+            proceeds: payout.proceeds,
+        });
+
+        // no bid wins, so every bid is refundable
+        auction.highest_bidder = Pubkey::default();
+        auction.highest_bid = 0;
+        auction.highest_max_bid = 0;
+        auction.token_amount = 0;
+        auction.end_time = cur_time;
+        auction.settled = true;
+
+        Ok(())
+    }
+
+    /**
+     * @dev Reclaim Item Open
+     * The auction creator can reclaim the NFT from PDA if there is no bidder and
+     * the auction is ended.
+     * In the Context<ReclaimItemOpen>, auction_ata and ownner_ata is the NFT ata
+     * of auction and auction_owner.
+     */
+    pub fn reclaim_item_open(ctx: Context<ReclaimItemOpen>) -> ProgramResult {
+        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let owner = &ctx.accounts.owner;
+        let owner_ata = &ctx.accounts.owner_ata;
+        let mint = &ctx.accounts.mint;
+        let token_program = &ctx.accounts.token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            (auction.highest_bid == 0
+                && cur_time > auction.end_time
+                && auction.max_bids_revealed(cur_time))
+                || auction.cancelled
+                || auction.reserve_forfeited(cur_time),
+            Err(AuctionError::AuctionNotOver.into())
+        );
+
+        let amount = auction.token_amount;
+
+        if owner_ata.to_account_info().data_is_empty() {
+            create_ata(
+                owner.to_account_info(),
+                owner.to_account_info(),
+                mint.to_account_info(),
+                owner_ata.to_account_info(),
+                token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+
+        assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+        assert_token_account(owner_ata, owner.key, &auction.mint)?;
+
+        transfer_spl(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            owner_ata.to_account_info(),
+            mint.to_account_info(),
+            amount,
+            token_program.to_account_info(),
+            &[&[
+                b"open auction",
+                auction.owner.as_ref(),
+                name_seed(&auction.title),
+                &[auction.bump],
+            ]],
+        )?;
+
+        auction.token_amount = 0;
+
+        emit!(ItemReclaimed {
+            auction: auction.key(),
+            owner: auction.owner,
+            mint: auction.mint,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Close Open Auction
+     * Once the item and every bid have left the PDA and every bid record has
+     * been closed with close_open_bid, the auction creator closes the NFT and
+     * bid token atas of the auction and the auction itself to reclaim their
+     * rent.
+     * In the Context<CloseOpenAuction>, auction_ata and auction_token_ata is the
+     * NFT and bid token ata of auction. auction_token_ata only exists once a bid
+     * has been made. token_program and bid_token_program are the token
+     * programs of mint and token_mint.
+     */
+    pub fn close_open_auction(ctx: Context<CloseOpenAuction>) -> ProgramResult {
+        let auction: &Account<OpenAuction> = &ctx.accounts.auction;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let auction_token_ata = &ctx.accounts.auction_token_ata;
+        let owner = &ctx.accounts.owner;
+        let mint = &ctx.accounts.mint;
+        let token_mint = &ctx.accounts.token_mint;
+        let token_program = &ctx.accounts.token_program;
+        let bid_token_program = &ctx.accounts.bid_token_program;
+
+        require!(
+            auction.token_amount == 0 && auction.outstanding_bids == 0 && auction.bid_records == 0,
+            Err(AuctionError::AuctionNotEmpty.into())
+        );
+        assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+        require!(
+            unpack_token_account(auction_ata)?.amount == 0,
+            Err(AuctionError::AuctionNotEmpty.into())
+        );
+
+        let seeds: &[&[u8]] = &[
+            b"open auction",
+            auction.owner.as_ref(),
+            name_seed(&auction.title),
+            &[auction.bump],
+        ];
+
+        close_spl_account(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            owner.to_account_info(),
+            mint.to_account_info(),
+            token_program.to_account_info(),
+            &[seeds],
+        )?;
+
+        if !auction.is_native() && !auction_token_ata.data_is_empty() {
+            assert_bid_account(auction_token_ata, &auction.key(), &auction.token_mint)?;
+            require!(
+                unpack_token_account(auction_token_ata)?.amount == 0,
+                Err(AuctionError::AuctionNotEmpty.into())
+            );
+
+            close_spl_account(
+                auction.to_account_info(),
+                auction_token_ata.to_account_info(),
+                owner.to_account_info(),
+                token_mint.to_account_info(),
+                bid_token_program.to_account_info(),
+                &[seeds],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * @dev Create sealed auction
+     * In this function the NFT is sent to the auction PDA and add main variables
+     * Bids are committed as keccak hashes with SOL escrowed alongside them and
+     * are only revealed after the auction ends.
+     * In the Context<CreateSealedAuction>, auction_ata and owner_ata is the NFT
+     * ata of auction and owner.
+     * @param bump: The bump used in auction
+     * @param title: The auction's title
+     * @param floor: The floor price in the auction
+     * @param first_price: The winner pays their own bid if true, otherwise the
+     * second highest bid
+     * @param start_time: The start time of this auction
+     * @param end_time: The end time of this auction
+     * @param reveal_period: The time until which bids can be revealed
+     * @param bidder_cap: The Maximum bidders
+     * @param token_amount: The token amount to be auctioned
+     */
+    pub fn create_sealed_auction(
+        ctx: Context<CreateSealedAuction>,
+        bump: u8,
+        title: String,
+        floor: u64,
+        first_price: bool,
+        start_time: u64,
+        end_time: u64,
+        reveal_period: u64,
+        bidder_cap: u64,
+        token_amount: u64,
+    ) -> ProgramResult {
+        let auction: &mut Account<SealedAuction> = &mut ctx.accounts.auction;
+        let config = &ctx.accounts.config;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let owner = &ctx.accounts.owner;
+        let owner_ata = &ctx.accounts.owner_ata;
+        let mint = &ctx.accounts.mint;
+        let token_program = &ctx.accounts.token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+        require!(
+            config.is_admin(owner.key),
+            Err(AuctionError::InvalidAdmin.into())
+        );
+
+        require!(
+            title.chars().count() <= 50,
+            Err(AuctionError::TitleOverflow.into())
+        );
+        require!(
+            token_amount != 0,
+            Err(AuctionError::InvalidTokenAmount.into())
+        );
+        require!(
+            start_time < end_time,
+            Err(AuctionError::InvalidStartTime.into())
+        );
+        require!(
+            cur_time < start_time,
+            Err(AuctionError::InvalidStartTime.into())
+        );
+        require!(
+            cur_time < end_time,
+            Err(AuctionError::InvalidEndTime.into())
+        );
+        require!(
+            end_time < reveal_period,
+            Err(AuctionError::InvalidRevealPeriod.into())
+        );
+        require!(floor > 0, Err(AuctionError::InvalidBidFloor.into()));
+
+        auction.first_price = first_price;
+
+        auction.owner = *owner.key;
+        auction.mint = mint.key();
+
+        auction.start_time = start_time;
+        auction.end_time = end_time;
+        auction.reveal_period = reveal_period;
+        auction.cancelled = false;
+
+        auction.title = title;
+
+        auction.bidder_cap = bidder_cap;
+        auction.highest_bid = 0;
+        auction.second_highest_bid = 0;
+        auction.bid_floor = floor;
+        auction.winning_bid_withdrawn = false;
+
+        auction.bump = bump;
+
+        create_ata(
+            owner.to_account_info(),
+            auction.to_account_info(),
+            mint.to_account_info(),
+            auction_ata.to_account_info(),
+            token_program.to_account_info(),
+            ata_program.to_account_info(),
+            system_program.to_account_info(),
+            rent_sysvar.to_account_info(),
+        )?;
+
+        // a Token-2022 transfer fee leaves less than token_amount in escrow
+        auction.token_amount = transfer_spl_received(
+            owner.to_account_info(),
+            owner_ata.to_account_info(),
+            auction_ata.to_account_info(),
+            mint.to_account_info(),
+            token_amount,
+            token_program.to_account_info(),
+            &[],
+        )?;
+
+        emit!(SealedAuctionCreated {
+            auction: auction.key(),
+            owner: auction.owner,
+            mint: auction.mint,
+            token_amount: auction.token_amount,
+            title: auction.title.clone(),
+            start_time: auction.start_time,
+            end_time: auction.end_time,
+            reveal_period: auction.reveal_period,
+            bid_floor: auction.bid_floor,
+            first_price: auction.first_price,
+            bidder_cap: auction.bidder_cap,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Cancel Sealed Auction
+     * In this function the owner of the auction can cancel his auction before
+     * it ends. Once the reveal period has started it can no longer be cancelled.
+     */
+    pub fn cancel_sealed_auction(ctx: Context<CancelSealedAuction>) -> ProgramResult {
+        let auction: &mut Account<SealedAuction> = &mut ctx.accounts.auction;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            cur_time < auction.end_time || cur_time >= auction.reveal_period,
+            Err(AuctionError::CannotCancelRevealPeriod.into())
+        );
+        require!(
+            cur_time < auction.reveal_period,
+            Err(AuctionError::CannotCancelAfterClose.into())
+        );
+
+        auction.cancelled = true;
+
+        emit!(AuctionCancelled {
+            auction: auction.key(),
+            owner: auction.owner,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Make sealed auction bid
+     * In this function the bidder commits to their bid and escrows SOL in the
+     * auction PDA. The escrowed amount can be higher than the real bid to
+     * hide it, and the difference is refunded in reclaim_sealed_bid.
+     * @param sealed_bid: compute_sealed_bid_hash of the bid and a nonce
+     * @param amount: The lamports escrowed with this bid
+     */
+    pub fn make_sealed_bid(
+        ctx: Context<MakeSealedBid>,
+        sealed_bid: [u8; 32],
+        amount: u64,
+    ) -> ProgramResult {
+        let auction: &mut Account<SealedAuction> = &mut ctx.accounts.auction;
+        let bidder: &Signer = &ctx.accounts.bidder;
+        let system_program = &ctx.accounts.system_program;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            !auction.cancelled,
+            Err(AuctionError::AuctionCancelled.into())
+        );
+        require!(
+            cur_time > auction.start_time,
+            Err(AuctionError::BidBeforeStart.into())
+        );
+        require!(
+            cur_time < auction.end_time,
+            Err(AuctionError::BidAfterClose.into())
+        );
+        require!(
+            *bidder.key != auction.owner,
+            Err(AuctionError::OwnerCannotBid.into())
+        );
+        require!(amount > 0, Err(AuctionError::MustSendSol.into()));
+        require!(
+            !auction.bidders.contains(bidder.key),
+            Err(AuctionError::DuplicateSealedBid.into())
+        );
+        require!(
+            auction.bidders.len() < (auction.bidder_cap as usize),
+            Err(AuctionError::BidderCapReached.into())
+        );
+
+        auction.bidders.push(*bidder.key);
+        auction.sealed_bids.push(sealed_bid);
+        auction.fake_bids.push(amount);
+
+        transfer_sol(
+            bidder.to_account_info(),
+            auction.to_account_info(),
+            amount,
+            system_program.to_account_info(),
+        )?;
+
+        emit!(SealedBidPlaced {
+            auction: auction.key(),
+            bidder: *bidder.key,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Reveal sealed auction bid
+     * After the auction ends and before the reveal period is over, bidders
+     * reveal their bid and nonce. The highest and second highest bids are
+     * tracked from the revealed bids.
+     * @param bid: The real bid amount in lamports
+     * @param nonce: The nonce used when the bid was sealed
+     */
+    pub fn reveal_sealed_bid(ctx: Context<RevealSealedBid>, bid: u64, nonce: u64) -> ProgramResult {
+        let auction: &mut Account<SealedAuction> = &mut ctx.accounts.auction;
+        let bidder: &Signer = &ctx.accounts.bidder;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            !auction.cancelled,
+            Err(AuctionError::AuctionCancelled.into())
+        );
+        require!(
+            cur_time > auction.end_time,
+            Err(AuctionError::AuctionNotOver.into())
+        );
+        require!(
+            cur_time < auction.reveal_period,
+            Err(AuctionError::RevealPeriodOver.into())
+        );
+
+        let index = auction
+            .bidders
+            .iter()
+            .position(|&x| x == *bidder.key)
+            .ok_or(AuctionError::NotBidder)?;
+
+        require!(
+            compute_sealed_bid_hash(bid, nonce) == auction.sealed_bids[index],
+            Err(AuctionError::HashMismatch.into())
+        );
+        require!(
+            bid <= auction.fake_bids[index],
+            Err(AuctionError::InsufficientSol.into())
+        );
+        require!(
+            bid > auction.bid_floor,
+            Err(AuctionError::UnderBidFloor.into())
+        );
+
+        // a bid can only be revealed once
+        auction.sealed_bids[index] = [0u8; 32];
+
+        if bid > auction.highest_bid {
+            auction.second_highest_bid = auction.highest_bid;
+            auction.highest_bid = bid;
+            auction.highest_bidder = *bidder.key;
+        } else if bid > auction.second_highest_bid {
+            auction.second_highest_bid = bid;
+        }
+
+        emit!(SealedBidRevealed {
+            auction: auction.key(),
+            bidder: *bidder.key,
+            bid,
+            highest_bidder: auction.highest_bidder,
+            highest_bid: auction.highest_bid,
+            second_highest_bid: auction.second_highest_bid,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Reclaim Sealed Bid
+     * After the reveal period, or once the auction is cancelled, bidders can
+     * reclaim their escrowed SOL. The winner can only reclaim the escrow in
+     * excess of the winning price.
+     */
+    pub fn reclaim_sealed_bid(ctx: Context<ReclaimSealedBid>) -> ProgramResult {
+        let auction: &mut Account<SealedAuction> = &mut ctx.accounts.auction;
+        let bidder: &Signer = &ctx.accounts.bidder;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            cur_time > auction.reveal_period || auction.cancelled,
+            Err(AuctionError::RevealPeriodNotOver.into())
+        );
+
+        let index = auction
+            .bidders
+            .iter()
+            .position(|&x| x == *bidder.key)
+            .ok_or(AuctionError::NotBidder)?;
+
+        let refund = if *bidder.key == auction.highest_bidder && !auction.cancelled {
+            let reserved = if auction.winning_bid_withdrawn {
+                0
+            } else {
+                auction.winning_price()
+            };
+            let refund = auction.fake_bids[index] - reserved;
+            auction.fake_bids[index] = reserved;
+            refund
+        } else {
+            let refund = auction.fake_bids[index];
+            auction.bidders.remove(index);
+            auction.sealed_bids.remove(index);
+            auction.fake_bids.remove(index);
+            refund
+        };
+
+        require!(refund > 0, Err(AuctionError::NothingToReclaim.into()));
+
+        transfer_from_owned_account(
+            &mut auction.to_account_info(),
+            &mut bidder.to_account_info(),
+            refund,
+        )?;
+
+        emit!(BidReclaimed {
+            auction: auction.key(),
+            bidder: *bidder.key,
+            amount: refund,
+            fee: 0,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Withdraw Item Sealed
+     * After the reveal period the winner can claim their winner prize- NFT.
+     * In the Context<WithdrawItemSealed>, auction_ata and highest_bidder_ata is
+     * the NFT ata of auction and highet_bidder(winner).
+     */
+    pub fn withdraw_item_sealed(ctx: Context<WithdrawItemSealed>) -> ProgramResult {
+        let auction: &mut Account<SealedAuction> = &mut ctx.accounts.auction;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let winner = &ctx.accounts.highest_bidder;
+        let winner_ata = &ctx.accounts.highest_bidder_ata;
+        let mint = &ctx.accounts.mint;
+        let token_program = &ctx.accounts.token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            !auction.cancelled,
+            Err(AuctionError::AuctionCancelled.into())
+        );
+        require!(
+            cur_time > auction.reveal_period,
+            Err(AuctionError::RevealPeriodNotOver.into())
+        );
+        require!(
+            auction.token_amount > 0,
+            Err(AuctionError::InvalidTokenAmount.into())
+        );
+
+        let amount = auction.token_amount;
+
+        if winner_ata.to_account_info().data_is_empty() {
+            create_ata(
+                winner.to_account_info(),
+                winner.to_account_info(),
+                mint.to_account_info(),
+                winner_ata.to_account_info(),
+                token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+
+        assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+        assert_token_account(winner_ata, winner.key, &auction.mint)?;
+
+        transfer_spl(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            winner_ata.to_account_info(),
+            mint.to_account_info(),
+            amount,
+            token_program.to_account_info(),
+            &[&[
+                b"sealed auction",
+                auction.owner.as_ref(),
+                name_seed(&auction.title),
+                &[auction.bump],
+            ]],
+        )?;
+        auction.token_amount = 0;
+
+        emit!(ItemWithdrawn {
+            auction: auction.key(),
+            winner: *winner.key,
+            mint: auction.mint,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Withdraw Winning Bid Sealed
+     * After the reveal period the auction creator can withdraw the winning
+     * price from the winner's escrowed SOL.
+     */
+    pub fn withdraw_winning_bid_sealed(ctx: Context<WithdrawWinningBidSealed>) -> ProgramResult {
+        let auction: &mut Account<SealedAuction> = &mut ctx.accounts.auction;
+        let owner: &Signer = &ctx.accounts.owner;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            !auction.cancelled,
+            Err(AuctionError::AuctionCancelled.into())
+        );
+        require!(
+            cur_time > auction.reveal_period,
+            Err(AuctionError::RevealPeriodNotOver.into())
+        );
+        require!(
+            !auction.winning_bid_withdrawn,
+            Err(AuctionError::AlreadyWithdrewBid.into())
+        );
+
+        require!(
+            auction.highest_bid > 0,
+            Err(AuctionError::NoWinningBid.into())
+        );
+        let index = auction
+            .bidders
+            .iter()
+            .position(|&x| x == auction.highest_bidder)
+            .ok_or(AuctionError::NoWinningBid)?;
+
+        let winning_price = auction.winning_price();
+        auction.fake_bids[index] -= winning_price;
+        auction.winning_bid_withdrawn = true;
+
+        transfer_from_owned_account(
+            &mut auction.to_account_info(),
+            &mut owner.to_account_info(),
+            winning_price,
+        )?;
+
+        // sealed auctions charge no fees or royalties and are paid in SOL
+        emit!(WinningBidWithdrawn {
+            auction: auction.key(),
+            owner: auction.owner,
+            winner: auction.highest_bidder,
+            token_mint: spl_token::native_mint::id(),
+            winning_bid: winning_price,
+            house_fee: 0,
+            bid_referral_fee: 0,
+            creation_referral_fee: 0,
+            project_fee: 0,
+            royalties: 0,
+            proceeds: winning_price,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Reclaim Item Sealed
+     * The auction creator can reclaim the NFT from PDA if no bid was revealed
+     * and the reveal period is over, or if the auction is cancelled.
+     * In the Context<ReclaimItemSealed>, auction_ata and ownner_ata is the NFT
+     * ata of auction and auction_owner.
+     */
+    pub fn reclaim_item_sealed(ctx: Context<ReclaimItemSealed>) -> ProgramResult {
+        let auction: &mut Account<SealedAuction> = &mut ctx.accounts.auction;
+        let auction_ata = &ctx.accounts.auction_ata;
+        let owner = &ctx.accounts.owner;
+        let owner_ata = &ctx.accounts.owner_ata;
+        let mint = &ctx.accounts.mint;
+        let token_program = &ctx.accounts.token_program;
+        let ata_program = &ctx.accounts.ata_program;
+        let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
+
+        let clock: Clock = Clock::get().unwrap();
+        let cur_time: u64 = clock.unix_timestamp as u64;
+
+        require!(
+            (auction.highest_bid == 0 && cur_time > auction.reveal_period) || auction.cancelled,
+            Err(AuctionError::AuctionNotOver.into())
+        );
+
+        let amount = auction.token_amount;
+
+        if owner_ata.to_account_info().data_is_empty() {
+            create_ata(
+                owner.to_account_info(),
+                owner.to_account_info(),
+                mint.to_account_info(),
+                owner_ata.to_account_info(),
+                token_program.to_account_info(),
+                ata_program.to_account_info(),
+                system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
+            )?;
+        }
+
+        assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+        assert_token_account(owner_ata, owner.key, &auction.mint)?;
+
+        transfer_spl(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            owner_ata.to_account_info(),
+            mint.to_account_info(),
+            amount,
+            token_program.to_account_info(),
+            &[&[
+                b"sealed auction",
+                auction.owner.as_ref(),
+                name_seed(&auction.title),
+                &[auction.bump],
+            ]],
+        )?;
+
+        auction.token_amount = 0;
+
+        emit!(ItemReclaimed {
+            auction: auction.key(),
+            owner: auction.owner,
+            mint: auction.mint,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * @dev Create dutch auction
+     * In this function the NFT is sent to the auction PDA and add main variables
+     * The price falls from start_price to floor_price between start_time and
+     * end_time and the first buyer gets the NFT at the current price.
+     * In the Context<CreateDutchAuction>, auction_ata and owner_ata is the NFT
+     * ata of auction and owner.
+     * mint and token_mint is mint address of NFT and payment token.
      * @param bump: The bump used in auction
      * @param title: The auction's title
-     * @param floor: The floor price in the auction
-     * @param increment: The minimum bid increasement price
+     * @param start_price: The price at the start time
+     * @param floor_price: The price at the end time
      * @param start_time: The start time of this auction
      * @param end_time: The end time of this auction
-     * @param bidder_cap: The Maximum bidders
+     * @param step_interval: The seconds between price drops, 0 for a linear
+     * price drop
      * @param token_amount: The token amount to be auctioned
-     * @param project_id: The project id for each different project
+     * @param project_id: The project id for each different project, the
+     * auction's project config must exist
      */
-    pub fn create_open_auction(
-        ctx: Context<CreateOpenAuction>,
+    pub fn create_dutch_auction(
+        ctx: Context<CreateDutchAuction>,
         bump: u8,
         title: String,
-        floor: u64,
-        increment: u64,
+        start_price: u64,
+        floor_price: u64,
         start_time: u64,
         end_time: u64,
-        bidder_cap: u64,
+        step_interval: u64,
         token_amount: u64,
         project_id: u16,
     ) -> ProgramResult {
-        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+        let auction: &mut Account<DutchAuction> = &mut ctx.accounts.auction;
+        let config = &ctx.accounts.config;
+        let project_config = &ctx.accounts.project_config;
         let auction_ata = &ctx.accounts.auction_ata;
         let owner = &ctx.accounts.owner;
         let owner_ata = &ctx.accounts.owner_ata;
@@ -54,19 +2462,19 @@ pub mod auctionhouse {
 
         let clock: Clock = Clock::get().unwrap();
         let cur_time: u64 = clock.unix_timestamp as u64;
-        let mut n: u64 = 0;
-        for i in 0..3 {
-            if owner.key() == ADMIN_WALLET[i].parse::<Pubkey>().unwrap() {
-                n = 1;
-            }
-        }
-        require!(n == 1, Err(AuctionError::InvalidAdmin.into()));
+        require!(
+            config.is_admin(owner.key) || project_config.is_admin(owner.key),
+            Err(AuctionError::InvalidAdmin.into())
+        );
+        require!(
+            project_config.is_allowed_token_mint(&token_mint.key()),
+            Err(AuctionError::InvalidTokenMint.into())
+        );
 
         require!(
             title.chars().count() <= 50,
             Err(AuctionError::TitleOverflow.into())
         );
-        require!(increment != 0, Err(AuctionError::InvalidIncrement.into()));
         require!(
             token_amount != 0,
             Err(AuctionError::InvalidTokenAmount.into())
@@ -79,31 +2487,32 @@ pub mod auctionhouse {
             cur_time < start_time,
             Err(AuctionError::InvalidStartTime.into())
         );
+        require!(floor_price > 0, Err(AuctionError::InvalidBidFloor.into()));
         require!(
-            cur_time < end_time,
-            Err(AuctionError::InvalidEndTime.into())
+            start_price > floor_price,
+            Err(AuctionError::InvalidPrice.into())
+        );
+        require!(
+            step_interval <= end_time - start_time,
+            Err(AuctionError::InvalidStepInterval.into())
         );
-        require!(floor > 0, Err(AuctionError::InvalidBidFloor.into()));
 
         auction.owner = *owner.key;
         auction.mint = mint.key();
         auction.token_mint = token_mint.key();
-        auction.token_amount = token_amount;
 
-        auction.start_time = if start_time == 0 {
-            cur_time
-        } else {
-            start_time
-        };
+        auction.start_time = start_time;
         auction.end_time = end_time;
         auction.cancelled = false;
 
         auction.title = title;
 
-        auction.bidder_cap = bidder_cap;
-        auction.highest_bid = 0;
-        auction.bid_floor = floor;
-        auction.min_bid_increment = increment;
+        auction.start_price = start_price;
+        auction.floor_price = floor_price;
+        auction.step_interval = step_interval;
+
+        auction.buyer = Pubkey::default();
+        auction.sale_price = 0;
 
         auction.bump = bump;
         auction.project_id = project_id;
@@ -119,24 +2528,42 @@ pub mod auctionhouse {
             rent_sysvar.to_account_info(),
         )?;
 
-        transfer_spl(
+        // a Token-2022 transfer fee leaves less than token_amount in escrow
+        auction.token_amount = transfer_spl_received(
             owner.to_account_info(),
             owner_ata.to_account_info(),
             auction_ata.to_account_info(),
+            mint.to_account_info(),
             token_amount,
             token_program.to_account_info(),
             &[],
         )?;
 
+        emit!(DutchAuctionCreated {
+            auction: auction.key(),
+            owner: auction.owner,
+            mint: auction.mint,
+            token_mint: auction.token_mint,
+            token_amount: auction.token_amount,
+            title: auction.title.clone(),
+            start_time: auction.start_time,
+            end_time: auction.end_time,
+            start_price: auction.start_price,
+            floor_price: auction.floor_price,
+            step_interval: auction.step_interval,
+            project_id: auction.project_id,
+        });
+
         Ok(())
     }
 
     /**
-     * @dev Cancel Open Auction
-     * In this function the owner of the auction can cancel his auction
+     * @dev Cancel Dutch Auction
+     * In this function the owner of the auction can cancel his auction before
+     * it is sold or ended.
      */
-    pub fn cancel_open_auction(ctx: Context<CancelOpenAuction>) -> ProgramResult {
-        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+    pub fn cancel_dutch_auction(ctx: Context<CancelDutchAuction>) -> ProgramResult {
+        let auction: &mut Account<DutchAuction> = &mut ctx.accounts.auction;
 
         let clock: Clock = Clock::get().unwrap();
         let cur_time: u64 = clock.unix_timestamp as u64;
@@ -145,32 +2572,49 @@ pub mod auctionhouse {
             cur_time < auction.end_time,
             Err(AuctionError::CannotCancelAfterClose.into())
         );
+        require!(
+            auction.sale_price == 0,
+            Err(AuctionError::AlreadySold.into())
+        );
 
         auction.cancelled = true;
 
+        emit!(AuctionCancelled {
+            auction: auction.key(),
+            owner: auction.owner,
+        });
+
         Ok(())
     }
 
     /**
-     * @dev Make open auction bid
-     * In this function the spl_token(for bid) is sent to the auction PDA and
-     * add bid amount
-     * In the Context<MakeOpenBid>, auction_ata and bidder_ata is the bid token
-     * ata of auction and bidder.
-     * token_mint is mint address of bid token.
-     * @param amount: The bid amount of the user for this auction
+     * @dev Buy Dutch
+     * The buyer pays the current price and receives the NFT. amount is taken
+     * into the auction PDA, the current price is sent to the auction creator
+     * and the rest is refunded to the buyer.
+     * In the Context<BuyDutch>, auction_ata and buyer_ata is the NFT ata of
+     * auction and buyer, auction_token_ata, buyer_token_ata and owner_ata is
+     * the payment token ata of auction, buyer and auction_owner.
+     * token_program and bid_token_program are the token programs of mint and
+     * token_mint.
+     * @param amount: The most the buyer is willing to pay
      */
-    pub fn make_open_bid(ctx: Context<MakeOpenBid>, amount: u64) -> ProgramResult {
-        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+    pub fn buy_dutch(ctx: Context<BuyDutch>, amount: u64) -> ProgramResult {
+        let auction: &mut Account<DutchAuction> = &mut ctx.accounts.auction;
         let auction_ata = &ctx.accounts.auction_ata;
-        let bidder: &Signer = &ctx.accounts.bidder;
-        let bidder_ata = &ctx.accounts.bidder_ata;
+        let auction_token_ata = &ctx.accounts.auction_token_ata;
+        let buyer: &Signer = &ctx.accounts.buyer;
+        let buyer_ata = &ctx.accounts.buyer_ata;
+        let buyer_token_ata = &ctx.accounts.buyer_token_ata;
+        let owner = &ctx.accounts.owner;
+        let owner_ata = &ctx.accounts.owner_ata;
+        let mint = &ctx.accounts.mint;
         let token_mint = &ctx.accounts.token_mint;
         let token_program = &ctx.accounts.token_program;
+        let bid_token_program = &ctx.accounts.bid_token_program;
         let ata_program = &ctx.accounts.ata_program;
-        let rent_sysvar = &ctx.accounts.rent_sysvar;
-
         let system_program = &ctx.accounts.system_program;
+        let rent_sysvar = &ctx.accounts.rent_sysvar;
 
         let clock: Clock = Clock::get().unwrap();
         let cur_time: u64 = clock.unix_timestamp as u64;
@@ -188,165 +2632,58 @@ pub mod auctionhouse {
             Err(AuctionError::BidAfterClose.into())
         );
         require!(
-            *bidder.key != auction.owner,
+            *buyer.key != auction.owner,
             Err(AuctionError::OwnerCannotBid.into())
         );
-
-        let index = auction.bidders.iter().position(|&x| x == *bidder.key);
-
-        // new amount plus already bid amount
-        let mut total_bid = amount;
-        let mut new_bidder = false;
-
-        if let None = index {
-            require!(
-                auction.bidders.len() < (auction.bidder_cap as usize),
-                Err(AuctionError::BidderCapReached.into())
-            );
-            new_bidder = true;
-        } else {
-            total_bid += auction.bids[index.unwrap()];
-        }
-
         require!(
-            total_bid > auction.bid_floor,
-            Err(AuctionError::UnderBidFloor.into())
-        );
-        require!(
-            total_bid >= (auction.highest_bid + auction.min_bid_increment),
-            Err(AuctionError::InsufficientBid.into())
+            auction.sale_price == 0,
+            Err(AuctionError::AlreadySold.into())
         );
 
-        if new_bidder {
-            auction.bidders.push(*bidder.key);
-            auction.bids.push(total_bid);
-        } else {
-            auction.bids[index.unwrap()] = total_bid;
-        }
+        let price = auction.current_price(cur_time);
+        require!(amount >= price, Err(AuctionError::InsufficientBid.into()));
 
-        if auction.end_time - cur_time < 300 {
-            auction.end_time += 300;
-        }
+        auction.buyer = *buyer.key;
+        auction.sale_price = price;
 
-        auction.highest_bidder = *bidder.key;
-        auction.highest_bid = total_bid;
+        let seeds: &[&[u8]] = &[
+            b"dutch auction",
+            auction.owner.as_ref(),
+            name_seed(&auction.title),
+            &[auction.bump],
+        ];
 
-        if auction_ata.to_account_info().data_is_empty() {
+        if auction_token_ata.data_is_empty() {
             create_ata(
-                bidder.to_account_info(),
+                buyer.to_account_info(),
                 auction.to_account_info(),
                 token_mint.to_account_info(),
-                auction_ata.to_account_info(),
-                token_program.to_account_info(),
+                auction_token_ata.to_account_info(),
+                bid_token_program.to_account_info(),
                 ata_program.to_account_info(),
                 system_program.to_account_info(),
                 rent_sysvar.to_account_info(),
             )?;
         }
-        transfer_spl(
-            bidder.to_account_info(),
-            bidder_ata.to_account_info(),
-            auction_ata.to_account_info(),
-            amount,
-            token_program.to_account_info(),
-            &[],
-        )?;
-
-        Ok(())
-    }
-
-    /**
-     * @dev Reclaim Open Bid
-     * The users who are not a winner can reclaim their bids from the PDA
-     * In the Context<ReclaimOpenBid>, auction_ata and bidder_ata is the
-     * bid token ata of auction and bidder.
-     * treasury_wallet is wallet address to receive cancel fee- <dev fee>.
-     */
-    pub fn reclaim_open_bid(ctx: Context<ReclaimOpenBid>) -> ProgramResult {
-        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
-        let bidder: &Signer = &ctx.accounts.bidder;
-        let token_program = &ctx.accounts.token_program;
-        let system_program = &ctx.accounts.system_program;
-
-        let index = auction.bidders.iter().position(|&x| x == *bidder.key);
-
-        if let None = index {
-            return Err(AuctionError::NotBidder.into());
-        } else if *bidder.key == auction.highest_bidder && !auction.cancelled {
-            return Err(AuctionError::WinnerCannotWithdrawBid.into());
-        } else {
-            let bid = auction.bids[index.unwrap()];
-            let bidder_ata = &ctx.accounts.bidder_ata;
-            let auction_ata = &ctx.accounts.auction_ata;
-            let treasury_wallet = &ctx.accounts.treasury_wallet;
-
-            auction.bidders.remove(index.unwrap());
-            auction.bids.remove(index.unwrap());
-
-            transfer_spl(
-                auction.to_account_info(),
-                auction_ata.to_account_info(),
-                bidder_ata.to_account_info(),
-                bid,
-                token_program.to_account_info(),
-                &[&[
-                    b"open auction",
-                    auction.owner.as_ref(),
-                    name_seed(&auction.title),
-                    &[auction.bump],
-                ]],
-            )?;
-
-            transfer_sol(
-                bidder.to_account_info(),
-                treasury_wallet.to_account_info(),
-                FEE_AMOUNT,
+        if owner_ata.data_is_empty() {
+            create_ata(
+                buyer.to_account_info(),
+                owner.to_account_info(),
+                token_mint.to_account_info(),
+                owner_ata.to_account_info(),
+                bid_token_program.to_account_info(),
+                ata_program.to_account_info(),
                 system_program.to_account_info(),
+                rent_sysvar.to_account_info(),
             )?;
         }
-
-        Ok(())
-    }
-
-    /**
-     * @dev Withdraw Item Open
-     * The winner can claim their winner prize- NFT. The NFT will be sent to
-     * winner from the PDA.
-     * In the Context<WithdrawItemOpen>, auction_ata and highest_bidder_ata is
-     * the NFT ata of auction and highet_bidder(winner).
-     * mint is mint address of NFT token.
-     */
-    pub fn withdraw_item_open(ctx: Context<WithdrawItemOpen>) -> ProgramResult {
-        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
-        let auction_ata = &ctx.accounts.auction_ata;
-        let winner = &ctx.accounts.highest_bidder;
-        let winner_ata = &ctx.accounts.highest_bidder_ata;
-        let mint = &ctx.accounts.mint;
-        let token_program = &ctx.accounts.token_program;
-        let ata_program = &ctx.accounts.ata_program;
-        let system_program = &ctx.accounts.system_program;
-        let rent_sysvar = &ctx.accounts.rent_sysvar;
-
-        let clock: Clock = Clock::get().unwrap();
-        let cur_time: u64 = clock.unix_timestamp as u64;
-
-        require!(
-            !auction.cancelled,
-            Err(AuctionError::AuctionCancelled.into())
-        );
-        require!(
-            cur_time > auction.end_time,
-            Err(AuctionError::AuctionNotOver.into())
-        );
-
-        let amount = auction.token_amount;
-
-        if winner_ata.to_account_info().data_is_empty() {
+        assert_token_account(owner_ata, owner.key, &auction.token_mint)?;
+        if buyer_ata.data_is_empty() {
             create_ata(
-                winner.to_account_info(),
-                winner.to_account_info(),
+                buyer.to_account_info(),
+                buyer.to_account_info(),
                 mint.to_account_info(),
-                winner_ata.to_account_info(),
+                buyer_ata.to_account_info(),
                 token_program.to_account_info(),
                 ata_program.to_account_info(),
                 system_program.to_account_info(),
@@ -354,92 +2691,90 @@ pub mod auctionhouse {
             )?;
         }
 
-        transfer_spl(
-            auction.to_account_info(),
-            auction_ata.to_account_info(),
-            winner_ata.to_account_info(),
+        assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+        assert_token_account(auction_token_ata, &auction.key(), &auction.token_mint)?;
+        assert_token_account(buyer_token_ata, buyer.key, &auction.token_mint)?;
+
+        // a Token-2022 transfer fee can leave less than amount in escrow
+        let received = transfer_spl_received(
+            buyer.to_account_info(),
+            buyer_token_ata.to_account_info(),
+            auction_token_ata.to_account_info(),
+            token_mint.to_account_info(),
             amount,
-            token_program.to_account_info(),
-            &[&[
-                b"open auction",
-                auction.owner.as_ref(),
-                name_seed(&auction.title),
-                &[auction.bump],
-            ]],
+            bid_token_program.to_account_info(),
+            &[],
         )?;
-        auction.token_amount = 0;
-
-        Ok(())
-    }
-
-    /**
-     * @dev Withdraw Winning Bid Open
-     * The auction creator can withdraw winning bid from the PDA.
-     * In the Context<WithdrawWinningBidOpen>, auction_ata and ownner_ata is the
-     * bid token ata of auction and auction_owner.
-     */
-    pub fn withdraw_winning_bid_open(ctx: Context<WithdrawWinningBidOpen>) -> ProgramResult {
-        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
-        let owner: &Signer = &ctx.accounts.owner;
-        let token_program = &ctx.accounts.token_program;
-
-        let clock: Clock = Clock::get().unwrap();
-        let cur_time: u64 = clock.unix_timestamp as u64;
-
-        require!(
-            !auction.cancelled,
-            Err(AuctionError::AuctionCancelled.into())
-        );
-        require!(
-            cur_time > auction.end_time,
-            Err(AuctionError::AuctionNotOver.into())
-        );
-
-        let index = auction
-            .bidders
-            .iter()
-            .position(|&x| x == auction.highest_bidder);
-        if let None = index {
-            return Err(AuctionError::NoWinningBid.into());
-        } else {
-            let winning_bid = auction.bids[index.unwrap()];
-
-            require!(
-                winning_bid > 0,
-                Err(AuctionError::AlreadyWithdrewBid.into())
-            );
+        require!(received >= price, Err(AuctionError::InsufficientBid.into()));
 
-            auction.bids[index.unwrap()] = 0;
+        transfer_spl(
+            auction.to_account_info(),
+            auction_token_ata.to_account_info(),
+            owner_ata.to_account_info(),
+            token_mint.to_account_info(),
+            price,
+            bid_token_program.to_account_info(),
+            &[seeds],
+        )?;
 
-            let owner_ata = &ctx.accounts.owner_ata;
-            let auction_ata = &ctx.accounts.auction_ata;
+        if received > price {
             transfer_spl(
                 auction.to_account_info(),
-                auction_ata.to_account_info(),
-                owner_ata.to_account_info(),
-                winning_bid,
-                token_program.to_account_info(),
-                &[&[
-                    b"open auction",
-                    auction.owner.as_ref(),
-                    name_seed(&auction.title),
-                    &[auction.bump],
-                ]],
+                auction_token_ata.to_account_info(),
+                buyer_token_ata.to_account_info(),
+                token_mint.to_account_info(),
+                received - price,
+                bid_token_program.to_account_info(),
+                &[seeds],
             )?;
         }
 
+        let token_amount = auction.token_amount;
+        transfer_spl(
+            auction.to_account_info(),
+            auction_ata.to_account_info(),
+            buyer_ata.to_account_info(),
+            mint.to_account_info(),
+            token_amount,
+            token_program.to_account_info(),
+            &[seeds],
+        )?;
+
+        auction.token_amount = 0;
+
+        // dutch auctions charge no fees or royalties
+        emit!(ItemWithdrawn {
+            auction: auction.key(),
+            winner: *buyer.key,
+            mint: auction.mint,
+            amount: token_amount,
+        });
+        emit!(WinningBidWithdrawn {
+            auction: auction.key(),
+            owner: auction.owner,
+            winner: *buyer.key,
+            token_mint: auction.token_mint,
+            winning_bid: price,
+            house_fee: 0,
+            bid_referral_fee: 0,
+            creation_referral_fee: 0,
+            project_fee: 0,
+            royalties: 0,
+            proceeds: price,
+        });
+
         Ok(())
     }
 
     /**
-     * @dev Reclaim Item Open
-     * The auction creator can reclaim the NFT from PDA if there is no bidder and
-     * the auction is ended.
-     * In the Context<ReclaimItemOpen>, auction_ata and ownner_ata is the NFT ata
-     * of auction and auction_owner.
+     * @dev Reclaim Item Dutch
+     * The auction creator can reclaim the NFT from PDA if nobody bought it
+     * before the end time, or if the auction is cancelled.
+     * In the Context<ReclaimItemDutch>, auction_ata and ownner_ata is the NFT
+     * ata of auction and auction_owner.
      */
-    pub fn reclaim_item_open(ctx: Context<ReclaimItemOpen>) -> ProgramResult {
-        let auction: &mut Account<OpenAuction> = &mut ctx.accounts.auction;
+    pub fn reclaim_item_dutch(ctx: Context<ReclaimItemDutch>) -> ProgramResult {
+        let auction: &mut Account<DutchAuction> = &mut ctx.accounts.auction;
         let auction_ata = &ctx.accounts.auction_ata;
         let owner = &ctx.accounts.owner;
         let owner_ata = &ctx.accounts.owner_ata;
@@ -453,7 +2788,11 @@ pub mod auctionhouse {
         let cur_time: u64 = clock.unix_timestamp as u64;
 
         require!(
-            (auction.highest_bid == 0 && cur_time > auction.end_time) || auction.cancelled,
+            auction.sale_price == 0,
+            Err(AuctionError::AlreadySold.into())
+        );
+        require!(
+            cur_time > auction.end_time || auction.cancelled,
             Err(AuctionError::AuctionNotOver.into())
         );
 
@@ -471,15 +2810,18 @@ pub mod auctionhouse {
                 rent_sysvar.to_account_info(),
             )?;
         }
+        assert_token_account(auction_ata, &auction.key(), &auction.mint)?;
+        assert_token_account(owner_ata, owner.key, &auction.mint)?;
 
         transfer_spl(
             auction.to_account_info(),
             auction_ata.to_account_info(),
             owner_ata.to_account_info(),
+            mint.to_account_info(),
             amount,
             token_program.to_account_info(),
             &[&[
-                b"open auction",
+                b"dutch auction",
                 auction.owner.as_ref(),
                 name_seed(&auction.title),
                 &[auction.bump],
@@ -488,6 +2830,13 @@ pub mod auctionhouse {
 
         auction.token_amount = 0;
 
+        emit!(ItemReclaimed {
+            auction: auction.key(),
+            owner: auction.owner,
+            mint: auction.mint,
+            amount,
+        });
+
         Ok(())
     }
 }
@@ -1,16 +1,44 @@
+#![allow(clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{
-    program::invoke, program::invoke_signed, system_instruction::transfer,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    program::invoke_signed,
+    system_instruction::transfer,
+};
+use mpl_token_metadata::{pda::find_metadata_account, state::Metadata};
+use spl_token_2022::extension::{
+    transfer_fee::{instruction::harvest_withheld_tokens_to_mint, TransferFeeAmount},
+    StateWithExtensions,
 };
 use tiny_keccak::{Hasher, Keccak};
 
-pub const TREASURY_WALLET: &str = "32NL69SFk8GLPFZfKQwsuexcXHd7rqAQn1mrasF1ksVj";
-pub const ADMIN_WALLET: [&str; 3] = [
-    "Fe4KejEc1pgo6MxjfRGYL1u5qMpYN7FMxPKYjbrdsFFE",
-    "32NL69SFk8GLPFZfKQwsuexcXHd7rqAQn1mrasF1ksVj",
-    "6dWYBATRHmnqn73WwAVnWgUYPniB5HJt8vzXbdTcBJfJ",
-];
-pub const FEE_AMOUNT: u64 = 25000000;
+use crate::account::{HouseConfig, OpenAuction, ProjectConfig, ReferralRecord, MAX_BPS};
+use crate::error::*;
+
+pub fn is_token_program(key: &Pubkey) -> bool {
+    *key == spl_token::id() || *key == spl_token_2022::id()
+}
+
+/// The upgradeable loader's data account of this program, which holds its
+/// upgrade authority.
+pub fn get_program_data_address() -> Pubkey {
+    Pubkey::find_program_address(
+        &[crate::ID.as_ref()],
+        &anchor_lang::solana_program::bpf_loader_upgradeable::id(),
+    )
+    .0
+}
+
+/// The associated token account of wallet for a mint of either token program.
+pub fn get_ata_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &spl_associated_token_account::id(),
+    )
+    .0
+}
 
 pub fn create_ata<'info>(
     payer: AccountInfo<'info>,
@@ -22,12 +50,22 @@ pub fn create_ata<'info>(
     system_program: AccountInfo<'info>,
     rent_sysvar: AccountInfo<'info>,
 ) -> ProgramResult {
+    // the ata program derives the address from the token program passed in
+    let ix = Instruction {
+        program_id: spl_associated_token_account::id(),
+        accounts: vec![
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new(ata.key(), false),
+            AccountMeta::new_readonly(wallet.key(), false),
+            AccountMeta::new_readonly(mint.key(), false),
+            AccountMeta::new_readonly(system_program.key(), false),
+            AccountMeta::new_readonly(token_program.key(), false),
+            AccountMeta::new_readonly(rent_sysvar.key(), false),
+        ],
+        data: vec![],
+    };
     invoke_signed(
-        &spl_associated_token_account::create_associated_token_account(
-            &payer.key(),
-            &wallet.key(),
-            &mint.key(),
-        ),
+        &ix,
         &[
             ata,
             wallet,
@@ -48,22 +86,41 @@ pub fn transfer_spl<'info>(
     src: AccountInfo<'info>,
     src_ata: AccountInfo<'info>,
     dst_ata: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
     amount: u64,
     token_program: AccountInfo<'info>,
     signer_seeds: &[&[&[u8]]],
 ) -> ProgramResult {
-    invoke_signed(
-        &spl_token::instruction::transfer(
+    let decimals = get_mint_decimals(&mint)?;
+    let ix = if *token_program.key == spl_token_2022::id() {
+        spl_token_2022::instruction::transfer_checked(
             &token_program.key(),
             &src_ata.key(),
+            &mint.key(),
             &dst_ata.key(),
             &src.key(),
             &[],
             amount,
-        )?,
+            decimals,
+        )?
+    } else {
+        spl_token::instruction::transfer_checked(
+            &token_program.key(),
+            &src_ata.key(),
+            &mint.key(),
+            &dst_ata.key(),
+            &src.key(),
+            &[],
+            amount,
+            decimals,
+        )?
+    };
+    invoke_signed(
+        &ix,
         &[
             src.to_account_info(),
             src_ata.to_account_info(),
+            mint.to_account_info(),
             dst_ata.to_account_info(),
             token_program.to_account_info(),
         ],
@@ -73,6 +130,74 @@ pub fn transfer_spl<'info>(
     Ok(())
 }
 
+/// Transfers like transfer_spl, returning the amount dst_ata actually
+/// received, which is less than amount when a Token-2022 transfer fee is
+/// withheld.
+pub fn transfer_spl_received<'info>(
+    src: AccountInfo<'info>,
+    src_ata: AccountInfo<'info>,
+    dst_ata: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    amount: u64,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<u64, ProgramError> {
+    let before = unpack_token_account(&dst_ata)?.amount;
+    transfer_spl(
+        src,
+        src_ata,
+        dst_ata.clone(),
+        mint,
+        amount,
+        token_program,
+        signer_seeds,
+    )?;
+    let after = unpack_token_account(&dst_ata)?.amount;
+
+    Ok(after - before)
+}
+
+pub fn close_spl_account<'info>(
+    authority: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    dst: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    // Token-2022 refuses to close an account still holding withheld transfer
+    // fees, so sweep them to the mint first
+    if *token_program.key == spl_token_2022::id() && get_withheld_amount(&account)? > 0 {
+        invoke(
+            &harvest_withheld_tokens_to_mint(&token_program.key(), &mint.key(), &[account.key])?,
+            &[
+                mint.to_account_info(),
+                account.to_account_info(),
+                token_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    invoke_signed(
+        &spl_token::instruction::close_account(
+            &token_program.key(),
+            &account.key(),
+            &dst.key(),
+            &authority.key(),
+            &[],
+        )?,
+        &[
+            account.to_account_info(),
+            dst.to_account_info(),
+            authority.to_account_info(),
+            token_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
 // transfer from system-owned account
 pub fn transfer_sol<'info>(
     src: AccountInfo<'info>,
@@ -112,13 +237,546 @@ pub fn transfer_from_owned_account(
     Ok(())
 }
 
-pub fn compute_bid_hash(bid: u64, nonce: u64) -> [u8; 32] {
-    let mut new_hash = [0u8; 32];
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::id()
+}
+
+pub fn get_mint_decimals(mint: &AccountInfo) -> std::result::Result<u8, ProgramError> {
+    if !is_token_program(mint.owner) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = mint.data.borrow();
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint.base.decimals)
+}
+
+/// Unpacks a token account of either token program, skipping any Token-2022
+/// extensions.
+pub fn unpack_token_account(
+    account: &AccountInfo,
+) -> std::result::Result<spl_token_2022::state::Account, ProgramError> {
+    if !is_token_program(account.owner) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = account.data.borrow();
+    let token = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(token.base)
+}
+
+/// The transfer fees withheld in a Token-2022 token account, 0 when its mint
+/// charges none.
+pub fn get_withheld_amount(account: &AccountInfo) -> std::result::Result<u64, ProgramError> {
+    let data = account.data.borrow();
+    let token = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(token
+        .get_extension::<TransferFeeAmount>()
+        .map(|fee| u64::from(fee.withheld_amount))
+        .unwrap_or(0))
+}
+
+/// Checks account is owner's token account of mint.
+pub fn assert_token_account(account: &AccountInfo, owner: &Pubkey, mint: &Pubkey) -> ProgramResult {
+    match unpack_token_account(account) {
+        Ok(token) if token.mint == *mint && token.owner == *owner => Ok(()),
+        _ => Err(AuctionError::InvalidTokenAccount.into()),
+    }
+}
+
+/// Checks account holds owner's bid tokens: owner's wallet itself when bids
+/// are in native SOL, otherwise owner's token account of token_mint.
+pub fn assert_bid_account(
+    account: &AccountInfo,
+    owner: &Pubkey,
+    token_mint: &Pubkey,
+) -> ProgramResult {
+    if is_native_mint(token_mint) {
+        if account.key != owner {
+            return Err(AuctionError::InvalidBidAccount.into());
+        }
+        return Ok(());
+    }
+
+    if assert_token_account(account, owner, token_mint).is_err() {
+        return Err(AuctionError::InvalidBidAccount.into());
+    }
+
+    Ok(())
+}
+
+/// Moves amount of the bid token from payer into the auction: lamports into
+/// the auction PDA itself for native SOL, otherwise tokens into its escrow ata.
+/// Returns the amount the auction received after any transfer fee.
+pub fn transfer_bid_in<'info>(
+    payer: AccountInfo<'info>,
+    payer_account: AccountInfo<'info>,
+    auction: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    token_mint: AccountInfo<'info>,
+    amount: u64,
+    native: bool,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> std::result::Result<u64, ProgramError> {
+    if native {
+        transfer_sol(payer, auction, amount, system_program)?;
+        Ok(amount)
+    } else {
+        transfer_spl_received(
+            payer,
+            payer_account,
+            escrow,
+            token_mint,
+            amount,
+            token_program,
+            &[],
+        )
+    }
+}
+
+/// Pays amount of the bid token out of the auction: lamports from the auction
+/// PDA itself for native SOL, otherwise tokens from its escrow ata.
+pub fn transfer_bid_out<'info>(
+    mut auction: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    mut dst: AccountInfo<'info>,
+    token_mint: AccountInfo<'info>,
+    amount: u64,
+    native: bool,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if native {
+        transfer_from_owned_account(&mut auction, &mut dst, amount)
+    } else {
+        transfer_spl(
+            auction,
+            escrow,
+            dst,
+            token_mint,
+            amount,
+            token_program,
+            signer_seeds,
+        )
+    }
+}
+
+/// The commitment to a sealed bid: keccak of a domain tag followed by bid and
+/// nonce as little-endian u64s, so that it opens to exactly one bid.
+pub fn compute_sealed_bid_hash(bid: u64, nonce: u64) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(b"auctionhouse sealed bid");
+    hasher.update(&bid.to_le_bytes());
+    hasher.update(&nonce.to_le_bytes());
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// The commitment to an open auction's hidden reserve: keccak of a domain tag
+/// followed by reserve and nonce as little-endian u64s.
+pub fn compute_reserve_hash(reserve: u64, nonce: u64) -> [u8; 32] {
+    let mut hash = [0u8; 32];
     let mut hasher = Keccak::v256();
-    hasher.update(bid.to_string().as_bytes());
-    hasher.update(nonce.to_string().as_bytes());
-    hasher.finalize(&mut new_hash);
-    new_hash
+    hasher.update(b"auctionhouse reserve");
+    hasher.update(&reserve.to_le_bytes());
+    hasher.update(&nonce.to_le_bytes());
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// The commitment to a hidden maximum bid in an open auction: keccak of a
+/// domain tag followed by the maximum and nonce as little-endian u64s.
+pub fn compute_max_bid_hash(max_bid: u64, nonce: u64) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(b"auctionhouse max bid");
+    hasher.update(&max_bid.to_le_bytes());
+    hasher.update(&nonce.to_le_bytes());
+    hasher.finalize(&mut hash);
+    hash
+}
+
+pub fn compute_allowlist_leaf(key: &Pubkey) -> [u8; 32] {
+    let mut leaf = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(key.as_ref());
+    hasher.finalize(&mut leaf);
+    leaf
+}
+
+/// Walks a keccak merkle proof from leaf to root, hashing each pair of nodes
+/// in sorted order.
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut node = leaf;
+    for sibling in proof {
+        let mut hasher = Keccak::v256();
+        if node <= *sibling {
+            hasher.update(&node);
+            hasher.update(sibling);
+        } else {
+            hasher.update(sibling);
+            hasher.update(&node);
+        }
+        hasher.finalize(&mut node);
+    }
+    node == root
+}
+
+pub fn compute_bps_fee(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / MAX_BPS as u128) as u64
+}
+
+/// Sends fee_bps of amount from the escrow to the treasury token account,
+/// returning the fee paid.
+pub fn pay_bps_fee<'info>(
+    authority: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    token_mint: AccountInfo<'info>,
+    amount: u64,
+    fee_bps: u16,
+    native: bool,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<u64, ProgramError> {
+    let fee = compute_bps_fee(amount, fee_bps);
+    if fee > 0 {
+        transfer_bid_out(
+            authority,
+            escrow,
+            treasury,
+            token_mint,
+            fee,
+            native,
+            token_program,
+            signer_seeds,
+        )?;
+    }
+
+    Ok(fee)
+}
+
+/// Sends fee from the escrow to the token account of the referrer's referral
+/// PDA, or the referral PDA itself for native SOL, and credits it there,
+/// returning the fee paid. Nothing is paid when there is no referrer or they
+/// have not initialized their referral PDA.
+pub fn pay_referral_fee<'info>(
+    referral: &AccountInfo<'info>,
+    referral_ata: &AccountInfo<'info>,
+    referrer: &Pubkey,
+    token_mint: &AccountInfo<'info>,
+    program_id: &Pubkey,
+    authority: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    fee: u64,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<u64, ProgramError> {
+    if fee == 0 || *referrer == Pubkey::default() {
+        return Ok(0);
+    }
+
+    let (address, _) = Pubkey::find_program_address(
+        &[b"referral", referrer.as_ref(), token_mint.key.as_ref()],
+        program_id,
+    );
+    let native = is_native_mint(token_mint.key);
+    let vault = if native {
+        address
+    } else {
+        get_ata_address(&address, token_mint.key, token_program.key)
+    };
+    if *referral.key != address || *referral_ata.key != vault {
+        return Err(AuctionError::InvalidReferralAccount.into());
+    }
+    if referral.data_is_empty() {
+        return Ok(0);
+    }
+
+    let mut record: Account<ReferralRecord> = Account::try_from(referral)?;
+    transfer_bid_out(
+        authority,
+        escrow,
+        referral_ata.clone(),
+        token_mint.clone(),
+        fee,
+        native,
+        token_program,
+        signer_seeds,
+    )?;
+    record.total_earned += fee;
+    record.referral_count += 1;
+    record.exit(program_id)?;
+
+    Ok(fee)
+}
+
+/// Loads the Metaplex metadata of mint, checking the account is its metadata
+/// PDA.
+pub fn load_metadata(
+    metadata: &AccountInfo,
+    mint: &Pubkey,
+) -> std::result::Result<Metadata, ProgramError> {
+    if *metadata.owner != mpl_token_metadata::id() || *metadata.key != find_metadata_account(mint).0
+    {
+        return Err(AuctionError::InvalidMetadata.into());
+    }
+    Metadata::from_account_info(metadata)
+}
+
+/// Checks that holder owns a nonzero balance of an NFT whose metadata carries
+/// the verified collection.
+pub fn assert_collection_holder(
+    holder: &Pubkey,
+    token_account: &AccountInfo,
+    metadata: &AccountInfo,
+    collection: &Pubkey,
+) -> ProgramResult {
+    let token = match unpack_token_account(token_account) {
+        Ok(token) => token,
+        Err(_) => return Err(AuctionError::NotCollectionHolder.into()),
+    };
+    if token.owner != *holder || token.amount == 0 {
+        return Err(AuctionError::NotCollectionHolder.into());
+    }
+
+    let metadata = load_metadata(metadata, &token.mint)?;
+    match metadata.collection {
+        Some(c) if c.verified && c.key == *collection => Ok(()),
+        _ => Err(AuctionError::NotCollectionHolder.into()),
+    }
+}
+
+/// Pays every verified creator of mint their share of the royalty on amount
+/// from the escrow, returning the total paid. accounts holds the Metaplex
+/// metadata of mint followed by the bid token account, or wallet for native
+/// SOL, of each verified creator in metadata order. The royalty is capped at
+/// max_royalty. An item without metadata pays no royalty.
+pub fn pay_creator_royalties<'info>(
+    accounts: &[AccountInfo<'info>],
+    mint: &Pubkey,
+    token_mint: &AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    amount: u64,
+    max_royalty: u64,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<u64, ProgramError> {
+    let metadata = accounts.first().ok_or(AuctionError::InvalidMetadata)?;
+    if *metadata.key != find_metadata_account(mint).0 {
+        return Err(AuctionError::InvalidMetadata.into());
+    }
+    if metadata.data_is_empty() {
+        return Ok(0);
+    }
+    let metadata = load_metadata(metadata, mint)?;
+
+    let royalty = std::cmp::min(
+        compute_bps_fee(amount, metadata.data.seller_fee_basis_points),
+        max_royalty,
+    );
+    let creators = metadata.data.creators.unwrap_or_default();
+
+    let native = is_native_mint(token_mint.key);
+    let mut creator_accounts = accounts[1..].iter();
+    let mut paid: u64 = 0;
+    for creator in creators.iter().filter(|c| c.verified) {
+        let creator_account = creator_accounts
+            .next()
+            .ok_or(AuctionError::InvalidCreatorAccount)?;
+        if assert_bid_account(creator_account, &creator.address, token_mint.key).is_err() {
+            return Err(AuctionError::InvalidCreatorAccount.into());
+        }
+
+        let share = (royalty as u128 * creator.share as u128 / 100) as u64;
+        if share > 0 {
+            transfer_bid_out(
+                authority.clone(),
+                escrow.clone(),
+                creator_account.clone(),
+                token_mint.clone(),
+                share,
+                native,
+                token_program.clone(),
+                signer_seeds,
+            )?;
+            paid += share;
+        }
+    }
+
+    Ok(paid)
+}
+
+/// Checks bidder passes the auction's allowlist with proof and, when it is
+/// gated, holds an NFT of the gating collection. gating_accounts are the
+/// bidder's token account of that NFT and its metadata.
+pub fn assert_open_bidder_allowed(
+    auction: &OpenAuction,
+    bidder: &Pubkey,
+    proof: &[[u8; 32]],
+    gating_accounts: &[AccountInfo],
+) -> ProgramResult {
+    if auction.has_allowlist()
+        && !verify_merkle_proof(
+            proof,
+            auction.allowlist_root,
+            compute_allowlist_leaf(bidder),
+        )
+    {
+        return Err(AuctionError::NotAllowlisted.into());
+    }
+    if auction.gating_collection != Pubkey::default() {
+        if gating_accounts.len() < 2 {
+            return Err(AuctionError::NotCollectionHolder.into());
+        }
+        assert_collection_holder(
+            bidder,
+            &gating_accounts[0],
+            &gating_accounts[1],
+            &auction.gating_collection,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// How a winning bid of an open auction was paid out.
+pub struct Payout {
+    pub house_fee: u64,
+    pub bid_referral_fee: u64,
+    pub creation_referral_fee: u64,
+    pub project_fee: u64,
+    pub royalties: u64,
+    pub proceeds: u64,
+}
+
+/// Pays winning_bid out of the open auction's escrow. The house treasury gets
+/// its fee_bps less the referrers' shares, the project treasury its fee_bps,
+/// the verified creators their royalty, capped at what the fees leave, and
+/// the owner the rest.
+/// creator_accounts is the NFT's metadata followed by the creators' bid token
+/// accounts, as for pay_creator_royalties.
+pub fn pay_winning_bid<'info>(
+    auction: &Account<'info, OpenAuction>,
+    escrow: &AccountInfo<'info>,
+    config: &HouseConfig,
+    project_config: &ProjectConfig,
+    house_treasury_ata: &AccountInfo<'info>,
+    project_treasury_ata: &AccountInfo<'info>,
+    bid_referrer: &Pubkey,
+    bid_referral: &AccountInfo<'info>,
+    bid_referral_ata: &AccountInfo<'info>,
+    creation_referral: &AccountInfo<'info>,
+    creation_referral_ata: &AccountInfo<'info>,
+    owner_ata: &AccountInfo<'info>,
+    creator_accounts: &[AccountInfo<'info>],
+    token_mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    winning_bid: u64,
+) -> std::result::Result<Payout, ProgramError> {
+    assert_bid_account(house_treasury_ata, &config.treasury, &auction.token_mint)?;
+    assert_bid_account(
+        project_treasury_ata,
+        &project_config.treasury,
+        &auction.token_mint,
+    )?;
+    assert_bid_account(owner_ata, &auction.owner, &auction.token_mint)?;
+
+    let native = auction.is_native();
+    let seeds: &[&[u8]] = &[
+        b"open auction",
+        auction.owner.as_ref(),
+        name_seed(&auction.title),
+        &[auction.bump],
+    ];
+
+    // the referrers' shares come out of the house fee
+    let house_fee = compute_bps_fee(winning_bid, config.fee_bps);
+    let bid_referral_fee = pay_referral_fee(
+        bid_referral,
+        bid_referral_ata,
+        bid_referrer,
+        token_mint,
+        &crate::ID,
+        auction.to_account_info(),
+        escrow.clone(),
+        compute_bps_fee(house_fee, config.bid_referral_bps),
+        token_program.clone(),
+        &[seeds],
+    )?;
+    let creation_referral_fee = pay_referral_fee(
+        creation_referral,
+        creation_referral_ata,
+        &auction.referrer,
+        token_mint,
+        &crate::ID,
+        auction.to_account_info(),
+        escrow.clone(),
+        compute_bps_fee(house_fee, config.creation_referral_bps),
+        token_program.clone(),
+        &[seeds],
+    )?;
+    let house_share = house_fee - bid_referral_fee - creation_referral_fee;
+    if house_share > 0 {
+        transfer_bid_out(
+            auction.to_account_info(),
+            escrow.clone(),
+            house_treasury_ata.clone(),
+            token_mint.clone(),
+            house_share,
+            native,
+            token_program.clone(),
+            &[seeds],
+        )?;
+    }
+    let project_fee = pay_bps_fee(
+        auction.to_account_info(),
+        escrow.clone(),
+        project_treasury_ata.clone(),
+        token_mint.clone(),
+        winning_bid,
+        project_config.fee_bps,
+        native,
+        token_program.clone(),
+        &[seeds],
+    )?;
+    // the creators get their royalty out of whatever the fees leave
+    let remaining = winning_bid
+        .checked_sub(house_fee + project_fee)
+        .ok_or(AuctionError::InvalidFeeBps)?;
+    let royalties = pay_creator_royalties(
+        creator_accounts,
+        &auction.mint,
+        token_mint,
+        auction.to_account_info(),
+        escrow.clone(),
+        winning_bid,
+        remaining,
+        token_program.clone(),
+        &[seeds],
+    )?;
+    let proceeds = remaining - royalties;
+
+    transfer_bid_out(
+        auction.to_account_info(),
+        escrow.clone(),
+        owner_ata.clone(),
+        token_mint.clone(),
+        proceeds,
+        native,
+        token_program.clone(),
+        &[seeds],
+    )?;
+
+    Ok(Payout {
+        house_fee,
+        bid_referral_fee,
+        creation_referral_fee,
+        project_fee,
+        royalties,
+        proceeds,
+    })
 }
 
 pub fn name_seed(name: &str) -> &[u8] {
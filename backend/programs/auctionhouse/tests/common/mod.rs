@@ -0,0 +1,982 @@
+//! In-process bank for end-to-end tests of the auctionhouse program.
+//!
+//! solana-program-test 1.9.5, the release matching our solana-program pin, no
+//! longer compiles on current compilers, so this runs the program the same
+//! way its processor mode does: the program's handlers are called directly and
+//! cross-program invocations go through the syscall stubs into the real SPL
+//! Token, Token-2022 and associated token account processors and a minimal
+//! system program. Transactions are atomic, lamports must balance after every
+//! instruction and the Clock sysvar can be warped freely.
+//!
+//! Like the runtime, every program invocation is checked when it returns and
+//! before each of its own CPIs: only an account's owner may change its data or
+//! owner, or take its lamports, and only through a writable account. The
+//! pinned associated token account program predates Token-2022, so creating
+//! a Token-2022 associated account is emulated the way later releases of it
+//! do, with the ImmutableOwner extension.
+//!
+//! The program logs of the last transaction are kept, so tests can decode the
+//! events it emitted.
+#![allow(dead_code)]
+
+use std::cell::{RefCell, UnsafeCell};
+use std::collections::HashMap;
+use std::sync::Once;
+
+use anchor_lang::__private::ErrorCode;
+use anchor_lang::context::Context;
+use anchor_lang::{
+    AccountDeserialize, Accounts, AccountsExit, AnchorDeserialize, AnchorSerialize, Discriminator,
+};
+use auctionhouse::error::AuctionError;
+use auctionhouse_client::error::error_code;
+use mpl_token_metadata::pda::find_metadata_account;
+use mpl_token_metadata::state::{Collection, Creator, Data, Key, Metadata, MAX_METADATA_LEN};
+use solana_program::account_info::AccountInfo;
+use solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_program::clock::Clock;
+use solana_program::entrypoint::{ProgramResult, SUCCESS};
+use solana_program::hash::hash;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::{invoke, invoke_signed};
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_instruction::{self, SystemInstruction};
+use solana_program::{bpf_loader, system_program, sysvar};
+use spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config;
+use spl_token_2022::extension::{ExtensionType, StateWithExtensions};
+
+pub const START_TIME: i64 = 1_650_000_000;
+pub const SOL: u64 = 1_000_000_000;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountState {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::default());
+    // programs currently executing, the last one makes any cpi
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+    // owner storage of the accounts of the running instruction, which the
+    // system program rewrites on assign
+    static OWNERS: RefCell<HashMap<Pubkey, *const UnsafeCell<Pubkey>>> =
+        RefCell::new(HashMap::new());
+    // messages logged by the running transaction
+    static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn account_state(info: &AccountInfo) -> AccountState {
+    AccountState {
+        lamports: info.lamports(),
+        data: info.data.borrow().to_vec(),
+        owner: *info.owner,
+        executable: info.executable,
+    }
+}
+
+/// A running program and the accounts it was passed, as they were when it
+/// last gained control.
+struct Frame {
+    program_id: Pubkey,
+    accounts: HashMap<Pubkey, (AccountState, bool)>,
+}
+
+impl Frame {
+    fn new(program_id: &Pubkey, accounts: &[AccountInfo]) -> Self {
+        let mut frame = Frame {
+            program_id: *program_id,
+            accounts: HashMap::new(),
+        };
+        for info in accounts {
+            let is_writable = info.is_writable
+                || frame
+                    .accounts
+                    .get(info.key)
+                    .is_some_and(|(_, is_writable)| *is_writable);
+            frame
+                .accounts
+                .insert(*info.key, (account_state(info), is_writable));
+        }
+        frame
+    }
+
+    /// Checks the program's own changes to accounts since it last gained
+    /// control against the runtime's rules: only the owner changes data,
+    /// takes lamports or reassigns an account, and only writable accounts
+    /// change at all.
+    fn verify(&self, accounts: &[AccountInfo]) {
+        for info in accounts {
+            let (old, is_writable) = match self.accounts.get(info.key) {
+                Some(account) => account,
+                None => continue,
+            };
+            let new = account_state(info);
+            let is_owner = old.owner == self.program_id;
+            let fail =
+                |rule: &str| panic!("program {} {} account {}", self.program_id, rule, info.key);
+            if new.data != old.data && !(is_owner && *is_writable) {
+                fail("changed the data of");
+            }
+            if new.lamports < old.lamports && !is_owner {
+                fail("took lamports from");
+            }
+            if new.lamports != old.lamports && !is_writable {
+                fail("changed the lamports of read-only");
+            }
+            if new.owner != old.owner && !(is_owner && *is_writable) {
+                fail("reassigned");
+            }
+        }
+    }
+
+    /// Takes the accounts a cpi may have changed as the caller's new
+    /// starting point.
+    fn resume(&mut self, accounts: &[AccountInfo]) {
+        for info in accounts {
+            if let Some((state, _)) = self.accounts.get_mut(info.key) {
+                *state = account_state(info);
+            }
+        }
+    }
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        LOGS.with(|logs| logs.borrow_mut().push(message.to_string()));
+    }
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        // the runtime checks the caller's changes so far before the callee runs
+        let caller = FRAMES.with(|frames| {
+            let frames = frames.borrow();
+            let frame = frames.last().unwrap();
+            frame.verify(account_infos);
+            frame.program_id
+        });
+        let pda_signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+
+        let mut infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in instruction.accounts.iter() {
+            let mut info = account_infos
+                .iter()
+                .find(|info| *info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?
+                .clone();
+            if meta.is_signer && !info.is_signer && !pda_signers.contains(info.key) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            infos.push(info);
+        }
+
+        dispatch(&instruction.program_id, &infos, &instruction.data)?;
+        FRAMES.with(|frames| {
+            frames
+                .borrow_mut()
+                .last_mut()
+                .unwrap()
+                .resume(account_infos)
+        });
+        Ok(())
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = CLOCK.with(|clock| clock.borrow().clone());
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+}
+
+// Anchor only generates the program's entry without the no-entrypoint feature,
+// which the client turns on, and keeps its per-instruction wrappers private,
+// so do what those wrappers do: match the sighash, deserialize the arguments
+// and accounts, run the handler and persist the accounts.
+macro_rules! auctionhouse_handlers {
+    ($($name:ident: $accounts:ident { $($arg:ident),* $(,)? };)*) => {
+        fn process_auctionhouse(
+            program_id: &Pubkey,
+            accounts: &[AccountInfo],
+            data: &[u8],
+        ) -> ProgramResult {
+            if data.len() < 8 {
+                return Err(ErrorCode::InstructionMissing.into());
+            }
+            let (sighash, ix_data) = data.split_at(8);
+            $(
+                if *sighash == hash(concat!("global:", stringify!($name)).as_bytes()).to_bytes()[..8] {
+                    let auctionhouse::instruction::$accounts { $($arg),* } =
+                        AnchorDeserialize::deserialize(&mut &ix_data[..])
+                            .map_err(|_| ErrorCode::InstructionDidNotDeserialize)?;
+                    let mut remaining_accounts: &[AccountInfo] = accounts;
+                    let mut accounts = auctionhouse::context::$accounts::try_accounts(
+                        program_id,
+                        &mut remaining_accounts,
+                        ix_data,
+                    )?;
+                    auctionhouse::auctionhouse::$name(
+                        Context::new(program_id, &mut accounts, remaining_accounts),
+                        $($arg),*
+                    )?;
+                    return accounts.exit(program_id);
+                }
+            )*
+            Err(ErrorCode::InstructionFallbackNotFound.into())
+        }
+    };
+}
+
+auctionhouse_handlers! {
+    initialize_house_config: InitializeHouseConfig {
+        bump,
+        admins,
+        treasury,
+        fee_bps,
+        bid_referral_bps,
+        creation_referral_bps,
+    };
+    update_house_config: UpdateHouseConfig {
+        authority,
+        admins,
+        treasury,
+        fee_bps,
+        bid_referral_bps,
+        creation_referral_bps,
+    };
+    initialize_project_config: InitializeProjectConfig {
+        bump,
+        project_id,
+        authority,
+        admins,
+        treasury,
+        fee_amount,
+        token_mints,
+        crank_reward,
+        fee_bps,
+    };
+    update_project_config: UpdateProjectConfig {
+        authority,
+        admins,
+        treasury,
+        fee_amount,
+        token_mints,
+        crank_reward,
+        fee_bps,
+    };
+    create_open_auction: CreateOpenAuction {
+        bump,
+        title,
+        floor,
+        increment,
+        start_time,
+        end_time,
+        bidder_cap,
+        token_amount,
+        project_id,
+        extension_window,
+        extension_amount,
+        max_end_time,
+        buy_now_price,
+        reserve_hash,
+        reserve_reveal_window,
+        retract_cutoff,
+        leader_can_retract,
+        max_bid_reveal_window,
+        allowlist_root,
+        gating_collection,
+        referrer,
+    };
+    cancel_open_auction: CancelOpenAuction {};
+    update_allowlist_open: UpdateAllowlistOpen { allowlist_root };
+    reveal_reserve_open: RevealReserveOpen { reserve, nonce };
+    make_open_bid: MakeOpenBid {
+        bump,
+        amount,
+        proof,
+        referrer,
+        max_hash,
+    };
+    reclaim_open_bid: ReclaimOpenBid {};
+    retract_open_bid: RetractOpenBid {};
+    initialize_referral: InitializeReferral { bump };
+    claim_referral_rewards: ClaimReferralRewards {};
+    reveal_open_bid: RevealOpenBid { max_bid, nonce };
+    refund_open_bids: RefundOpenBids {};
+    close_open_bid: CloseOpenBid {};
+    withdraw_item_open: WithdrawItemOpen {};
+    withdraw_winning_bid_open: WithdrawWinningBidOpen {};
+    settle_open_auction: SettleOpenAuction {};
+    buy_now_open: BuyNowOpen { proof, referrer };
+    reclaim_item_open: ReclaimItemOpen {};
+    close_open_auction: CloseOpenAuction {};
+    create_sealed_auction: CreateSealedAuction {
+        bump,
+        title,
+        floor,
+        first_price,
+        start_time,
+        end_time,
+        reveal_period,
+        bidder_cap,
+        token_amount,
+    };
+    cancel_sealed_auction: CancelSealedAuction {};
+    make_sealed_bid: MakeSealedBid { sealed_bid, amount };
+    reveal_sealed_bid: RevealSealedBid { bid, nonce };
+    reclaim_sealed_bid: ReclaimSealedBid {};
+    withdraw_item_sealed: WithdrawItemSealed {};
+    withdraw_winning_bid_sealed: WithdrawWinningBidSealed {};
+    reclaim_item_sealed: ReclaimItemSealed {};
+    create_dutch_auction: CreateDutchAuction {
+        bump,
+        title,
+        start_price,
+        floor_price,
+        start_time,
+        end_time,
+        step_interval,
+        token_amount,
+        project_id,
+    };
+    cancel_dutch_auction: CancelDutchAuction {};
+    buy_dutch: BuyDutch { amount };
+    reclaim_item_dutch: ReclaimItemDutch {};
+}
+
+fn dispatch(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    FRAMES.with(|frames| frames.borrow_mut().push(Frame::new(program_id, accounts)));
+    let result = if *program_id == auctionhouse::ID {
+        process_auctionhouse(program_id, accounts, data)
+    } else if *program_id == spl_token::ID {
+        spl_token::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == spl_token_2022::ID {
+        spl_token_2022::processor::Processor::process(program_id, accounts, data)
+    } else if *program_id == spl_associated_token_account::ID {
+        if accounts.get(5).map(|info| *info.key) == Some(spl_token_2022::ID) {
+            create_token_2022_ata(program_id, accounts)
+        } else {
+            spl_associated_token_account::processor::process_instruction(program_id, accounts, data)
+        }
+    } else if *program_id == system_program::ID {
+        process_system(accounts, data)
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    };
+    let frame = FRAMES.with(|frames| frames.borrow_mut().pop().unwrap());
+    if result.is_ok() {
+        frame.verify(accounts);
+    }
+    result
+}
+
+/// The associated token account program 1.0.3 predates Token-2022 and always
+/// initializes an SPL Token account, so create Token-2022 ones the way later
+/// releases do: sized for the extensions the mint requires, with an immutable
+/// owner.
+fn create_token_2022_ata(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let [funder, ata, wallet, mint, system, token_program, _rent] = match accounts {
+        [a, b, c, d, e, f, g, ..] => [a, b, c, d, e, f, g],
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    let (address, bump) = Pubkey::find_program_address(
+        &[
+            wallet.key.as_ref(),
+            token_program.key.as_ref(),
+            mint.key.as_ref(),
+        ],
+        program_id,
+    );
+    if address != *ata.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let seeds: &[&[u8]] = &[
+        wallet.key.as_ref(),
+        token_program.key.as_ref(),
+        mint.key.as_ref(),
+        &[bump],
+    ];
+
+    let mint_extensions =
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data.borrow())?
+            .get_extension_types()?;
+    let mut extensions = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+    extensions.push(ExtensionType::ImmutableOwner);
+    let space = ExtensionType::get_account_len::<spl_token_2022::state::Account>(&extensions);
+
+    invoke(
+        &system_instruction::transfer(funder.key, ata.key, Rent::default().minimum_balance(space)),
+        &[funder.clone(), ata.clone(), system.clone()],
+    )?;
+    invoke_signed(
+        &system_instruction::allocate(ata.key, space as u64),
+        &[ata.clone(), system.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(ata.key, token_program.key),
+        &[ata.clone(), system.clone()],
+        &[seeds],
+    )?;
+    invoke(
+        &spl_token_2022::instruction::initialize_immutable_owner(token_program.key, ata.key)?,
+        &[ata.clone(), token_program.clone()],
+    )?;
+    invoke(
+        &spl_token_2022::instruction::initialize_account3(
+            token_program.key,
+            ata.key,
+            mint.key,
+            wallet.key,
+        )?,
+        &[ata.clone(), mint.clone(), token_program.clone()],
+    )
+}
+
+fn set_owner(account: &AccountInfo, owner: &Pubkey) {
+    OWNERS.with(|owners| {
+        let cell = owners.borrow()[account.key];
+        unsafe { *(*cell).get() = *owner };
+    });
+}
+
+fn set_data_len(account: &AccountInfo, space: u64) {
+    *account.data.borrow_mut() = Box::leak(vec![0u8; space as usize].into_boxed_slice());
+}
+
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+/// The system program instructions the SPL programs and Anchor use.
+fn process_system(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let is_unused =
+        |account: &AccountInfo| *account.owner == system_program::ID && account.data_is_empty();
+    let instruction: SystemInstruction =
+        bincode::deserialize(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let (from, to) = (&accounts[0], &accounts[1]);
+            if !from.is_signer || !to.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !is_unused(to) || to.lamports() > 0 {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            move_lamports(from, to, lamports)?;
+            set_data_len(to, space);
+            set_owner(to, &owner);
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let (from, to) = (&accounts[0], &accounts[1]);
+            if !from.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !is_unused(from) {
+                return Err(ProgramError::InvalidArgument);
+            }
+            move_lamports(from, to, lamports)?;
+        }
+        SystemInstruction::Allocate { space } => {
+            let account = &accounts[0];
+            if !account.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !is_unused(account) {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            set_data_len(account, space);
+        }
+        SystemInstruction::Assign { owner } => {
+            let account = &accounts[0];
+            if !account.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if *account.owner != system_program::ID {
+                return Err(ProgramError::IllegalOwner);
+            }
+            set_owner(account, &owner);
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+
+    Ok(())
+}
+
+pub struct Bank {
+    pub accounts: HashMap<Pubkey, AccountState>,
+    pub clock: Clock,
+    // funds rent and fees of the bank's own setup transactions
+    pub payer: Pubkey,
+    // what the last transaction logged
+    pub logs: Vec<String>,
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(Stubs));
+        });
+
+        let mut bank = Bank {
+            accounts: HashMap::new(),
+            clock: Clock {
+                unix_timestamp: START_TIME,
+                ..Clock::default()
+            },
+            payer: Pubkey::new_unique(),
+            logs: Vec::new(),
+        };
+        for program_id in [
+            auctionhouse::ID,
+            spl_token::ID,
+            spl_token_2022::ID,
+            spl_associated_token_account::ID,
+            system_program::ID,
+        ] {
+            bank.accounts.insert(
+                program_id,
+                AccountState {
+                    lamports: 1,
+                    data: vec![],
+                    owner: bpf_loader::ID,
+                    executable: true,
+                },
+            );
+        }
+        // the cluster's wrapped SOL mint, which native SOL auctions name
+        let mut native_mint = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..spl_token::state::Mint::default()
+        }
+        .pack_into_slice(&mut native_mint);
+        bank.accounts.insert(
+            spl_token::native_mint::ID,
+            AccountState {
+                lamports: Rent::default().minimum_balance(native_mint.len()),
+                data: native_mint,
+                owner: spl_token::ID,
+                executable: false,
+            },
+        );
+        bank.accounts.insert(
+            sysvar::rent::ID,
+            AccountState {
+                lamports: 1,
+                data: bincode::serialize(&Rent::default()).unwrap(),
+                owner: sysvar::ID,
+                executable: false,
+            },
+        );
+        bank.airdrop(&bank.payer.clone(), 1_000_000 * SOL);
+        bank
+    }
+
+    pub fn now(&self) -> u64 {
+        self.clock.unix_timestamp as u64
+    }
+
+    pub fn warp_to(&mut self, unix_timestamp: u64) {
+        self.clock.unix_timestamp = unix_timestamp as i64;
+        self.clock.slot += 1;
+    }
+
+    pub fn warp_by(&mut self, seconds: u64) {
+        self.warp_to(self.now() + seconds);
+    }
+
+    /// Deploys the auctionhouse program data with the given upgrade authority.
+    pub fn set_upgrade_authority(&mut self, authority: &Pubkey) {
+        let state = UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(*authority),
+        };
+        self.accounts.insert(
+            auctionhouse::utils::get_program_data_address(),
+            AccountState {
+                lamports: 1,
+                data: bincode::serialize(&state).unwrap(),
+                owner: bpf_loader_upgradeable::ID,
+                executable: false,
+            },
+        );
+    }
+
+    /// Writes Metaplex metadata for mint, with royalty_bps split equally
+    /// between the verified creators and an optional verified collection.
+    pub fn set_metadata(
+        &mut self,
+        mint: &Pubkey,
+        royalty_bps: u16,
+        creators: &[Pubkey],
+        collection: Option<Pubkey>,
+    ) {
+        let creators = creators
+            .iter()
+            .map(|address| Creator {
+                address: *address,
+                verified: true,
+                share: (100 / creators.len()) as u8,
+            })
+            .collect();
+        let metadata = Metadata {
+            key: Key::MetadataV1,
+            update_authority: Pubkey::new_unique(),
+            mint: *mint,
+            data: Data {
+                name: String::new(),
+                symbol: String::new(),
+                uri: String::new(),
+                seller_fee_basis_points: royalty_bps,
+                creators: Some(creators),
+            },
+            primary_sale_happened: true,
+            is_mutable: false,
+            edition_nonce: None,
+            token_standard: None,
+            collection: collection.map(|key| Collection {
+                verified: true,
+                key,
+            }),
+            uses: None,
+        };
+        let mut data = metadata.try_to_vec().unwrap();
+        data.resize(MAX_METADATA_LEN, 0);
+        self.accounts.insert(
+            find_metadata_account(mint).0,
+            AccountState {
+                lamports: 1,
+                data,
+                owner: mpl_token_metadata::ID,
+                executable: false,
+            },
+        );
+    }
+
+    pub fn airdrop(&mut self, to: &Pubkey, lamports: u64) {
+        self.accounts.entry(*to).or_default().lamports += lamports;
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<&AccountState> {
+        self.accounts.get(key)
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key)
+            .map(|account| account.lamports)
+            .unwrap_or(0)
+    }
+
+    pub fn total_lamports(&self) -> u128 {
+        self.accounts
+            .values()
+            .map(|account| account.lamports as u128)
+            .sum()
+    }
+
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.account(key).expect("account does not exist");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// The balance of a token account of either token program, 0 when it
+    /// does not exist.
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        self.account(key)
+            .map(|account| {
+                StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+                    .unwrap()
+                    .base
+                    .amount
+            })
+            .unwrap_or(0)
+    }
+
+    /// Runs instructions as one transaction signed by signers, rolling every
+    /// account back if any of them fails.
+    pub fn process(&mut self, instructions: &[Instruction], signers: &[Pubkey]) -> ProgramResult {
+        let snapshot = self.accounts.clone();
+        CLOCK.with(|clock| *clock.borrow_mut() = self.clock.clone());
+        LOGS.with(|logs| logs.borrow_mut().clear());
+
+        for instruction in instructions {
+            let result = self.process_instruction(instruction, signers);
+            self.logs = LOGS.with(|logs| logs.borrow().clone());
+            if let Err(err) = result {
+                self.accounts = snapshot;
+                return Err(err);
+            }
+        }
+        // like the runtime, drop accounts left without lamports
+        self.accounts.retain(|_, account| account.lamports > 0);
+
+        Ok(())
+    }
+
+    /// The events of type E the last transaction emitted, in order.
+    pub fn events<E: Discriminator + AnchorDeserialize>(&self) -> Vec<E> {
+        self.logs
+            .iter()
+            .filter_map(|message| anchor_lang::__private::base64::decode(message).ok())
+            .filter(|data| data.starts_with(&E::discriminator()))
+            .map(|data| E::try_from_slice(&data[8..]).unwrap())
+            .collect()
+    }
+
+    fn process_instruction(
+        &mut self,
+        instruction: &Instruction,
+        signers: &[Pubkey],
+    ) -> ProgramResult {
+        let mut keys: Vec<Pubkey> = Vec::new();
+        for meta in instruction.accounts.iter() {
+            if !keys.contains(&meta.pubkey) {
+                keys.push(meta.pubkey);
+            }
+        }
+        let mut states: Vec<AccountState> = keys
+            .iter()
+            .map(|key| {
+                self.accounts.get(key).cloned().unwrap_or(AccountState {
+                    owner: system_program::ID,
+                    ..AccountState::default()
+                })
+            })
+            .collect();
+        let owners: Vec<Box<UnsafeCell<Pubkey>>> = states
+            .iter()
+            .map(|state| Box::new(UnsafeCell::new(state.owner)))
+            .collect();
+        let before = states.clone();
+
+        OWNERS.with(|cells| {
+            let mut cells = cells.borrow_mut();
+            cells.clear();
+            for (key, owner) in keys.iter().zip(owners.iter()) {
+                cells.insert(*key, &**owner as *const UnsafeCell<Pubkey>);
+            }
+        });
+
+        let infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(states.iter_mut())
+            .zip(owners.iter())
+            .map(|((key, state), owner)| {
+                let is_writable = instruction
+                    .accounts
+                    .iter()
+                    .any(|meta| meta.pubkey == *key && meta.is_writable);
+                AccountInfo::new(
+                    key,
+                    signers.contains(key),
+                    is_writable,
+                    &mut state.lamports,
+                    &mut state.data,
+                    unsafe { &*owner.get() },
+                    state.executable,
+                    0,
+                )
+            })
+            .collect();
+        let accounts: Vec<AccountInfo> = instruction
+            .accounts
+            .iter()
+            .map(|meta| infos[keys.iter().position(|key| *key == meta.pubkey).unwrap()].clone())
+            .collect();
+
+        let result = dispatch(&instruction.program_id, &accounts, &instruction.data);
+
+        let after: Vec<AccountState> = infos
+            .iter()
+            .zip(owners.iter())
+            .map(|(info, owner)| AccountState {
+                lamports: info.lamports(),
+                data: info.data.borrow().to_vec(),
+                owner: unsafe { *owner.get() },
+                executable: info.executable,
+            })
+            .collect();
+        drop(accounts);
+        drop(infos);
+        OWNERS.with(|cells| cells.borrow_mut().clear());
+        result?;
+
+        let sum = |states: &[AccountState]| -> u128 {
+            states.iter().map(|state| state.lamports as u128).sum()
+        };
+        assert_eq!(
+            sum(&before),
+            sum(&after),
+            "instruction created or destroyed lamports"
+        );
+        for ((key, old), new) in keys.iter().zip(before.iter()).zip(after.iter()) {
+            let is_writable = instruction
+                .accounts
+                .iter()
+                .any(|meta| meta.pubkey == *key && meta.is_writable);
+            assert!(
+                is_writable || old == new,
+                "instruction modified read-only account {}",
+                key
+            );
+            self.accounts.insert(*key, new.clone());
+        }
+
+        Ok(())
+    }
+
+    pub fn create_mint(&mut self, authority: &Pubkey, decimals: u8) -> Pubkey {
+        self.create_mint_with(authority, decimals, &spl_token::ID, 0)
+    }
+
+    /// Creates a mint of token_program. A Token-2022 mint with a nonzero
+    /// transfer_fee_bps withholds that share of every transfer, uncapped.
+    pub fn create_mint_with(
+        &mut self,
+        authority: &Pubkey,
+        decimals: u8,
+        token_program: &Pubkey,
+        transfer_fee_bps: u16,
+    ) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        let payer = self.payer;
+        let mut instructions = Vec::new();
+        let initialize_mint = if *token_program == spl_token_2022::ID {
+            let mut extensions = Vec::new();
+            if transfer_fee_bps > 0 {
+                extensions.push(ExtensionType::TransferFeeConfig);
+            }
+            let space = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(&extensions);
+            instructions.push(system_instruction::create_account(
+                &payer,
+                &mint,
+                Rent::default().minimum_balance(space),
+                space as u64,
+                token_program,
+            ));
+            if transfer_fee_bps > 0 {
+                instructions.push(
+                    initialize_transfer_fee_config(
+                        token_program,
+                        &mint,
+                        None,
+                        None,
+                        transfer_fee_bps,
+                        u64::MAX,
+                    )
+                    .unwrap(),
+                );
+            }
+            spl_token_2022::instruction::initialize_mint(
+                token_program,
+                &mint,
+                authority,
+                None,
+                decimals,
+            )
+        } else {
+            instructions.push(system_instruction::create_account(
+                &payer,
+                &mint,
+                Rent::default().minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                token_program,
+            ));
+            spl_token::instruction::initialize_mint(token_program, &mint, authority, None, decimals)
+        };
+        instructions.push(initialize_mint.unwrap());
+        self.process(&instructions, &[payer, mint]).unwrap();
+        mint
+    }
+
+    /// Creates wallet's associated token account of mint if needed and mints
+    /// amount into it.
+    pub fn mint_to(
+        &mut self,
+        mint: &Pubkey,
+        authority: &Pubkey,
+        wallet: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let payer = self.payer;
+        let token_program = self.account(mint).expect("mint does not exist").owner;
+        let ata = auctionhouse::utils::get_ata_address(wallet, mint, &token_program);
+        if self.account(&ata).is_none() {
+            // the ata program derives the address from the token program passed in
+            let create = Instruction {
+                program_id: spl_associated_token_account::ID,
+                accounts: vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(ata, false),
+                    AccountMeta::new_readonly(*wallet, false),
+                    AccountMeta::new_readonly(*mint, false),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                    AccountMeta::new_readonly(token_program, false),
+                    AccountMeta::new_readonly(sysvar::rent::ID, false),
+                ],
+                data: vec![],
+            };
+            self.process(&[create], &[payer]).unwrap();
+        }
+        if amount > 0 {
+            let mint_to = if token_program == spl_token_2022::ID {
+                spl_token_2022::instruction::mint_to(
+                    &token_program,
+                    mint,
+                    &ata,
+                    authority,
+                    &[],
+                    amount,
+                )
+            } else {
+                spl_token::instruction::mint_to(&token_program, mint, &ata, authority, &[], amount)
+            };
+            self.process(&[mint_to.unwrap()], &[*authority]).unwrap();
+        }
+        ata
+    }
+}
+
+pub fn assert_auction_error(result: ProgramResult, error: AuctionError) {
+    assert_eq!(result, Err(ProgramError::Custom(error_code(error))));
+}
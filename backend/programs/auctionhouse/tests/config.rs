@@ -0,0 +1,148 @@
+mod common;
+
+use auctionhouse::account::{HouseConfig, MAX_HOUSE_FEE_BPS, MAX_PROJECT_FEE_BPS};
+use auctionhouse::error::AuctionError;
+use auctionhouse_client::instruction as ix;
+use auctionhouse_client::pda;
+use common::{assert_auction_error, Bank, SOL};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const HOUSE_FEE_BPS: u16 = 250;
+const PROJECT_ID: u16 = 1;
+
+fn setup() -> (Bank, Pubkey) {
+    let mut bank = Bank::new();
+    let authority = Pubkey::new_unique();
+    bank.airdrop(&authority, 10 * SOL);
+    bank.set_upgrade_authority(&authority);
+    (bank, authority)
+}
+
+fn initialize_house(bank: &mut Bank, authority: &Pubkey) -> ProgramResult {
+    initialize_house_with_fee(bank, authority, HOUSE_FEE_BPS)
+}
+
+fn initialize_house_with_fee(bank: &mut Bank, authority: &Pubkey, fee_bps: u16) -> ProgramResult {
+    bank.process(
+        &[ix::initialize_house_config(
+            authority,
+            vec![],
+            Pubkey::new_unique(),
+            fee_bps,
+            0,
+            0,
+        )],
+        &[*authority],
+    )
+}
+
+fn project_config_instructions(authority: &Pubkey, fee_bps: u16) -> [Instruction; 2] {
+    [
+        ix::initialize_project_config(
+            authority,
+            PROJECT_ID,
+            *authority,
+            vec![],
+            Pubkey::new_unique(),
+            0,
+            vec![],
+            0,
+            fee_bps,
+        ),
+        ix::update_project_config(
+            authority,
+            PROJECT_ID,
+            *authority,
+            vec![],
+            Pubkey::new_unique(),
+            0,
+            vec![],
+            0,
+            fee_bps,
+        ),
+    ]
+}
+
+#[test]
+fn upgrade_authority_initializes_house() {
+    let (mut bank, authority) = setup();
+    initialize_house(&mut bank, &authority).unwrap();
+
+    let config: HouseConfig = bank.get(&pda::house_config().0);
+    assert_eq!(config.authority, authority);
+    assert_eq!(config.fee_bps, HOUSE_FEE_BPS);
+}
+
+#[test]
+fn house_cannot_be_front_run() {
+    let (mut bank, authority) = setup();
+    let squatter = Pubkey::new_unique();
+    bank.airdrop(&squatter, 10 * SOL);
+
+    assert_auction_error(
+        initialize_house(&mut bank, &squatter),
+        AuctionError::NotUpgradeAuthority,
+    );
+    initialize_house(&mut bank, &authority).unwrap();
+}
+
+#[test]
+fn house_fee_is_bounded() {
+    let (mut bank, authority) = setup();
+    assert_auction_error(
+        initialize_house_with_fee(&mut bank, &authority, MAX_HOUSE_FEE_BPS + 1),
+        AuctionError::InvalidFeeBps,
+    );
+    initialize_house_with_fee(&mut bank, &authority, MAX_HOUSE_FEE_BPS).unwrap();
+
+    let update = |fee_bps| {
+        ix::update_house_config(
+            &authority,
+            authority,
+            vec![],
+            Pubkey::new_unique(),
+            fee_bps,
+            0,
+            0,
+        )
+    };
+    assert_auction_error(
+        bank.process(&[update(MAX_HOUSE_FEE_BPS + 1)], &[authority]),
+        AuctionError::InvalidFeeBps,
+    );
+    // the referral shares are summed without overflowing
+    let overflowing = ix::update_house_config(
+        &authority,
+        authority,
+        vec![],
+        Pubkey::new_unique(),
+        0,
+        u16::MAX,
+        u16::MAX,
+    );
+    assert_auction_error(
+        bank.process(&[overflowing], &[authority]),
+        AuctionError::InvalidFeeBps,
+    );
+}
+
+#[test]
+fn project_fee_is_bounded() {
+    let (mut bank, authority) = setup();
+    initialize_house(&mut bank, &authority).unwrap();
+
+    let [initialize, _] = project_config_instructions(&authority, MAX_PROJECT_FEE_BPS + 1);
+    assert_auction_error(
+        bank.process(&[initialize], &[authority]),
+        AuctionError::InvalidFeeBps,
+    );
+    let [initialize, _] = project_config_instructions(&authority, MAX_PROJECT_FEE_BPS);
+    bank.process(&[initialize], &[authority]).unwrap();
+    let [_, update] = project_config_instructions(&authority, MAX_PROJECT_FEE_BPS + 1);
+    assert_auction_error(
+        bank.process(&[update], &[authority]),
+        AuctionError::InvalidFeeBps,
+    );
+}
@@ -0,0 +1,252 @@
+mod common;
+
+use auctionhouse::account::DutchAuction;
+use auctionhouse::error::AuctionError;
+use auctionhouse_client::instruction::{self as ix, CreateDutchAuctionParams};
+use auctionhouse_client::pda;
+use common::{assert_auction_error, Bank, SOL};
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+const PROJECT_ID: u16 = 1;
+const START_PRICE: u64 = 1_000;
+const FLOOR_PRICE: u64 = 100;
+
+struct Fixture {
+    bank: Bank,
+    owner: Pubkey,
+    mint_authority: Pubkey,
+    mint: Pubkey,
+    token_mint: Pubkey,
+    buyer: Pubkey,
+    auction: Pubkey,
+    token_program: Pubkey,
+    bid_token_program: Pubkey,
+}
+
+impl Fixture {
+    /// A house and project config, an NFT and a bid token, a funded buyer and
+    /// a dutch auction of the NFT running from now + 10 to now + 1000.
+    fn new() -> Self {
+        Self::with_token_programs(spl_token::ID, spl_token::ID, 0)
+    }
+
+    /// Like new, with the NFT a mint of token_program and the bid token one of
+    /// bid_token_program charging transfer_fee_bps.
+    fn with_token_programs(
+        token_program: Pubkey,
+        bid_token_program: Pubkey,
+        transfer_fee_bps: u16,
+    ) -> Self {
+        let mut bank = Bank::new();
+        let authority = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let buyer = Pubkey::new_unique();
+        let mint_authority = Pubkey::new_unique();
+        for wallet in [authority, owner, buyer] {
+            bank.airdrop(&wallet, 10 * SOL);
+        }
+        bank.set_upgrade_authority(&authority);
+
+        let mint = bank.create_mint_with(&mint_authority, 0, &token_program, 0);
+        let token_mint =
+            bank.create_mint_with(&mint_authority, 6, &bid_token_program, transfer_fee_bps);
+        bank.mint_to(&mint, &mint_authority, &owner, 1);
+        bank.mint_to(&token_mint, &mint_authority, &buyer, 1_000_000);
+
+        bank.process(
+            &[
+                ix::initialize_house_config(&authority, vec![owner], Pubkey::new_unique(), 0, 0, 0),
+                ix::initialize_project_config(
+                    &authority,
+                    PROJECT_ID,
+                    authority,
+                    vec![],
+                    Pubkey::new_unique(),
+                    0,
+                    vec![token_mint],
+                    0,
+                    0,
+                ),
+            ],
+            &[authority],
+        )
+        .unwrap();
+
+        let params = CreateDutchAuctionParams {
+            title: "dutch auction".to_string(),
+            start_price: START_PRICE,
+            floor_price: FLOOR_PRICE,
+            start_time: bank.now() + 10,
+            end_time: bank.now() + 1000,
+            step_interval: 0,
+            token_amount: 1,
+            project_id: PROJECT_ID,
+        };
+        let auction = pda::dutch_auction(&owner, &params.title).0;
+        bank.process(
+            &[ix::create_dutch_auction(
+                &owner,
+                &mint,
+                &token_mint,
+                &token_program,
+                params,
+            )],
+            &[owner],
+        )
+        .unwrap();
+
+        Fixture {
+            bank,
+            owner,
+            mint_authority,
+            mint,
+            token_mint,
+            buyer,
+            auction,
+            token_program,
+            bid_token_program,
+        }
+    }
+
+    fn state(&self) -> DutchAuction {
+        self.bank.get(&self.auction)
+    }
+
+    fn start(&mut self) {
+        let start_time = self.state().start_time;
+        self.bank.warp_to(start_time + 1);
+    }
+
+    fn buy_instruction(&self, amount: u64) -> Instruction {
+        ix::buy_dutch(
+            &self.auction,
+            &self.state(),
+            &self.buyer,
+            amount,
+            &self.token_program,
+            &self.bid_token_program,
+        )
+    }
+
+    fn bid_balance(&self, wallet: &Pubkey) -> u64 {
+        self.bank.token_balance(&pda::token_account(
+            wallet,
+            &self.token_mint,
+            &self.bid_token_program,
+        ))
+    }
+
+    fn item_balance(&self, wallet: &Pubkey) -> u64 {
+        self.bank
+            .token_balance(&pda::token_account(wallet, &self.mint, &self.token_program))
+    }
+}
+
+#[test]
+fn buy_pays_owner() {
+    let mut fixture = Fixture::new();
+    fixture.start();
+    let price = fixture.state().current_price(fixture.bank.now());
+
+    let instruction = fixture.buy_instruction(START_PRICE);
+    fixture
+        .bank
+        .process(&[instruction], &[fixture.buyer])
+        .unwrap();
+
+    let state = fixture.state();
+    assert_eq!(state.sale_price, price);
+    assert_eq!(fixture.bid_balance(&fixture.owner), price);
+    assert_eq!(fixture.bid_balance(&fixture.buyer), 1_000_000 - price);
+    assert_eq!(fixture.item_balance(&fixture.buyer), 1);
+}
+
+#[test]
+fn buyer_cannot_pay_themselves() {
+    let mut fixture = Fixture::new();
+    fixture.start();
+
+    // the price is routed back into the buyer's own token account
+    let owner_ata = pda::token_account(&fixture.owner, &fixture.token_mint, &spl_token::ID);
+    let buyer_ata = pda::token_account(&fixture.buyer, &fixture.token_mint, &spl_token::ID);
+    let mut instruction = fixture.buy_instruction(START_PRICE);
+    for meta in instruction.accounts.iter_mut() {
+        if meta.pubkey == owner_ata {
+            meta.pubkey = buyer_ata;
+        }
+    }
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[fixture.buyer]),
+        AuctionError::InvalidTokenAccount,
+    );
+    assert_eq!(fixture.item_balance(&fixture.buyer), 0);
+    assert_eq!(fixture.state().sale_price, 0);
+}
+
+#[test]
+fn reclaim_item_to_owner_account_of_mint() {
+    let mut fixture = Fixture::new();
+    let end_time = fixture.state().end_time;
+    fixture.bank.warp_to(end_time + 1);
+
+    // the owner's account of the bid token is not an account for the item
+    let mut instruction =
+        ix::reclaim_item_dutch(&fixture.auction, &fixture.state(), &spl_token::ID);
+    let item_ata = pda::token_account(&fixture.owner, &fixture.mint, &spl_token::ID);
+    let owner_token_ata = pda::token_account(&fixture.owner, &fixture.token_mint, &spl_token::ID);
+    let (token_mint, mint_authority, owner) =
+        (fixture.token_mint, fixture.mint_authority, fixture.owner);
+    fixture
+        .bank
+        .mint_to(&token_mint, &mint_authority, &owner, 0);
+    for meta in instruction.accounts.iter_mut() {
+        if meta.pubkey == item_ata {
+            meta.pubkey = owner_token_ata;
+        }
+    }
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[fixture.owner]),
+        AuctionError::InvalidTokenAccount,
+    );
+
+    let instruction = ix::reclaim_item_dutch(&fixture.auction, &fixture.state(), &spl_token::ID);
+    fixture
+        .bank
+        .process(&[instruction], &[fixture.owner])
+        .unwrap();
+    assert_eq!(fixture.item_balance(&fixture.owner), 1);
+}
+
+#[test]
+fn buy_with_token_2022_transfer_fee() {
+    const FEE_BPS: u16 = 100;
+    let fee = |amount: u64| (amount * FEE_BPS as u64).div_ceil(10_000);
+    let mut fixture = Fixture::with_token_programs(spl_token_2022::ID, spl_token_2022::ID, FEE_BPS);
+    fixture.start();
+    let price = fixture.state().current_price(fixture.bank.now());
+
+    // the fee on the way into escrow leaves less than the price
+    let instruction = fixture.buy_instruction(price);
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[fixture.buyer]),
+        AuctionError::InsufficientBid,
+    );
+
+    let amount = 2 * START_PRICE;
+    let instruction = fixture.buy_instruction(amount);
+    fixture
+        .bank
+        .process(&[instruction], &[fixture.buyer])
+        .unwrap();
+
+    let refund = amount - fee(amount) - price;
+    assert_eq!(fixture.state().sale_price, price);
+    assert_eq!(fixture.bid_balance(&fixture.owner), price - fee(price));
+    assert_eq!(
+        fixture.bid_balance(&fixture.buyer),
+        1_000_000 - amount + refund - fee(refund)
+    );
+    assert_eq!(fixture.bid_balance(&fixture.auction), 0);
+    assert_eq!(fixture.item_balance(&fixture.buyer), 1);
+}
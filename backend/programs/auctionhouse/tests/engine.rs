@@ -0,0 +1,647 @@
+use auctionhouse::engine::{Auction, Bid};
+use auctionhouse::error::AuctionError;
+use proptest::prelude::*;
+use solana_program::pubkey::Pubkey;
+
+const BIDDERS: usize = 4;
+const START_TIME: u64 = 100;
+const END_TIME: u64 = 1_000;
+
+fn owner() -> Pubkey {
+    Pubkey::new_from_array([0xff; 32])
+}
+
+fn bidder(index: usize) -> Pubkey {
+    Pubkey::new_from_array([index as u8 + 1; 32])
+}
+
+fn auction() -> impl Strategy<Value = Auction> {
+    (
+        (0..500u64, 1..100u64, 0..=BIDDERS as u64),
+        (0..100u64, 0..200u64, prop::option::of(0..300u64)),
+        (any::<bool>(), any::<bool>(), 0..100u64),
+        (0..1_000u64, any::<bool>()),
+    )
+        .prop_map(
+            |(
+                (bid_floor, min_bid_increment, bidder_cap),
+                (extension_window, extension_amount, max_extension),
+                (has_reserve, reserve_revealed, reserve_reveal_window),
+                (retract_cutoff, leader_can_retract),
+            )| Auction {
+                owner: owner(),
+                start_time: START_TIME,
+                end_time: END_TIME,
+                extension_window,
+                extension_amount,
+                max_end_time: max_extension.map_or(0, |extension| END_TIME + extension),
+                has_reserve,
+                reserve_revealed,
+                reserve_reveal_window,
+                retract_cutoff,
+                leader_can_retract,
+                bidder_cap,
+                bid_floor,
+                min_bid_increment,
+                ..Auction::default()
+            },
+        )
+}
+
+/// An auction whose bids commit to hidden maximums.
+fn hidden_auction() -> impl Strategy<Value = Auction> {
+    (auction(), 1..2_000u64).prop_map(|(auction, max_bid_reveal_window)| Auction {
+        max_bid_reveal_window,
+        has_reserve: false,
+        ..auction
+    })
+}
+
+/// Amounts within a thousand of u64::MAX.
+fn near_max() -> impl Strategy<Value = u64> {
+    (0..1_000u64).prop_map(|below| u64::MAX - below)
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    // below is how far under the escrow a hidden maximum is committed
+    Bid {
+        bidder: usize,
+        amount: u64,
+        below: u64,
+    },
+    Reveal(usize),
+    Reclaim(usize),
+    Retract(usize),
+    Cancel,
+    Settle,
+    Warp(u64),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        6 => (0..BIDDERS, 0..2_000u64, 0..200u64)
+            .prop_map(|(bidder, amount, below)| Op::Bid { bidder, amount, below }),
+        2 => (0..BIDDERS).prop_map(Op::Reveal),
+        2 => (0..BIDDERS).prop_map(Op::Reclaim),
+        2 => (0..BIDDERS).prop_map(Op::Retract),
+        1 => Just(Op::Cancel),
+        1 => Just(Op::Settle),
+        3 => (0..300u64).prop_map(Op::Warp),
+    ]
+}
+
+/// The auction with every bidder's escrow and the tokens the auction holds.
+struct Model {
+    auction: Auction,
+    bids: [Bid; BIDDERS],
+    // each bidder's committed hidden maximum
+    maxes: [u64; BIDDERS],
+    // the revealed maximums, with when they were committed
+    revealed: [Option<(u64, u64)>; BIDDERS],
+    escrow: u64,
+    cur_time: u64,
+}
+
+impl Model {
+    fn leader(&self) -> Option<usize> {
+        (0..BIDDERS).find(|&index| bidder(index) == self.auction.highest_bidder)
+    }
+
+    fn check_invariants(&self) {
+        let auction = &self.auction;
+
+        // escrow holds every outstanding bid and nothing else
+        let outstanding: Vec<&Bid> = self
+            .bids
+            .iter()
+            .filter(|bid| !bid.claimed && bid.amount > 0)
+            .collect();
+        assert_eq!(
+            self.escrow,
+            self.bids.iter().map(|bid| bid.amount).sum::<u64>()
+        );
+        assert_eq!(auction.outstanding_bids, outstanding.len() as u64);
+        assert!(self.bids.iter().all(|bid| !bid.claimed || bid.amount == 0));
+
+        let bidders = self
+            .bids
+            .iter()
+            .filter(|bid| bid.bidder != Pubkey::default())
+            .count() as u64;
+        assert_eq!(auction.bidder_count, bidders);
+        assert!(auction.bidder_count <= auction.bidder_cap);
+
+        if auction.hides_max_bids() {
+            self.check_revealed();
+        }
+
+        match self.leader() {
+            None => {
+                assert_eq!(auction.highest_bidder, Pubkey::default());
+                assert_eq!(auction.highest_bid, 0);
+            }
+            Some(leader) if auction.hides_max_bids() => {
+                if auction.is_winning_bid_locked(&bidder(leader), self.cur_time) {
+                    assert!(self.bids[leader].amount >= auction.highest_max_bid);
+                }
+            }
+            Some(leader) => {
+                assert!(auction.highest_bid >= auction.min_opening_bid());
+                assert!(auction.highest_bid <= auction.highest_max_bid);
+                if !auction.winning_bid_paid {
+                    // nobody else's maximum beats the leader's
+                    assert!(self
+                        .bids
+                        .iter()
+                        .all(|bid| bid.amount <= auction.highest_max_bid));
+                    if auction.is_winning_bid_locked(&bidder(leader), self.cur_time) {
+                        assert_eq!(self.bids[leader].amount, auction.highest_max_bid);
+                    }
+                }
+            }
+        }
+
+        if auction.max_end_time != 0 {
+            assert!(auction.end_time <= auction.max_end_time);
+        }
+    }
+
+    /// The leader and standing bid are what open bidding with the revealed
+    /// maximums would have reached, in whatever order they were revealed.
+    fn check_revealed(&self) {
+        let auction = &self.auction;
+        let mut revealed: Vec<(u64, u64)> = self.revealed.iter().flatten().copied().collect();
+        // highest maximum first, the earlier commitment on equal maximums
+        revealed.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        match revealed[..] {
+            [] => assert_eq!(auction.highest_bidder, Pubkey::default()),
+            [(first, at), ..] => {
+                let second = revealed.get(1).map(|&(max_bid, _)| max_bid);
+                let highest_bid = second.map_or(auction.min_opening_bid(), |second| {
+                    auction
+                        .min_opening_bid()
+                        .max(first.min(second + auction.min_bid_increment))
+                });
+                assert_eq!(auction.highest_max_bid, first);
+                assert_eq!(auction.highest_bid, highest_bid);
+                let leader = self.leader().unwrap();
+                assert_eq!(self.revealed[leader].unwrap().0, first);
+                // equal maximums committed in the same second go to the
+                // first reveal
+                if revealed.get(1) != Some(&(first, at)) {
+                    assert_eq!(self.revealed[leader], Some((first, at)));
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, op: &Op) -> Result<(), AuctionError> {
+        let before = self.auction;
+        match *op {
+            Op::Bid {
+                bidder: index,
+                amount,
+                below,
+            } => {
+                let mut bid = self.bids[index];
+                let result =
+                    self.auction
+                        .place_bid(&mut bid, &bidder(index), amount, self.cur_time);
+                match result {
+                    Ok(placed) => {
+                        assert_eq!(placed.amount, amount);
+                        assert_eq!(placed.total_bid, bid.amount);
+                        assert_eq!(placed.previous_leader, before.highest_bidder);
+                        assert!(self.cur_time > before.start_time);
+                        assert!(self.cur_time < before.end_time);
+                        // the standing bid and end time only move up
+                        assert!(self.auction.highest_bid >= before.highest_bid);
+                        assert!(self.auction.end_time >= before.end_time);
+                        assert_eq!(
+                            placed.extension.is_some(),
+                            self.auction.end_time > before.end_time
+                        );
+                        self.bids[index] = bid;
+                        self.maxes[index] = bid.amount.saturating_sub(below);
+                        self.escrow += amount;
+                    }
+                    Err(err) => {
+                        assert_eq!(bid, self.bids[index]);
+                        return Err(err);
+                    }
+                }
+            }
+            Op::Reveal(index) => {
+                let mut bid = self.bids[index];
+                let max_bid = self.maxes[index];
+                match self
+                    .auction
+                    .reveal_max_bid(&mut bid, max_bid, self.cur_time)
+                {
+                    Ok(()) => {
+                        assert!(before.hides_max_bids());
+                        assert!(self.cur_time > before.end_time);
+                        assert!(self.revealed[index].is_none());
+                        assert!(max_bid <= bid.amount);
+                        self.bids[index] = bid;
+                        self.revealed[index] = Some((max_bid, bid.updated_at));
+                    }
+                    Err(err) => {
+                        assert_eq!(bid, self.bids[index]);
+                        return Err(err);
+                    }
+                }
+            }
+            Op::Reclaim(index) => {
+                let mut bid = self.bids[index];
+                let locked = before.is_winning_bid_locked(&bid.bidder, self.cur_time);
+                match self.auction.reclaim(&mut bid, self.cur_time) {
+                    Ok(reclaimed) => {
+                        assert!(!locked);
+                        assert_eq!(reclaimed.amount, self.bids[index].amount);
+                        assert_eq!(bid.amount, 0);
+                        self.bids[index] = bid;
+                        self.escrow -= reclaimed.amount;
+                    }
+                    Err(err) => {
+                        assert_eq!(bid, self.bids[index]);
+                        return Err(err);
+                    }
+                }
+            }
+            Op::Retract(index) => {
+                let mut bid = self.bids[index];
+                let others: Vec<Bid> = (0..BIDDERS)
+                    .filter(|&other| other != index)
+                    .map(|other| self.bids[other])
+                    .filter(|other| !other.claimed && other.amount > 0)
+                    .collect();
+                match self.auction.retract(&mut bid, &others, self.cur_time) {
+                    Ok(retracted) => {
+                        assert!(before.is_retraction_open(self.cur_time));
+                        assert!(before.leader_can_retract || bid.bidder != before.highest_bidder);
+                        assert_eq!(retracted.amount, self.bids[index].amount);
+                        assert_ne!(self.auction.highest_bidder, bid.bidder);
+                        self.bids[index] = bid;
+                        self.escrow -= retracted.amount;
+                    }
+                    Err(err) => {
+                        assert_eq!(bid, self.bids[index]);
+                        return Err(err);
+                    }
+                }
+            }
+            Op::Cancel => {
+                self.auction.cancel(self.cur_time)?;
+                assert!(self.cur_time < before.end_time);
+            }
+            Op::Settle => {
+                let leader = self.leader().unwrap_or(0);
+                let mut bid = self.bids[leader];
+                match self.auction.settle(&mut bid, self.cur_time) {
+                    Ok(settlement) => {
+                        assert!(!before.cancelled && !before.winning_bid_paid);
+                        assert!(self.cur_time > before.end_time);
+                        assert_eq!(bid.bidder, before.highest_bidder);
+                        assert_eq!(settlement.winning_bid, before.highest_bid);
+                        assert_eq!(settlement.excess, bid.amount);
+                        self.bids[leader] = bid;
+                        self.escrow -= settlement.winning_bid;
+                    }
+                    Err(err) => {
+                        assert_eq!(bid, self.bids[leader]);
+                        return Err(err);
+                    }
+                }
+            }
+            Op::Warp(seconds) => self.cur_time += seconds,
+        }
+        Ok(())
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn invariants_hold(
+        auction in prop_oneof![auction(), hidden_auction()],
+        ops in prop::collection::vec(op(), 1..60),
+        reveal_order in Just((0..BIDDERS).collect::<Vec<usize>>()).prop_shuffle(),
+    ) {
+        let mut model = Model {
+            auction,
+            bids: [Bid::default(); BIDDERS],
+            maxes: [0; BIDDERS],
+            revealed: [None; BIDDERS],
+            escrow: 0,
+            cur_time: START_TIME - 50,
+        };
+
+        for op in ops.iter() {
+            let before = model.auction;
+            if model.step(op).is_err() {
+                // failed transitions change nothing
+                prop_assert_eq!(model.auction, before);
+            }
+            model.check_invariants();
+        }
+
+        // hidden maximums still committed are revealed once bidding closes
+        if model.auction.hides_max_bids() {
+            model.cur_time = model.cur_time.max(model.auction.end_time + 1);
+            for &index in reveal_order.iter() {
+                let _ = model.step(&Op::Reveal(index));
+                model.check_invariants();
+            }
+        }
+
+        // every bid can be paid out once the auction is over, an unrevealed
+        // reserve voiding the sale
+        model.cur_time = model.auction.end_time
+            + model.auction.reserve_reveal_window
+            + model.auction.max_bid_reveal_window
+            + 1;
+        let auction = model.auction;
+        if model.leader().is_some()
+            && !auction.cancelled
+            && !auction.winning_bid_paid
+            && !auction.reserve_forfeited(model.cur_time)
+        {
+            model.step(&Op::Settle).unwrap();
+        }
+        for index in 0..BIDDERS {
+            let _ = model.step(&Op::Reclaim(index));
+        }
+        prop_assert_eq!(model.escrow, 0);
+        prop_assert_eq!(model.auction.outstanding_bids, 0);
+    }
+
+    #[test]
+    fn leader_maximum_covers_lower_bids(
+        auction in auction(),
+        leader_max in 1..10_000u64,
+        challenge in 1..10_000u64,
+    ) {
+        let mut auction = Auction { bidder_cap: 2, ..auction };
+        let (alice, bob) = (bidder(0), bidder(1));
+        let (mut alice_bid, mut bob_bid) = (Bid::default(), Bid::default());
+        let cur_time = START_TIME + 1;
+
+        prop_assume!(auction.place_bid(&mut alice_bid, &alice, leader_max, cur_time).is_ok());
+        let opening = auction.highest_bid;
+        prop_assert_eq!(opening, auction.min_opening_bid());
+
+        match auction.place_bid(&mut bob_bid, &bob, challenge, cur_time) {
+            Ok(_) if challenge > leader_max => {
+                // bob takes the lead one increment over alice's maximum at most
+                prop_assert_eq!(auction.highest_bidder, bob);
+                prop_assert!(auction.highest_bid <= leader_max + auction.min_bid_increment);
+                prop_assert!(auction.highest_bid >= opening + auction.min_bid_increment);
+            }
+            Ok(_) => {
+                // alice's maximum answers for her
+                prop_assert_eq!(auction.highest_bidder, alice);
+                prop_assert_eq!(
+                    auction.highest_bid,
+                    leader_max.min(challenge + auction.min_bid_increment)
+                );
+            }
+            Err(err) => {
+                prop_assert!(challenge < opening + auction.min_bid_increment);
+                prop_assert!(matches!(
+                    err,
+                    AuctionError::InsufficientBid | AuctionError::UnderBidFloor
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn reveal_order_does_not_matter(
+        auction in hidden_auction(),
+        maxes in prop::collection::vec(1..2_000u64, BIDDERS),
+        order in Just((0..BIDDERS).collect::<Vec<usize>>()).prop_shuffle(),
+    ) {
+        let mut auction = Auction { bidder_cap: BIDDERS as u64, ..auction };
+        let mut bids = [Bid::default(); BIDDERS];
+        // a second apart, so no two maximums are committed at once
+        for (index, &max_bid) in maxes.iter().enumerate() {
+            let cur_time = START_TIME + 1 + index as u64;
+            let placed = auction.place_bid(&mut bids[index], &bidder(index), max_bid, cur_time);
+            prop_assume!(placed.is_ok());
+        }
+
+        let reveal = |order: &[usize]| {
+            let (mut auction, mut bids) = (auction, bids);
+            for &index in order {
+                let cur_time = auction.end_time + 1;
+                let _ = auction.reveal_max_bid(&mut bids[index], maxes[index], cur_time);
+            }
+            auction
+        };
+        let in_order: Vec<usize> = (0..BIDDERS).collect();
+        prop_assert_eq!(reveal(&order), reveal(&in_order));
+    }
+
+    #[test]
+    fn bids_outside_the_bidding_window_fail(auction in auction(), cur_time in 0..2 * END_TIME) {
+        let mut auction = auction;
+        let mut bid = Bid::default();
+        let result = auction.place_bid(&mut bid, &bidder(0), u64::MAX / 2, cur_time);
+
+        if cur_time <= START_TIME {
+            prop_assert!(matches!(result, Err(AuctionError::BidBeforeStart)));
+        } else if cur_time >= END_TIME {
+            prop_assert!(matches!(result, Err(AuctionError::BidAfterClose)));
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn transitions_near_u64_max_fail_cleanly(
+        auction in auction(),
+        min_bid_increment in prop_oneof![1..100u64, near_max()],
+        extension_amount in prop_oneof![0..200u64, near_max()],
+        bids in prop::collection::vec(
+            (0..BIDDERS, prop_oneof![0..2_000u64, near_max()]),
+            1..20,
+        ),
+    ) {
+        let mut auction = Auction {
+            min_bid_increment,
+            extension_amount,
+            max_end_time: 0,
+            has_reserve: false,
+            bidder_cap: BIDDERS as u64,
+            ..auction
+        };
+        let mut escrows = [Bid::default(); BIDDERS];
+        let cur_time = END_TIME - 1;
+
+        for &(index, amount) in bids.iter() {
+            let before = (auction, escrows[index]);
+            let mut bid = escrows[index];
+            match auction.place_bid(&mut bid, &bidder(index), amount, cur_time) {
+                Ok(_) => escrows[index] = bid,
+                Err(err) => {
+                    // an overflow is an error like any other, not a panic
+                    prop_assert_eq!((auction, bid), before);
+                    prop_assert!(!matches!(err, AuctionError::BidAfterClose));
+                }
+            }
+        }
+
+        // the leader's escrow always covers the standing bid
+        let leader = (0..BIDDERS).find(|&index| bidder(index) == auction.highest_bidder);
+        if let (Some(leader), Some(cur_time)) = (leader, auction.end_time.checked_add(1)) {
+            let mut bid = escrows[leader];
+            let settlement = auction.settle(&mut bid, cur_time).unwrap();
+            prop_assert_eq!(settlement.winning_bid + bid.amount, escrows[leader].amount);
+        }
+    }
+
+    #[test]
+    fn settle_from_short_escrow_fails(highest_bid in near_max(), short in 1..1_000u64) {
+        let mut auction = Auction {
+            owner: owner(),
+            start_time: START_TIME,
+            end_time: END_TIME,
+            highest_bidder: bidder(0),
+            highest_bid,
+            highest_max_bid: highest_bid,
+            outstanding_bids: 1,
+            ..Auction::default()
+        };
+        let mut bid = Bid {
+            bidder: bidder(0),
+            amount: highest_bid - short,
+            ..Bid::default()
+        };
+        let before = (auction, bid);
+
+        prop_assert!(matches!(
+            auction.settle(&mut bid, END_TIME + 1),
+            Err(AuctionError::ArithmeticOverflow)
+        ));
+        prop_assert_eq!((auction, bid), before);
+    }
+}
+
+#[test]
+fn leader_cannot_pass_own_bid_when_retracting() {
+    let mut auction = Auction {
+        owner: owner(),
+        start_time: START_TIME,
+        end_time: END_TIME,
+        bidder_cap: 2,
+        min_bid_increment: 1,
+        retract_cutoff: 100,
+        leader_can_retract: true,
+        ..Auction::default()
+    };
+    let (mut alice_bid, mut bob_bid) = (Bid::default(), Bid::default());
+    auction
+        .place_bid(&mut alice_bid, &bidder(0), 200, START_TIME + 1)
+        .unwrap();
+    auction
+        .place_bid(&mut bob_bid, &bidder(1), 300, START_TIME + 2)
+        .unwrap();
+    let before = (auction, bob_bid);
+
+    // bob's own bid stands in for alice's
+    let stale = bob_bid;
+    let result = auction.retract(&mut bob_bid, &[stale], START_TIME + 3);
+    assert!(matches!(result, Err(AuctionError::InvalidBidRecord)));
+    assert_eq!((auction, bob_bid), before);
+    let result = auction.retract(&mut bob_bid, &[], START_TIME + 3);
+    assert!(matches!(result, Err(AuctionError::InvalidBidRecord)));
+
+    let retracted = auction
+        .retract(&mut bob_bid, &[alice_bid], START_TIME + 3)
+        .unwrap();
+    assert_eq!(retracted.amount, 300);
+    assert_eq!(auction.highest_bidder, bidder(0));
+    assert_eq!(auction.highest_bid, auction.min_opening_bid());
+    assert_eq!(auction.highest_max_bid, 200);
+}
+
+#[test]
+fn owner_cannot_bid() {
+    let mut auction = Auction {
+        owner: owner(),
+        start_time: START_TIME,
+        end_time: END_TIME,
+        bidder_cap: 1,
+        min_bid_increment: 1,
+        ..Auction::default()
+    };
+    let mut bid = Bid::default();
+
+    assert!(matches!(
+        auction.place_bid(&mut bid, &owner(), 100, START_TIME + 1),
+        Err(AuctionError::OwnerCannotBid)
+    ));
+}
+
+#[test]
+fn settle_without_bids_fails() {
+    let mut auction = Auction {
+        owner: owner(),
+        start_time: START_TIME,
+        end_time: END_TIME,
+        ..Auction::default()
+    };
+    let before = auction;
+
+    assert!(matches!(
+        auction.settle(&mut Bid::default(), END_TIME + 1),
+        Err(AuctionError::NoWinningBid)
+    ));
+    assert_eq!(auction, before);
+}
+
+#[test]
+fn equal_hidden_maximums_go_to_the_first_commitment() {
+    let mut auction = Auction {
+        owner: owner(),
+        start_time: START_TIME,
+        end_time: END_TIME,
+        bidder_cap: 2,
+        min_bid_increment: 10,
+        max_bid_reveal_window: 100,
+        ..Auction::default()
+    };
+    let (mut alice_bid, mut bob_bid) = (Bid::default(), Bid::default());
+    auction
+        .place_bid(&mut alice_bid, &bidder(0), 600, START_TIME + 1)
+        .unwrap();
+    auction
+        .place_bid(&mut bob_bid, &bidder(1), 500, START_TIME + 2)
+        .unwrap();
+    // nobody leads while maximums are hidden
+    assert_eq!(auction.highest_bidder, Pubkey::default());
+
+    // bob reveals first, alice's equal maximum committed earlier still wins
+    auction
+        .reveal_max_bid(&mut bob_bid, 500, END_TIME + 1)
+        .unwrap();
+    assert_eq!(auction.highest_bidder, bidder(1));
+    auction
+        .reveal_max_bid(&mut alice_bid, 500, END_TIME + 1)
+        .unwrap();
+    assert_eq!(auction.highest_bidder, bidder(0));
+    assert_eq!(auction.highest_bid, 500);
+    assert!(matches!(
+        auction.reveal_max_bid(&mut alice_bid, 500, END_TIME + 1),
+        Err(AuctionError::NoMaxBidToReveal)
+    ));
+
+    // alice's over-escrow comes back with the rest once paid
+    let settlement = auction.settle(&mut alice_bid, END_TIME + 101).unwrap();
+    assert_eq!(settlement.winning_bid, 500);
+    assert_eq!(settlement.excess, 100);
+}
@@ -0,0 +1,1481 @@
+mod common;
+
+use anchor_lang::__private::ErrorCode;
+use auctionhouse::account::{BidRecord, OpenAuction, ReferralRecord};
+use auctionhouse::error::AuctionError;
+use auctionhouse::event::{AllowlistUpdated, MaxBidRevealed, ReserveRevealed};
+use auctionhouse::utils::{compute_allowlist_leaf, compute_max_bid_hash, compute_reserve_hash};
+use auctionhouse_client::instruction::{self as ix, CreateOpenAuctionParams};
+use auctionhouse_client::pda;
+use common::{assert_auction_error, Bank, SOL, START_TIME};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+
+const PROJECT_ID: u16 = 1;
+const FLOOR: u64 = 100;
+const INCREMENT: u64 = 10;
+const RECLAIM_FEE: u64 = 5_000;
+const HOUSE_FEE_BPS: u16 = 250;
+const PROJECT_FEE_BPS: u16 = 100;
+const CRANK_REWARD: u64 = 1_000;
+const BID_REFERRAL_BPS: u16 = 2_000;
+const CREATION_REFERRAL_BPS: u16 = 1_000;
+const MAX_BID_NONCE: u64 = 42;
+
+/// The token an auction takes bids in.
+#[derive(Clone, Copy)]
+enum BidToken {
+    Spl,
+    // with the item a Token-2022 NFT as well
+    Token2022 { transfer_fee_bps: u16 },
+    Native,
+}
+
+struct Fixture {
+    bank: Bank,
+    owner: Pubkey,
+    mint_authority: Pubkey,
+    house_treasury: Pubkey,
+    project_treasury: Pubkey,
+    mint: Pubkey,
+    token_mint: Pubkey,
+    bidders: Vec<Pubkey>,
+    auction: Pubkey,
+    token_program: Pubkey,
+    bid_token_program: Pubkey,
+}
+
+impl Fixture {
+    /// A house and project config, an NFT and a bid token, funded bidders and
+    /// an open auction of the NFT running from now + 10 to now + 1000.
+    fn new(params: CreateOpenAuctionParams) -> Self {
+        Self::with_bid_token(params, BidToken::Spl)
+    }
+
+    fn with_bid_token(params: CreateOpenAuctionParams, bid_token: BidToken) -> Self {
+        let mut bank = Bank::new();
+        let authority = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mint_authority = Pubkey::new_unique();
+        let house_treasury = Pubkey::new_unique();
+        let project_treasury = Pubkey::new_unique();
+        let bidders: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for wallet in [authority, owner].iter().chain(bidders.iter()) {
+            bank.airdrop(wallet, 10 * SOL);
+        }
+        bank.set_upgrade_authority(&authority);
+
+        let token_program = match bid_token {
+            BidToken::Token2022 { .. } => spl_token_2022::ID,
+            BidToken::Spl | BidToken::Native => spl_token::ID,
+        };
+        let bid_token_program = token_program;
+        let mint = bank.create_mint_with(&mint_authority, 0, &token_program, 0);
+        bank.mint_to(&mint, &mint_authority, &owner, 1);
+        let token_mint = match bid_token {
+            BidToken::Spl => bank.create_mint(&mint_authority, 6),
+            BidToken::Token2022 { transfer_fee_bps } => {
+                bank.create_mint_with(&mint_authority, 6, &bid_token_program, transfer_fee_bps)
+            }
+            // bids are the wallets' own lamports
+            BidToken::Native => spl_token::native_mint::ID,
+        };
+        if !matches!(bid_token, BidToken::Native) {
+            for bidder in bidders.iter() {
+                bank.mint_to(&token_mint, &mint_authority, bidder, 1_000_000);
+            }
+            for wallet in [owner, house_treasury, project_treasury] {
+                bank.mint_to(&token_mint, &mint_authority, &wallet, 0);
+            }
+        }
+
+        bank.process(
+            &[
+                ix::initialize_house_config(
+                    &authority,
+                    vec![owner],
+                    house_treasury,
+                    HOUSE_FEE_BPS,
+                    BID_REFERRAL_BPS,
+                    CREATION_REFERRAL_BPS,
+                ),
+                ix::initialize_project_config(
+                    &authority,
+                    PROJECT_ID,
+                    authority,
+                    vec![],
+                    project_treasury,
+                    RECLAIM_FEE,
+                    vec![token_mint],
+                    CRANK_REWARD,
+                    PROJECT_FEE_BPS,
+                ),
+            ],
+            &[authority],
+        )
+        .unwrap();
+
+        let auction = pda::open_auction(&owner, &params.title).0;
+        let params = CreateOpenAuctionParams {
+            floor: FLOOR,
+            increment: INCREMENT,
+            start_time: bank.now() + 10,
+            end_time: bank.now() + 1000,
+            token_amount: 1,
+            project_id: PROJECT_ID,
+            ..params
+        };
+        bank.process(
+            &[ix::create_open_auction(
+                &owner,
+                &mint,
+                &token_mint,
+                &token_program,
+                params,
+            )],
+            &[owner],
+        )
+        .unwrap();
+
+        Fixture {
+            bank,
+            owner,
+            mint_authority,
+            house_treasury,
+            project_treasury,
+            mint,
+            token_mint,
+            bidders,
+            auction,
+            token_program,
+            bid_token_program,
+        }
+    }
+
+    fn default_params() -> CreateOpenAuctionParams {
+        CreateOpenAuctionParams {
+            title: "open auction".to_string(),
+            bidder_cap: 10,
+            ..CreateOpenAuctionParams::default()
+        }
+    }
+
+    fn state(&self) -> OpenAuction {
+        self.bank.get(&self.auction)
+    }
+
+    fn bid_record(&self, bidder: &Pubkey) -> BidRecord {
+        self.bank.get(&pda::bid_record(&self.auction, bidder).0)
+    }
+
+    /// The wallet's balance of the bid token, its lamports for native SOL.
+    fn bid_balance(&self, wallet: &Pubkey) -> u64 {
+        let account = pda::bid_account(wallet, &self.token_mint, &self.bid_token_program);
+        if account == *wallet {
+            self.bank.lamports(wallet)
+        } else {
+            self.bank.token_balance(&account)
+        }
+    }
+
+    fn item_balance(&self, wallet: &Pubkey) -> u64 {
+        self.bank
+            .token_balance(&pda::token_account(wallet, &self.mint, &self.token_program))
+    }
+
+    fn start(&mut self) {
+        let start_time = self.state().start_time;
+        self.bank.warp_to(start_time + 1);
+    }
+
+    fn end(&mut self) {
+        let end_time = self.state().end_time;
+        self.bank.warp_to(end_time + 1);
+    }
+
+    fn bid(&mut self, bidder: &Pubkey, amount: u64) -> ProgramResult {
+        self.referred_bid(bidder, amount, &Pubkey::default())
+    }
+
+    fn referred_bid(&mut self, bidder: &Pubkey, amount: u64, referrer: &Pubkey) -> ProgramResult {
+        let state = self.state();
+        let instruction = ix::make_open_bid(
+            &self.auction,
+            &state,
+            bidder,
+            &self.bid_token_program,
+            amount,
+            vec![],
+            *referrer,
+            [0; 32],
+            None,
+        );
+        self.bank.process(&[instruction], &[*bidder])
+    }
+
+    /// Escrows amount behind a hidden maximum of max_bid.
+    fn hidden_bid(&mut self, bidder: &Pubkey, amount: u64, max_bid: u64) -> ProgramResult {
+        let state = self.state();
+        let instruction = ix::make_open_bid(
+            &self.auction,
+            &state,
+            bidder,
+            &self.bid_token_program,
+            amount,
+            vec![],
+            Pubkey::default(),
+            compute_max_bid_hash(max_bid, MAX_BID_NONCE),
+            None,
+        );
+        self.bank.process(&[instruction], &[*bidder])
+    }
+
+    fn reveal_bid(&mut self, bidder: &Pubkey, max_bid: u64, nonce: u64) -> ProgramResult {
+        let instruction = ix::reveal_open_bid(&self.auction, bidder, max_bid, nonce);
+        self.bank.process(&[instruction], &[*bidder])
+    }
+
+    fn reclaim(&mut self, bidder: &Pubkey) -> ProgramResult {
+        let state = self.state();
+        let instruction = ix::reclaim_open_bid(
+            &self.auction,
+            &state,
+            bidder,
+            &self.project_treasury,
+            &self.bid_token_program,
+        );
+        self.bank.process(&[instruction], &[*bidder])
+    }
+
+    fn cancel(&mut self) -> ProgramResult {
+        let instruction = ix::cancel_open_auction(&self.auction, &self.owner);
+        self.bank.process(&[instruction], &[self.owner])
+    }
+
+    fn reveal_reserve(&mut self, reserve: u64, nonce: u64) -> ProgramResult {
+        let instruction = ix::reveal_reserve_open(&self.auction, &self.owner, reserve, nonce);
+        self.bank.process(&[instruction], &[self.owner])
+    }
+
+    fn withdraw_item(&mut self) -> ProgramResult {
+        let state = self.state();
+        let instruction = ix::withdraw_item_open(&self.auction, &state, &self.token_program);
+        self.bank.process(&[instruction], &[state.highest_bidder])
+    }
+
+    fn settle(&mut self, payer: &Pubkey) -> ProgramResult {
+        let instruction = self.settle_instruction(payer);
+        self.bank.process(&[instruction], &[*payer])
+    }
+
+    fn settle_instruction(&self, payer: &Pubkey) -> Instruction {
+        let state = self.state();
+        ix::settle_open_auction(
+            &self.auction,
+            &state,
+            payer,
+            &self.house_treasury,
+            &self.project_treasury,
+            &Pubkey::default(),
+            &[],
+            &self.token_program,
+            &self.bid_token_program,
+        )
+    }
+
+    fn buy_now(
+        &mut self,
+        buyer: &Pubkey,
+        proof: Vec<[u8; 32]>,
+        creators: &[Pubkey],
+    ) -> ProgramResult {
+        let state = self.state();
+        let instruction = ix::buy_now_open(
+            &self.auction,
+            &state,
+            buyer,
+            &self.house_treasury,
+            &self.project_treasury,
+            proof,
+            Pubkey::default(),
+            None,
+            creators,
+            &self.token_program,
+            &self.bid_token_program,
+        );
+        self.bank.process(&[instruction], &[*buyer])
+    }
+
+    fn withdraw_winning_bid(&mut self) -> ProgramResult {
+        let state = self.state();
+        let instruction = ix::withdraw_winning_bid_open(
+            &self.auction,
+            &state,
+            &self.house_treasury,
+            &self.project_treasury,
+            &Pubkey::default(),
+            &[],
+            &self.bid_token_program,
+        );
+        self.bank.process(&[instruction], &[self.owner])
+    }
+}
+
+#[test]
+fn create_escrows_item() {
+    let fixture = Fixture::new(Fixture::default_params());
+
+    let state = fixture.state();
+    assert_eq!(state.owner, fixture.owner);
+    assert_eq!(state.token_amount, 1);
+    assert_eq!(fixture.item_balance(&fixture.owner), 0);
+    assert_eq!(fixture.item_balance(&fixture.auction), 1);
+}
+
+#[test]
+fn bid_before_start() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    let bidder = fixture.bidders[0];
+
+    assert_auction_error(fixture.bid(&bidder, 200), AuctionError::BidBeforeStart);
+
+    // start_time itself is still too early
+    let start_time = fixture.state().start_time;
+    fixture.bank.warp_to(start_time);
+    assert_auction_error(fixture.bid(&bidder, 200), AuctionError::BidBeforeStart);
+
+    fixture.bank.warp_by(1);
+    fixture.bid(&bidder, 200).unwrap();
+}
+
+#[test]
+fn bid_after_close() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    let bidder = fixture.bidders[0];
+
+    let end_time = fixture.state().end_time;
+    fixture.bank.warp_to(end_time);
+    assert_auction_error(fixture.bid(&bidder, 200), AuctionError::BidAfterClose);
+
+    fixture.bank.warp_by(100);
+    assert_auction_error(fixture.bid(&bidder, 200), AuctionError::BidAfterClose);
+    assert_eq!(fixture.bid_balance(&fixture.auction), 0);
+}
+
+#[test]
+fn owner_cannot_bid() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+
+    let owner = fixture.owner;
+    assert_auction_error(fixture.bid(&owner, 200), AuctionError::OwnerCannotBid);
+}
+
+#[test]
+fn bid_floor_and_increment() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+
+    assert_auction_error(fixture.bid(&alice, FLOOR), AuctionError::UnderBidFloor);
+    fixture.bid(&alice, FLOOR + 1).unwrap();
+
+    let state = fixture.state();
+    assert_eq!(state.highest_bidder, alice);
+    assert_eq!(state.highest_bid, INCREMENT.max(FLOOR + 1));
+
+    assert_auction_error(
+        fixture.bid(&bob, state.highest_bid + INCREMENT - 1),
+        AuctionError::InsufficientBid,
+    );
+    fixture.bid(&bob, 500).unwrap();
+
+    // bob's maximum sets the standing bid one increment over alice's
+    let state = fixture.state();
+    assert_eq!(state.highest_bidder, bob);
+    assert_eq!(state.highest_bid, FLOOR + 1 + INCREMENT);
+    assert_eq!(state.highest_max_bid, 500);
+    assert_eq!(fixture.bid_balance(&fixture.auction), FLOOR + 1 + 500);
+}
+
+#[test]
+fn bidder_cap_reached() {
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        bidder_cap: 2,
+        ..Fixture::default_params()
+    });
+    fixture.start();
+    let bidders = fixture.bidders.clone();
+
+    fixture.bid(&bidders[0], 200).unwrap();
+    fixture.bid(&bidders[1], 300).unwrap();
+    assert_auction_error(
+        fixture.bid(&bidders[2], 400),
+        AuctionError::BidderCapReached,
+    );
+
+    // bidders already in can keep raising
+    fixture.bid(&bidders[0], 200).unwrap();
+    assert_eq!(fixture.state().bidder_count, 2);
+    assert_eq!(fixture.bid_record(&bidders[0]).amount, 400);
+}
+
+#[test]
+fn anti_snipe_extension() {
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        extension_window: 60,
+        extension_amount: 120,
+        ..Fixture::default_params()
+    });
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.start();
+
+    // bids outside the window leave the end time alone
+    let end_time = fixture.state().end_time;
+    fixture.bid(&alice, 200).unwrap();
+    assert_eq!(fixture.state().end_time, end_time);
+
+    fixture.bank.warp_to(end_time - 30);
+    fixture.bid(&bob, 300).unwrap();
+    let state = fixture.state();
+    assert_eq!(state.end_time, end_time + 120);
+    assert_eq!(state.extension_count, 1);
+
+    // the old end time has passed but bidding is still open
+    fixture.bank.warp_to(end_time + 1);
+    fixture.bid(&alice, 200).unwrap();
+    assert_eq!(fixture.state().end_time, end_time + 120);
+
+    fixture.bank.warp_to(end_time + 120);
+    assert_auction_error(fixture.bid(&bob, 300), AuctionError::BidAfterClose);
+}
+
+#[test]
+fn anti_snipe_extension_capped() {
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        extension_window: 60,
+        extension_amount: 120,
+        max_end_time: START_TIME as u64 + 1050,
+        ..Fixture::default_params()
+    });
+    let state = fixture.state();
+    let max_end_time = state.end_time + 50;
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+
+    fixture.bank.warp_to(state.end_time - 30);
+    fixture.bid(&alice, 200).unwrap();
+    assert_eq!(fixture.state().end_time, max_end_time);
+    assert_eq!(fixture.state().extension_count, 1);
+
+    // at the cap, bids in the window no longer extend
+    fixture.bank.warp_to(max_end_time - 10);
+    fixture.bid(&bob, 300).unwrap();
+    assert_eq!(fixture.state().end_time, max_end_time);
+    assert_eq!(fixture.state().extension_count, 1);
+}
+
+#[test]
+fn winner_cannot_reclaim() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+
+    assert_auction_error(fixture.reclaim(&bob), AuctionError::WinnerCannotWithdrawBid);
+
+    // the outbid bidder gets their bid back less nothing, paying the fee in SOL
+    let treasury_lamports = fixture.bank.lamports(&fixture.project_treasury);
+    fixture.reclaim(&alice).unwrap();
+    assert_eq!(fixture.bid_balance(&alice), 1_000_000);
+    assert_eq!(
+        fixture.bank.lamports(&fixture.project_treasury),
+        treasury_lamports + RECLAIM_FEE
+    );
+    assert_auction_error(fixture.reclaim(&alice), AuctionError::NotBidder);
+
+    fixture.end();
+    assert_auction_error(fixture.reclaim(&bob), AuctionError::WinnerCannotWithdrawBid);
+}
+
+#[test]
+fn withdraw_before_end() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let alice = fixture.bidders[0];
+    fixture.bid(&alice, 200).unwrap();
+
+    assert_auction_error(fixture.withdraw_item(), AuctionError::AuctionNotOver);
+    assert_auction_error(fixture.withdraw_winning_bid(), AuctionError::AuctionNotOver);
+}
+
+#[test]
+fn double_withdraw() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+    fixture.end();
+
+    let winning_bid = fixture.state().highest_bid;
+    fixture.withdraw_winning_bid().unwrap();
+    assert_auction_error(
+        fixture.withdraw_winning_bid(),
+        AuctionError::AlreadyWithdrewBid,
+    );
+
+    let house_fee = winning_bid * HOUSE_FEE_BPS as u64 / 10_000;
+    let project_fee = winning_bid * PROJECT_FEE_BPS as u64 / 10_000;
+    assert_eq!(fixture.bid_balance(&fixture.house_treasury), house_fee);
+    assert_eq!(fixture.bid_balance(&fixture.project_treasury), project_fee);
+    assert_eq!(
+        fixture.bid_balance(&fixture.owner),
+        winning_bid - house_fee - project_fee
+    );
+
+    // once paid, the winner can take back what they escrowed over the bid
+    fixture.reclaim(&bob).unwrap();
+    assert_eq!(fixture.bid_balance(&bob), 1_000_000 - winning_bid);
+
+    fixture.withdraw_item().unwrap();
+    assert_eq!(fixture.item_balance(&bob), 1);
+    assert_eq!(fixture.item_balance(&fixture.auction), 0);
+}
+
+#[test]
+fn settle() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob, cranker) = (fixture.bidders[0], fixture.bidders[1], fixture.bidders[2]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+
+    assert_auction_error(fixture.settle(&cranker), AuctionError::AuctionNotOver);
+    fixture.end();
+    fixture.settle(&cranker).unwrap();
+
+    // bob wins at one increment over alice and gets his excess back
+    let winning_bid = 200 + INCREMENT;
+    let house_fee = winning_bid * HOUSE_FEE_BPS as u64 / 10_000;
+    let project_fee = winning_bid * PROJECT_FEE_BPS as u64 / 10_000;
+    let state = fixture.state();
+    assert!(state.settled);
+    assert_eq!(state.highest_bid, winning_bid);
+    assert_eq!(fixture.item_balance(&bob), 1);
+    assert_eq!(fixture.bid_balance(&bob), 1_000_000 - winning_bid);
+    assert_eq!(
+        fixture.bid_balance(&fixture.owner),
+        winning_bid - house_fee - project_fee
+    );
+    // only alice's losing bid is left in escrow
+    assert_eq!(fixture.bid_balance(&fixture.auction), 200);
+    assert_eq!(state.outstanding_bids, 1);
+
+    assert_auction_error(
+        fixture.withdraw_winning_bid(),
+        AuctionError::AlreadyWithdrewBid,
+    );
+    fixture.reclaim(&alice).unwrap();
+    assert_eq!(fixture.bid_balance(&fixture.auction), 0);
+}
+
+#[test]
+fn settle_pays_item_to_winner_only() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.bid(&bob, 300).unwrap();
+    fixture.end();
+
+    // alice settles bob's win into her own item account
+    let mint_authority = fixture.mint_authority;
+    fixture
+        .bank
+        .mint_to(&fixture.mint, &mint_authority, &alice, 0);
+    let winner_ata = pda::token_account(&bob, &fixture.mint, &spl_token::ID);
+    let alice_ata = pda::token_account(&alice, &fixture.mint, &spl_token::ID);
+    let mut instruction = fixture.settle_instruction(&alice);
+    for meta in instruction.accounts.iter_mut() {
+        if meta.pubkey == winner_ata {
+            meta.pubkey = alice_ata;
+        }
+    }
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[alice]),
+        AuctionError::InvalidTokenAccount,
+    );
+    assert_eq!(fixture.item_balance(&alice), 0);
+
+    fixture.settle(&alice).unwrap();
+    assert_eq!(fixture.item_balance(&bob), 1);
+}
+
+#[test]
+fn cancel() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let alice = fixture.bidders[0];
+    fixture.bid(&alice, 200).unwrap();
+
+    fixture.cancel().unwrap();
+    assert!(fixture.state().cancelled);
+    assert_auction_error(fixture.bid(&alice, 200), AuctionError::AuctionCancelled);
+
+    // the leader of a cancelled auction can reclaim
+    fixture.reclaim(&alice).unwrap();
+    assert_eq!(fixture.bid_balance(&alice), 1_000_000);
+
+    fixture.end();
+    assert_auction_error(fixture.withdraw_item(), AuctionError::AuctionCancelled);
+}
+
+#[test]
+fn cancel_after_close() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+
+    let end_time = fixture.state().end_time;
+    fixture.bank.warp_to(end_time);
+    assert_auction_error(fixture.cancel(), AuctionError::CannotCancelAfterClose);
+    assert!(!fixture.state().cancelled);
+}
+
+#[test]
+fn failed_transaction_rolls_back() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let alice = fixture.bidders[0];
+
+    let before = fixture.bank.accounts.clone();
+    let state = fixture.state();
+    let bid = |amount| {
+        ix::make_open_bid(
+            &fixture.auction,
+            &state,
+            &alice,
+            &spl_token::ID,
+            amount,
+            vec![],
+            Pubkey::default(),
+            [0; 32],
+            None,
+        )
+    };
+    // the first bid succeeds on its own, the leader then raises by nothing
+    let result = fixture.bank.process(&[bid(200), bid(0)], &[alice]);
+    assert_auction_error(result, AuctionError::InsufficientBid);
+    assert_eq!(fixture.bank.accounts, before);
+}
+
+#[test]
+fn buy_now_pays_fees_and_royalties() {
+    const BUY_NOW_PRICE: u64 = 1_000;
+    const ROYALTY_BPS: u16 = 500;
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        buy_now_price: BUY_NOW_PRICE,
+        ..Fixture::default_params()
+    });
+    let creator = Pubkey::new_unique();
+    let (mint, token_mint, mint_authority) =
+        (fixture.mint, fixture.token_mint, fixture.mint_authority);
+    fixture
+        .bank
+        .set_metadata(&mint, ROYALTY_BPS, &[creator], None);
+    fixture
+        .bank
+        .mint_to(&token_mint, &mint_authority, &creator, 0);
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.bid(&alice, 200).unwrap();
+
+    fixture.buy_now(&bob, vec![], &[creator]).unwrap();
+
+    let house_fee = BUY_NOW_PRICE * HOUSE_FEE_BPS as u64 / 10_000;
+    let project_fee = BUY_NOW_PRICE * PROJECT_FEE_BPS as u64 / 10_000;
+    let royalty = BUY_NOW_PRICE * ROYALTY_BPS as u64 / 10_000;
+    assert_eq!(fixture.item_balance(&bob), 1);
+    assert_eq!(fixture.bid_balance(&bob), 1_000_000 - BUY_NOW_PRICE);
+    assert_eq!(fixture.bid_balance(&fixture.house_treasury), house_fee);
+    assert_eq!(fixture.bid_balance(&fixture.project_treasury), project_fee);
+    assert_eq!(fixture.bid_balance(&creator), royalty);
+    assert_eq!(
+        fixture.bid_balance(&fixture.owner),
+        BUY_NOW_PRICE - house_fee - project_fee - royalty
+    );
+    // only alice's bid is left in escrow, and she can take it back
+    assert_eq!(fixture.bid_balance(&fixture.auction), 200);
+    assert!(fixture.state().settled);
+    fixture.reclaim(&alice).unwrap();
+}
+
+#[test]
+fn buy_now_requires_allowlist() {
+    let (alice, bob, carol) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let (alice_leaf, bob_leaf) = (compute_allowlist_leaf(&alice), compute_allowlist_leaf(&bob));
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        buy_now_price: 1_000,
+        allowlist_root: keccak_pair(alice_leaf, bob_leaf),
+        ..Fixture::default_params()
+    });
+    let (mint, token_mint, mint_authority) =
+        (fixture.mint, fixture.token_mint, fixture.mint_authority);
+    fixture.bank.set_metadata(&mint, 0, &[], None);
+    for buyer in [alice, carol] {
+        fixture.bank.airdrop(&buyer, 10 * SOL);
+        fixture
+            .bank
+            .mint_to(&token_mint, &mint_authority, &buyer, 1_000_000);
+    }
+    fixture.start();
+
+    assert_auction_error(
+        fixture.buy_now(&carol, vec![alice_leaf], &[]),
+        AuctionError::NotAllowlisted,
+    );
+    fixture.buy_now(&alice, vec![bob_leaf], &[]).unwrap();
+    assert_eq!(fixture.item_balance(&alice), 1);
+}
+
+fn keccak_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    solana_program::keccak::hashv(&[&first, &second]).0
+}
+
+#[test]
+fn update_allowlist_before_start() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    let alice = fixture.bidders[0];
+    let root = compute_allowlist_leaf(&alice);
+    let state = fixture.state();
+    let update = ix::update_allowlist_open(&fixture.auction, &state, &fixture.owner, root);
+    fixture.bank.process(&[update], &[fixture.owner]).unwrap();
+
+    let events: Vec<AllowlistUpdated> = fixture.bank.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].auction, fixture.auction);
+    assert_eq!(events[0].allowlist_root, root);
+    assert_eq!(fixture.state().allowlist_root, root);
+
+    fixture.start();
+    let update = ix::update_allowlist_open(&fixture.auction, &state, &fixture.owner, [0; 32]);
+    assert_auction_error(
+        fixture.bank.process(&[update], &[fixture.owner]),
+        AuctionError::AuctionAlreadyStarted,
+    );
+    assert!(fixture.bank.events::<AllowlistUpdated>().is_empty());
+}
+
+#[test]
+fn refund_crank_pays_funded_reward() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob, cranker) = (fixture.bidders[0], fixture.bidders[1], fixture.bidders[2]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+    fixture.end();
+    fixture.settle(&cranker).unwrap();
+
+    // one reward is funded, and both losing and winning records are passed
+    let funding = ix::fund_crank_rewards(&cranker, PROJECT_ID, CRANK_REWARD);
+    fixture.bank.process(&[funding], &[cranker]).unwrap();
+    let before = fixture.bank.lamports(&cranker);
+    let state = fixture.state();
+    let instruction = ix::refund_open_bids(
+        &fixture.auction,
+        &state,
+        &cranker,
+        &spl_token::ID,
+        &[alice, bob],
+    );
+    fixture.bank.process(&[instruction], &[cranker]).unwrap();
+
+    assert_eq!(fixture.bid_balance(&alice), 1_000_000);
+    assert_eq!(fixture.bid_balance(&fixture.auction), 0);
+    assert_eq!(fixture.state().outstanding_bids, 0);
+    assert_eq!(fixture.bank.lamports(&cranker), before + CRANK_REWARD);
+}
+
+#[test]
+fn refund_batch_skips_unsettled_winner() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob, cranker) = (fixture.bidders[0], fixture.bidders[1], fixture.bidders[2]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+    fixture.end();
+
+    // bob's winning bid stays escrowed until the auction is settled
+    let state = fixture.state();
+    let instruction = ix::refund_open_bids(
+        &fixture.auction,
+        &state,
+        &cranker,
+        &spl_token::ID,
+        &[alice, bob],
+    );
+    fixture.bank.process(&[instruction], &[cranker]).unwrap();
+
+    assert_eq!(fixture.bid_balance(&alice), 1_000_000);
+    assert_eq!(fixture.bid_balance(&fixture.auction), 300);
+    assert_eq!(fixture.state().outstanding_bids, 1);
+    let record = fixture.bid_record(&bob);
+    assert!(!record.claimed);
+    assert_eq!(record.amount, 300);
+    fixture.settle(&cranker).unwrap();
+}
+
+#[test]
+fn recreated_auction_starts_without_bidders() {
+    let params = CreateOpenAuctionParams {
+        bidder_cap: 1,
+        ..Fixture::default_params()
+    };
+    let mut fixture = Fixture::new(params.clone());
+    fixture.start();
+    let (alice, bob, referrer) = (fixture.bidders[0], fixture.bidders[1], fixture.bidders[2]);
+    fixture.referred_bid(&alice, 200, &referrer).unwrap();
+    fixture.cancel().unwrap();
+    fixture.reclaim(&alice).unwrap();
+    let state = fixture.state();
+    let instruction = ix::reclaim_item_open(&fixture.auction, &state, &fixture.token_program);
+    fixture
+        .bank
+        .process(&[instruction], &[fixture.owner])
+        .unwrap();
+
+    // the auction only closes once alice's record is
+    let close = ix::close_open_auction(
+        &fixture.auction,
+        &state,
+        &fixture.token_program,
+        &fixture.bid_token_program,
+    );
+    assert_auction_error(
+        fixture
+            .bank
+            .process(std::slice::from_ref(&close), &[fixture.owner]),
+        AuctionError::AuctionNotEmpty,
+    );
+    let bid_record = pda::bid_record(&fixture.auction, &alice).0;
+    let rent = fixture.bank.lamports(&bid_record);
+    let before = fixture.bank.lamports(&alice);
+    let instruction = ix::close_open_bid(&fixture.auction, &alice);
+    fixture.bank.process(&[instruction], &[bob]).unwrap();
+    assert!(fixture.bank.account(&bid_record).is_none());
+    assert_eq!(fixture.bank.lamports(&alice), before + rent);
+    fixture.bank.process(&[close], &[fixture.owner]).unwrap();
+
+    // recreated at the same address, alice is a new bidder again
+    let params = CreateOpenAuctionParams {
+        floor: FLOOR,
+        increment: INCREMENT,
+        start_time: fixture.bank.now() + 10,
+        end_time: fixture.bank.now() + 1000,
+        token_amount: 1,
+        project_id: PROJECT_ID,
+        ..params
+    };
+    let instruction = ix::create_open_auction(
+        &fixture.owner,
+        &fixture.mint,
+        &fixture.token_mint,
+        &fixture.token_program,
+        params,
+    );
+    fixture
+        .bank
+        .process(&[instruction], &[fixture.owner])
+        .unwrap();
+    fixture.start();
+    fixture.bid(&alice, 200).unwrap();
+    assert_eq!(fixture.state().bidder_count, 1);
+    assert_eq!(fixture.bid_record(&alice).referrer, Pubkey::default());
+    assert_auction_error(fixture.bid(&bob, 300), AuctionError::BidderCapReached);
+}
+
+#[test]
+fn bid_record_closes_once_reclaimed() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+    fixture.reclaim(&alice).unwrap();
+
+    let close = |bidder: &Pubkey| ix::close_open_bid(&fixture.auction, bidder);
+    let (close_alice, close_bob) = (close(&alice), close(&bob));
+    assert_auction_error(
+        fixture
+            .bank
+            .process(std::slice::from_ref(&close_alice), &[alice]),
+        AuctionError::AuctionNotOver,
+    );
+    fixture.end();
+    assert_auction_error(
+        fixture.bank.process(&[close_bob], &[bob]),
+        AuctionError::BidNotReclaimed,
+    );
+    fixture.bank.process(&[close_alice], &[alice]).unwrap();
+    assert_eq!(fixture.state().bid_records, 1);
+}
+
+#[test]
+fn reveal_reserve() {
+    const RESERVE: u64 = 500;
+    const NONCE: u64 = 7;
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        reserve_hash: compute_reserve_hash(RESERVE, NONCE),
+        reserve_reveal_window: 100,
+        ..Fixture::default_params()
+    });
+    fixture.start();
+    let alice = fixture.bidders[0];
+    fixture.bid(&alice, 300).unwrap();
+    fixture.end();
+
+    assert_auction_error(
+        fixture.reveal_reserve(RESERVE, NONCE + 1),
+        AuctionError::HashMismatch,
+    );
+    fixture.reveal_reserve(RESERVE, NONCE).unwrap();
+    // alice's maximum is below the reserve, so nobody wins
+    let events: Vec<ReserveRevealed> = fixture.bank.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].auction, fixture.auction);
+    assert_eq!(events[0].reserve, RESERVE);
+    assert!(!events[0].met);
+    let state = fixture.state();
+    assert!(state.reserve_revealed);
+    assert_eq!(state.highest_bidder, Pubkey::default());
+    assert_auction_error(
+        fixture.reveal_reserve(RESERVE, NONCE),
+        AuctionError::NoReserveToReveal,
+    );
+    fixture.reclaim(&alice).unwrap();
+}
+
+#[test]
+fn reveal_reserve_met() {
+    const RESERVE: u64 = 500;
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        reserve_hash: compute_reserve_hash(RESERVE, 7),
+        reserve_reveal_window: 100,
+        ..Fixture::default_params()
+    });
+    fixture.start();
+    let alice = fixture.bidders[0];
+    fixture.bid(&alice, 800).unwrap();
+    fixture.end();
+
+    fixture.reveal_reserve(RESERVE, 7).unwrap();
+    let events: Vec<ReserveRevealed> = fixture.bank.events();
+    assert_eq!(events.len(), 1);
+    assert!(events[0].met);
+    // the standing bid is raised to the reserve
+    let state = fixture.state();
+    assert_eq!(state.highest_bidder, alice);
+    assert_eq!(state.highest_bid, RESERVE);
+}
+
+#[test]
+fn hidden_maximums_revealed_after_close() {
+    const REVEAL_WINDOW: u64 = 100;
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        max_bid_reveal_window: REVEAL_WINDOW,
+        ..Fixture::default_params()
+    });
+    fixture.start();
+    let (alice, bob, cranker) = (fixture.bidders[0], fixture.bidders[1], fixture.bidders[2]);
+    // both escrow more than their maximum, so escrow does not give it away
+    fixture.hidden_bid(&alice, 900, 600).unwrap();
+    fixture.hidden_bid(&bob, 700, 500).unwrap();
+
+    let state = fixture.state();
+    assert_eq!(state.highest_bidder, Pubkey::default());
+    assert_eq!(state.highest_max_bid, 0);
+    assert_eq!(fixture.bid_record(&bob).amount, 700);
+    assert_auction_error(fixture.reclaim(&bob), AuctionError::RevealPeriodNotOver);
+    assert_auction_error(
+        fixture.reveal_bid(&bob, 500, MAX_BID_NONCE),
+        AuctionError::AuctionNotOver,
+    );
+
+    fixture.end();
+    assert_auction_error(
+        fixture.reveal_bid(&bob, 500, MAX_BID_NONCE + 1),
+        AuctionError::HashMismatch,
+    );
+    assert_auction_error(
+        fixture.reveal_bid(&bob, 800, MAX_BID_NONCE),
+        AuctionError::MaxBidOverEscrow,
+    );
+    fixture.reveal_bid(&bob, 500, MAX_BID_NONCE).unwrap();
+    fixture.reveal_bid(&alice, 600, MAX_BID_NONCE).unwrap();
+    let events: Vec<MaxBidRevealed> = fixture.bank.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].max_bid, 600);
+    assert_eq!(events[0].highest_bidder, alice);
+    assert_auction_error(
+        fixture.reveal_bid(&alice, 600, MAX_BID_NONCE),
+        AuctionError::NoMaxBidToReveal,
+    );
+
+    // alice wins one increment over bob's maximum, once the window closes
+    let state = fixture.state();
+    assert_eq!(state.highest_bidder, alice);
+    assert_eq!(state.highest_bid, 500 + INCREMENT);
+    assert_eq!(state.highest_max_bid, 600);
+    assert_auction_error(fixture.settle(&cranker), AuctionError::RevealPeriodNotOver);
+    fixture.bank.warp_by(REVEAL_WINDOW);
+    fixture.settle(&cranker).unwrap();
+    assert_eq!(fixture.item_balance(&alice), 1);
+    assert_eq!(fixture.bid_balance(&alice), 1_000_000 - (500 + INCREMENT));
+    fixture.reclaim(&bob).unwrap();
+    assert_eq!(fixture.bid_balance(&fixture.auction), 0);
+}
+
+#[test]
+fn hidden_maximums_need_commitments() {
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        max_bid_reveal_window: 100,
+        ..Fixture::default_params()
+    });
+    fixture.start();
+    let alice = fixture.bidders[0];
+    assert_auction_error(fixture.bid(&alice, 300), AuctionError::InvalidMaxBidHash);
+
+    // public maximums take no commitment
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let alice = fixture.bidders[0];
+    assert_auction_error(
+        fixture.hidden_bid(&alice, 300, 200),
+        AuctionError::InvalidMaxBidHash,
+    );
+
+    // and a hidden reserve could not be checked against unrevealed maximums
+    let params = CreateOpenAuctionParams {
+        title: "hidden reserve".to_string(),
+        floor: FLOOR,
+        increment: INCREMENT,
+        start_time: fixture.bank.now() + 10,
+        end_time: fixture.bank.now() + 1000,
+        token_amount: 1,
+        project_id: PROJECT_ID,
+        reserve_hash: compute_reserve_hash(500, 7),
+        reserve_reveal_window: 100,
+        max_bid_reveal_window: 100,
+        ..CreateOpenAuctionParams::default()
+    };
+    fixture
+        .bank
+        .mint_to(&fixture.mint, &fixture.mint_authority, &fixture.owner, 1);
+    let instruction = ix::create_open_auction(
+        &fixture.owner,
+        &fixture.mint,
+        &fixture.token_mint,
+        &fixture.token_program,
+        params,
+    );
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[fixture.owner]),
+        AuctionError::HiddenMaxBidConflict,
+    );
+}
+
+#[test]
+fn unrevealed_bid_cannot_win() {
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        max_bid_reveal_window: 100,
+        ..Fixture::default_params()
+    });
+    fixture.start();
+    let alice = fixture.bidders[0];
+    fixture.hidden_bid(&alice, 300, 200).unwrap();
+    fixture.end();
+
+    // the owner only gets the item back once the window closes unrevealed
+    let state = fixture.state();
+    let reclaim_item = ix::reclaim_item_open(&fixture.auction, &state, &fixture.token_program);
+    assert_auction_error(
+        fixture
+            .bank
+            .process(std::slice::from_ref(&reclaim_item), &[fixture.owner]),
+        AuctionError::AuctionNotOver,
+    );
+    fixture.bank.warp_by(100);
+    assert_auction_error(
+        fixture.reveal_bid(&alice, 200, MAX_BID_NONCE),
+        AuctionError::RevealPeriodOver,
+    );
+    fixture
+        .bank
+        .process(&[reclaim_item], &[fixture.owner])
+        .unwrap();
+    fixture.reclaim(&alice).unwrap();
+    assert_eq!(fixture.bid_balance(&fixture.auction), 0);
+}
+
+#[test]
+fn reveal_without_reserve() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.end();
+    assert_auction_error(
+        fixture.reveal_reserve(0, 0),
+        AuctionError::NoReserveToReveal,
+    );
+}
+
+#[test]
+fn royalties_capped_at_what_fees_leave() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    let creator = Pubkey::new_unique();
+    let (mint, token_mint, mint_authority) =
+        (fixture.mint, fixture.token_mint, fixture.mint_authority);
+    // a full royalty would come on top of the house and project fees
+    fixture.bank.set_metadata(&mint, 10_000, &[creator], None);
+    fixture
+        .bank
+        .mint_to(&token_mint, &mint_authority, &creator, 0);
+    fixture.start();
+    let alice = fixture.bidders[0];
+    fixture.bid(&alice, 200).unwrap();
+    fixture.end();
+
+    let state = fixture.state();
+    let instruction = ix::settle_open_auction(
+        &fixture.auction,
+        &state,
+        &alice,
+        &fixture.house_treasury,
+        &fixture.project_treasury,
+        &Pubkey::default(),
+        &[creator],
+        &spl_token::ID,
+        &spl_token::ID,
+    );
+    fixture.bank.process(&[instruction], &[alice]).unwrap();
+
+    let winning_bid = fixture.state().highest_bid;
+    let house_fee = winning_bid * HOUSE_FEE_BPS as u64 / 10_000;
+    let project_fee = winning_bid * PROJECT_FEE_BPS as u64 / 10_000;
+    assert_eq!(
+        fixture.bid_balance(&creator),
+        winning_bid - house_fee - project_fee
+    );
+    assert_eq!(fixture.bid_balance(&fixture.owner), 0);
+    assert_eq!(fixture.item_balance(&alice), 1);
+}
+
+#[test]
+fn native_sol_settle_and_reclaim() {
+    let mut fixture = Fixture::with_bid_token(Fixture::default_params(), BidToken::Native);
+    fixture.start();
+    let (alice, bob, cranker) = (fixture.bidders[0], fixture.bidders[1], fixture.bidders[2]);
+    let escrow = fixture.bid_balance(&fixture.auction);
+    let bob_before = fixture.bid_balance(&bob);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+    assert_eq!(fixture.bid_balance(&fixture.auction), escrow + 500);
+
+    fixture.end();
+    let owner_before = fixture.bid_balance(&fixture.owner);
+    fixture.settle(&cranker).unwrap();
+
+    // the payouts and bob's excess are lamports moved out of the auction PDA
+    let winning_bid = 200 + INCREMENT;
+    let house_fee = winning_bid * HOUSE_FEE_BPS as u64 / 10_000;
+    let project_fee = winning_bid * PROJECT_FEE_BPS as u64 / 10_000;
+    let record_rent = fixture
+        .bank
+        .lamports(&pda::bid_record(&fixture.auction, &bob).0);
+    assert_eq!(fixture.item_balance(&bob), 1);
+    assert_eq!(
+        fixture.bid_balance(&bob),
+        bob_before - winning_bid - record_rent
+    );
+    assert_eq!(
+        fixture.bid_balance(&fixture.owner),
+        owner_before + winning_bid - house_fee - project_fee
+    );
+    assert_eq!(fixture.bid_balance(&fixture.house_treasury), house_fee);
+    assert_eq!(fixture.bid_balance(&fixture.project_treasury), project_fee);
+    assert_eq!(fixture.bid_balance(&fixture.auction), escrow + 200);
+
+    // the reclaim fee is charged on top of the refund
+    let alice_before = fixture.bid_balance(&alice);
+    fixture.reclaim(&alice).unwrap();
+    assert_eq!(
+        fixture.bid_balance(&alice),
+        alice_before + 200 - RECLAIM_FEE
+    );
+    assert_eq!(
+        fixture.bid_balance(&fixture.project_treasury),
+        project_fee + RECLAIM_FEE
+    );
+    assert_eq!(fixture.bid_balance(&fixture.auction), escrow);
+}
+
+#[test]
+fn token_2022_bids_are_net_of_transfer_fee() {
+    let transfer_fee_bps: u64 = 100;
+    let net = |amount: u64| amount - (amount * transfer_fee_bps).div_ceil(10_000);
+    let mut fixture = Fixture::with_bid_token(
+        Fixture::default_params(),
+        BidToken::Token2022 {
+            transfer_fee_bps: transfer_fee_bps as u16,
+        },
+    );
+    assert_eq!(fixture.item_balance(&fixture.auction), 1);
+    fixture.start();
+    let (alice, bob, cranker) = (fixture.bidders[0], fixture.bidders[1], fixture.bidders[2]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+
+    // the escrow holds what arrived, not what was sent
+    assert_eq!(fixture.bid_record(&alice).amount, net(200));
+    assert_eq!(fixture.bid_record(&bob).amount, net(300));
+    assert_eq!(fixture.bid_balance(&fixture.auction), net(200) + net(300));
+
+    fixture.end();
+    fixture.settle(&cranker).unwrap();
+
+    let winning_bid = net(200) + INCREMENT;
+    let house_fee = winning_bid * HOUSE_FEE_BPS as u64 / 10_000;
+    let project_fee = winning_bid * PROJECT_FEE_BPS as u64 / 10_000;
+    assert_eq!(fixture.state().highest_bid, winning_bid);
+    assert_eq!(fixture.item_balance(&bob), 1);
+    assert_eq!(
+        fixture.bid_balance(&bob),
+        1_000_000 - 300 + net(net(300) - winning_bid)
+    );
+    assert_eq!(
+        fixture.bid_balance(&fixture.owner),
+        net(winning_bid - house_fee - project_fee)
+    );
+
+    fixture.reclaim(&alice).unwrap();
+    assert_eq!(fixture.bid_balance(&alice), 1_000_000 - 200 + net(net(200)));
+    assert_eq!(fixture.bid_balance(&fixture.auction), 0);
+}
+
+#[test]
+fn referrers_share_house_fee() {
+    let bid_referrer = Pubkey::new_unique();
+    let creation_referrer = Pubkey::new_unique();
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        referrer: creation_referrer,
+        ..Fixture::default_params()
+    });
+    let (token_mint, mint_authority) = (fixture.token_mint, fixture.mint_authority);
+    for referrer in [bid_referrer, creation_referrer] {
+        fixture.bank.airdrop(&referrer, 10 * SOL);
+        let instruction = ix::initialize_referral(&referrer, &token_mint, &spl_token::ID);
+        fixture.bank.process(&[instruction], &[referrer]).unwrap();
+        fixture
+            .bank
+            .mint_to(&token_mint, &mint_authority, &referrer, 0);
+    }
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.bid(&alice, 100_000).unwrap();
+    fixture.referred_bid(&bob, 300_000, &bid_referrer).unwrap();
+    fixture.end();
+
+    let state = fixture.state();
+    let instruction = ix::settle_open_auction(
+        &fixture.auction,
+        &state,
+        &alice,
+        &fixture.house_treasury,
+        &fixture.project_treasury,
+        &bid_referrer,
+        &[],
+        &spl_token::ID,
+        &spl_token::ID,
+    );
+    fixture.bank.process(&[instruction], &[alice]).unwrap();
+
+    // both referral fees come out of the house fee
+    let winning_bid = 100_000 + INCREMENT;
+    let house_fee = winning_bid * HOUSE_FEE_BPS as u64 / 10_000;
+    let project_fee = winning_bid * PROJECT_FEE_BPS as u64 / 10_000;
+    let bid_referral_fee = house_fee * BID_REFERRAL_BPS as u64 / 10_000;
+    let creation_referral_fee = house_fee * CREATION_REFERRAL_BPS as u64 / 10_000;
+    assert_eq!(
+        fixture.bid_balance(&fixture.house_treasury),
+        house_fee - bid_referral_fee - creation_referral_fee
+    );
+    assert_eq!(
+        fixture.bid_balance(&fixture.owner),
+        winning_bid - house_fee - project_fee
+    );
+
+    for (referrer, fee) in [
+        (bid_referrer, bid_referral_fee),
+        (creation_referrer, creation_referral_fee),
+    ] {
+        let referral = pda::referral(&referrer, &token_mint).0;
+        let record: ReferralRecord = fixture.bank.get(&referral);
+        assert_eq!(record.total_earned, fee);
+        assert_eq!(record.referral_count, 1);
+        assert_eq!(fixture.bid_balance(&referral), fee);
+
+        let claim = ix::claim_referral_rewards(&referrer, &token_mint, &spl_token::ID);
+        fixture.bank.process(&[claim], &[referrer]).unwrap();
+        assert_eq!(fixture.bid_balance(&referrer), fee);
+        assert_eq!(fixture.bid_balance(&referral), 0);
+    }
+}
+
+#[test]
+fn leader_retracts() {
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        retract_cutoff: 100,
+        leader_can_retract: true,
+        ..Fixture::default_params()
+    });
+    fixture.start();
+    let (alice, bob, carol) = (fixture.bidders[0], fixture.bidders[1], fixture.bidders[2]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+    fixture.bid(&carol, 250).unwrap();
+    assert_eq!(fixture.state().highest_bidder, bob);
+    assert_eq!(fixture.state().highest_bid, 250 + INCREMENT);
+
+    // every other outstanding bid is needed to find the new leader
+    let retract = |fixture: &Fixture, bidder: &Pubkey, others: &[Pubkey]| {
+        ix::retract_open_bid(
+            &fixture.auction,
+            &fixture.state(),
+            bidder,
+            &spl_token::ID,
+            others,
+        )
+    };
+    let instruction = retract(&fixture, &bob, &[alice]);
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[bob]),
+        AuctionError::InvalidBidRecord,
+    );
+    let instruction = retract(&fixture, &bob, &[alice, carol]);
+    fixture.bank.process(&[instruction], &[bob]).unwrap();
+
+    // carol leads at one increment over alice, up to her maximum
+    let state = fixture.state();
+    assert_eq!(fixture.bid_balance(&bob), 1_000_000);
+    assert_eq!(state.highest_bidder, carol);
+    assert_eq!(state.highest_bid, 200 + INCREMENT);
+    assert_eq!(state.highest_max_bid, 250);
+    assert_eq!(state.outstanding_bids, 2);
+    assert_eq!(fixture.bid_balance(&fixture.auction), 450);
+
+    // retraction closes retract_cutoff seconds before the end
+    let end_time = fixture.state().end_time;
+    fixture.bank.warp_to(end_time - 100);
+    let instruction = retract(&fixture, &alice, &[]);
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[alice]),
+        AuctionError::RetractionClosed,
+    );
+}
+
+#[test]
+fn leader_cannot_retract_with_own_record() {
+    let mut fixture = Fixture::new(CreateOpenAuctionParams {
+        retract_cutoff: 100,
+        leader_can_retract: true,
+        ..Fixture::default_params()
+    });
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+
+    // bob's stale record stands in for alice's
+    let state = fixture.state();
+    let mut instruction =
+        ix::retract_open_bid(&fixture.auction, &state, &bob, &spl_token::ID, &[alice]);
+    let last = instruction.accounts.len() - 1;
+    instruction.accounts[last].pubkey = pda::bid_record(&fixture.auction, &bob).0;
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[bob]),
+        AuctionError::InvalidBidRecord,
+    );
+    assert_eq!(fixture.state().highest_bidder, bob);
+    assert_eq!(fixture.bid_balance(&bob), 1_000_000 - 300);
+
+    // alice's record alongside it is still rejected
+    let mut instruction =
+        ix::retract_open_bid(&fixture.auction, &state, &bob, &spl_token::ID, &[alice]);
+    instruction.accounts.push(AccountMeta::new_readonly(
+        pda::bid_record(&fixture.auction, &bob).0,
+        false,
+    ));
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[bob]),
+        AuctionError::InvalidBidRecord,
+    );
+
+    let instruction =
+        ix::retract_open_bid(&fixture.auction, &state, &bob, &spl_token::ID, &[alice]);
+    fixture.bank.process(&[instruction], &[bob]).unwrap();
+    assert_eq!(fixture.state().highest_bidder, alice);
+    assert_eq!(fixture.state().highest_bid, FLOOR + 1);
+}
+
+#[test]
+fn withdraw_without_bids_fails() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.end();
+
+    // with no bids there is no winning bid record to load
+    assert_eq!(
+        fixture.withdraw_winning_bid(),
+        Err(ErrorCode::AccountNotInitialized.into())
+    );
+    assert!(!fixture.state().winning_bid_paid);
+
+    let state = fixture.state();
+    let instruction = ix::reclaim_item_open(&fixture.auction, &state, &spl_token::ID);
+    fixture
+        .bank
+        .process(&[instruction], &[fixture.owner])
+        .unwrap();
+    assert_eq!(fixture.item_balance(&fixture.owner), 1);
+}
+
+#[test]
+fn withdraw_needs_winning_bid_record() {
+    let mut fixture = Fixture::new(Fixture::default_params());
+    fixture.start();
+    let (alice, bob) = (fixture.bidders[0], fixture.bidders[1]);
+    fixture.bid(&alice, 200).unwrap();
+    fixture.bid(&bob, 300).unwrap();
+    fixture.end();
+
+    // alice's record cannot stand in for bob's winning one
+    let state = fixture.state();
+    let mut instruction = ix::withdraw_winning_bid_open(
+        &fixture.auction,
+        &state,
+        &fixture.house_treasury,
+        &fixture.project_treasury,
+        &Pubkey::default(),
+        &[],
+        &spl_token::ID,
+    );
+    instruction.accounts[1].pubkey = pda::bid_record(&fixture.auction, &alice).0;
+    assert_eq!(
+        fixture.bank.process(&[instruction], &[fixture.owner]),
+        Err(ErrorCode::ConstraintSeeds.into())
+    );
+
+    fixture.withdraw_winning_bid().unwrap();
+    assert_eq!(fixture.bid_record(&bob).amount, 300 - 200 - INCREMENT);
+}
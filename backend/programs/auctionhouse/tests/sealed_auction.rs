@@ -0,0 +1,212 @@
+mod common;
+
+use auctionhouse::account::SealedAuction;
+use auctionhouse::error::AuctionError;
+use auctionhouse::utils::compute_sealed_bid_hash;
+use auctionhouse_client::instruction::{self as ix, CreateSealedAuctionParams};
+use auctionhouse_client::pda;
+use common::{assert_auction_error, Bank, SOL};
+use solana_program::pubkey::Pubkey;
+
+const FLOOR: u64 = 100;
+const BID: u64 = SOL;
+const NONCE: u64 = 7;
+
+struct Fixture {
+    bank: Bank,
+    owner: Pubkey,
+    mint_authority: Pubkey,
+    mint: Pubkey,
+    bidder: Pubkey,
+    auction: Pubkey,
+    token_program: Pubkey,
+}
+
+impl Fixture {
+    /// A house config, an NFT of token_program and a second-price sealed
+    /// auction of it running from now + 10 to now + 1000, revealed until
+    /// now + 2000.
+    fn new(token_program: Pubkey) -> Self {
+        let mut bank = Bank::new();
+        let authority = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let bidder = Pubkey::new_unique();
+        let mint_authority = Pubkey::new_unique();
+        for wallet in [authority, owner, bidder] {
+            bank.airdrop(&wallet, 10 * SOL);
+        }
+        bank.set_upgrade_authority(&authority);
+
+        let mint = bank.create_mint_with(&mint_authority, 0, &token_program, 0);
+        bank.mint_to(&mint, &mint_authority, &owner, 1);
+
+        bank.process(
+            &[ix::initialize_house_config(
+                &authority,
+                vec![owner],
+                Pubkey::new_unique(),
+                0,
+                0,
+                0,
+            )],
+            &[authority],
+        )
+        .unwrap();
+
+        let params = CreateSealedAuctionParams {
+            title: "sealed auction".to_string(),
+            floor: FLOOR,
+            first_price: false,
+            start_time: bank.now() + 10,
+            end_time: bank.now() + 1000,
+            reveal_period: bank.now() + 2000,
+            bidder_cap: 10,
+            token_amount: 1,
+        };
+        let auction = pda::sealed_auction(&owner, &params.title).0;
+        bank.process(
+            &[ix::create_sealed_auction(
+                &owner,
+                &mint,
+                &token_program,
+                params,
+            )],
+            &[owner],
+        )
+        .unwrap();
+
+        Fixture {
+            bank,
+            owner,
+            mint_authority,
+            mint,
+            bidder,
+            auction,
+            token_program,
+        }
+    }
+
+    fn state(&self) -> SealedAuction {
+        self.bank.get(&self.auction)
+    }
+
+    fn item_balance(&self, wallet: &Pubkey) -> u64 {
+        self.bank
+            .token_balance(&pda::token_account(wallet, &self.mint, &self.token_program))
+    }
+
+    /// Seals BID with twice as much escrowed and reveals it after the end.
+    fn bid_and_reveal(&mut self) {
+        let start_time = self.state().start_time;
+        self.bank.warp_to(start_time + 1);
+        let bid = ix::make_sealed_bid(
+            &self.auction,
+            &self.bidder,
+            compute_sealed_bid_hash(BID, NONCE),
+            2 * BID,
+        );
+        self.bank.process(&[bid], &[self.bidder]).unwrap();
+
+        let end_time = self.state().end_time;
+        self.bank.warp_to(end_time + 1);
+        let reveal = ix::reveal_sealed_bid(&self.auction, &self.bidder, BID, NONCE);
+        self.bank.process(&[reveal], &[self.bidder]).unwrap();
+
+        let reveal_period = self.state().reveal_period;
+        self.bank.warp_to(reveal_period + 1);
+    }
+}
+
+#[test]
+fn token_2022_item_goes_to_winner() {
+    let mut fixture = Fixture::new(spl_token_2022::ID);
+    assert_eq!(fixture.item_balance(&fixture.auction), 1);
+    fixture.bid_and_reveal();
+
+    let owner_lamports = fixture.bank.lamports(&fixture.owner);
+    let state = fixture.state();
+    let instructions = [
+        ix::withdraw_item_sealed(&fixture.auction, &state, &fixture.token_program),
+        ix::withdraw_winning_bid_sealed(&fixture.auction, &state),
+    ];
+    fixture
+        .bank
+        .process(&instructions, &[fixture.bidder, fixture.owner])
+        .unwrap();
+
+    // the only revealed bid pays the floor
+    assert_eq!(fixture.item_balance(&fixture.bidder), 1);
+    assert_eq!(fixture.item_balance(&fixture.auction), 0);
+    assert_eq!(
+        fixture.bank.lamports(&fixture.owner),
+        owner_lamports + FLOOR
+    );
+}
+
+#[test]
+fn item_only_reclaimed_to_owner() {
+    let mut fixture = Fixture::new(spl_token_2022::ID);
+    let reveal_period = fixture.state().reveal_period;
+    fixture.bank.warp_to(reveal_period + 1);
+
+    // the item cannot be routed into someone else's account
+    let (mint, mint_authority, bidder) = (fixture.mint, fixture.mint_authority, fixture.bidder);
+    fixture.bank.mint_to(&mint, &mint_authority, &bidder, 0);
+    let mut instruction =
+        ix::reclaim_item_sealed(&fixture.auction, &fixture.state(), &fixture.token_program);
+    let owner_ata = pda::token_account(&fixture.owner, &fixture.mint, &fixture.token_program);
+    let bidder_ata = pda::token_account(&fixture.bidder, &fixture.mint, &fixture.token_program);
+    for meta in instruction.accounts.iter_mut() {
+        if meta.pubkey == owner_ata {
+            meta.pubkey = bidder_ata;
+        }
+    }
+    assert_auction_error(
+        fixture.bank.process(&[instruction], &[fixture.owner]),
+        AuctionError::InvalidTokenAccount,
+    );
+
+    let instruction =
+        ix::reclaim_item_sealed(&fixture.auction, &fixture.state(), &fixture.token_program);
+    fixture
+        .bank
+        .process(&[instruction], &[fixture.owner])
+        .unwrap();
+    assert_eq!(fixture.item_balance(&fixture.owner), 1);
+    assert_auction_error(
+        fixture.bank.process(
+            &[ix::withdraw_winning_bid_sealed(
+                &fixture.auction,
+                &fixture.state(),
+            )],
+            &[fixture.owner],
+        ),
+        AuctionError::NoWinningBid,
+    );
+}
+
+#[test]
+fn commitment_opens_to_one_bid() {
+    let mut fixture = Fixture::new(spl_token::ID);
+    let start_time = fixture.state().start_time;
+    fixture.bank.warp_to(start_time + 1);
+    let bid = ix::make_sealed_bid(
+        &fixture.auction,
+        &fixture.bidder,
+        compute_sealed_bid_hash(123, 45),
+        BID,
+    );
+    fixture.bank.process(&[bid], &[fixture.bidder]).unwrap();
+    let end_time = fixture.state().end_time;
+    fixture.bank.warp_to(end_time + 1);
+
+    // the digits of bid and nonce cannot be split differently
+    let split = ix::reveal_sealed_bid(&fixture.auction, &fixture.bidder, 12, 345);
+    assert_auction_error(
+        fixture.bank.process(&[split], &[fixture.bidder]),
+        AuctionError::HashMismatch,
+    );
+    let reveal = ix::reveal_sealed_bid(&fixture.auction, &fixture.bidder, 123, 45);
+    fixture.bank.process(&[reveal], &[fixture.bidder]).unwrap();
+    assert_eq!(fixture.state().highest_bid, 123);
+}